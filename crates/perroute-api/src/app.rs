@@ -3,8 +3,14 @@ use crate::rest::{
     modules::{
         business_unit::service::BusinessUnitRestService,
         channel::service::ChannelRestService,
+        dead_letter::service::DeadLetterRestService,
+        message::service::MessageRestService,
         message_type::service::MessageTypeRestService,
-        route::service::RouteRestService, routes,
+        route::service::RouteRestService,
+        template::service::TemplateRestService,
+        partial::service::PartialRestService,
+        template_coverage::service::TemplateCoverageRestService,
+        template_preview::service::TemplatePreviewRestService, routes,
     },
 };
 use actix_web::{dev::Server, middleware::Logger, web::Data, App, HttpServer};
@@ -21,6 +27,12 @@ impl Application {
             + MessageTypeRestService
             + ChannelRestService
             + RouteRestService
+            + DeadLetterRestService
+            + MessageRestService
+            + TemplateRestService
+            + TemplatePreviewRestService
+            + PartialRestService
+            + TemplateCoverageRestService
             + Clone
             + Send
             + Sync
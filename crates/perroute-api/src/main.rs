@@ -1,9 +1,17 @@
 use perroute_api::{app::Application, rest::service::RestService};
 use perroute_command_bus::create_command_bus;
-use perroute_commons::configuration::settings::Settings;
+use perroute_commons::configuration::settings::{print_config_requested, Settings};
 use perroute_query_bus::create_query_bus;
 use perroute_storage::create_datasource;
-use std::{error::Error, net::TcpListener};
+use perroute_template::{
+    limits::RenderLimits,
+    render::{
+        engines::RenderEngines, handlebars::HandlebarsPlugin,
+        minijinja::MiniJinjaPlugin, TemplateEngine,
+    },
+    repository::{self, postgres::PgTemplateRepository},
+};
+use std::{error::Error, net::TcpListener, sync::Arc};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -11,17 +19,45 @@ async fn main() -> Result<(), Box<dyn Error>> {
     env_logger::init();
 
     let settings = Settings::load()?;
+    if print_config_requested() {
+        println!("{}", settings.dump()?);
+        return Ok(());
+    }
 
-    let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+    let server = settings.server.as_ref().ok_or("Missing server settings")?;
+    let listener = TcpListener::bind((server.host.as_str(), server.port))?;
     let datasource = create_datasource(settings.database.as_ref().unwrap())
         .await
         .unwrap();
 
     let plugin_repository = perroute_connectors::plugin_repository();
 
-    let command_bus = create_command_bus(datasource.clone(), plugin_repository);
+    let template_repository = PgTemplateRepository::new(datasource.clone());
+    let template_lookup =
+        repository::configured(&settings, &datasource).await?;
+    let mut handlebars = HandlebarsPlugin::new();
+    if let Some(helpers) = settings.template_helpers.as_ref() {
+        handlebars = handlebars.with_script_helpers(&helpers.scripts_path)?;
+    }
+    let render_engines = RenderEngines::default()
+        .with_engine(TemplateEngine::Handlebars, handlebars)
+        .with_engine(TemplateEngine::MiniJinja, MiniJinjaPlugin::new());
+    let command_bus =
+        create_command_bus(datasource.clone(), plugin_repository, &settings);
     let query_bus = create_query_bus(datasource);
-    let rest_service = RestService::new(command_bus, query_bus);
+    let rest_service = RestService::new(
+        command_bus,
+        query_bus,
+        Arc::new(template_repository),
+        template_lookup,
+        Arc::new(render_engines),
+        settings
+            .template_limits
+            .as_ref()
+            .map(RenderLimits::from)
+            .unwrap_or_default(),
+    )
+    .with_template_writes(settings.delivers_templates_from_postgres());
 
     let app = Application::new(listener, rest_service)?;
 
@@ -1,11 +1,33 @@
 use actix_web::{
     body::BoxBody, http::StatusCode, HttpResponse, Responder, ResponseError,
 };
-use perroute_command_bus::CommandBusError;
+use perroute_command_bus::{
+    commands::{
+        channel::{
+            create::CreateChannelCommandError,
+            update::UpdateChannelCommandError,
+        },
+        dead_letter::redrive::RedriveDeadLetterCommandError,
+        message::{
+            cancel::CancelMessageCommandError,
+            create::CreateMessageCommandError,
+            reschedule::RescheduleMessageCommandError,
+        },
+        template::{
+            publish::PublishTemplateVersionCommandError,
+            rollback::RollbackTemplateVersionCommandError,
+        },
+    },
+    CommandBusError,
+};
 use perroute_commons::types::{
-    code::InvalidCodeError, name::InvalidNameError, schema::InvalidSchemaError,
+    code::InvalidCodeError, idempotency_key::InvalidIdempotencyKeyError,
+    locale::InvalidLocaleError, name::InvalidNameError,
+    recipient::InvalidRecipientError, schema::InvalidSchemaError,
 };
+use perroute_connectors::types::ConfigurationError;
 use perroute_query_bus::QueryBusError;
+use perroute_template::repository::TemplateRepositoryError;
 use serde::{ser::SerializeStruct, Serialize};
 use serde_json::Value;
 use std::{collections::HashMap, error::Error};
@@ -53,8 +75,23 @@ pub enum ApiError {
     #[error("Invalid schema error: {0}")]
     InvalidSchemaError(#[from] InvalidSchemaError),
 
+    #[error("Invalid idempotency key: {0}")]
+    InvalidIdempotencyKeyError(#[from] InvalidIdempotencyKeyError),
+
+    #[error("Invalid locale: {0}")]
+    InvalidLocaleError(#[from] InvalidLocaleError),
+
+    #[error("Invalid recipient: {0}")]
+    InvalidRecipientError(#[from] InvalidRecipientError),
+
     #[error("Enum parser error: {0}")]
     EnumParserError(#[from] ParseError),
+
+    #[error("Template repository error: {0}")]
+    TemplateRepositoryError(#[from] TemplateRepositoryError),
+
+    #[error("Templates are only managed here when delivered from Postgres")]
+    ReadOnlyTemplateStorage,
 }
 
 impl ResponseError for ApiError {
@@ -76,15 +113,169 @@ impl From<&ApiError> for RestError {
                 RestError::internal_server("Internal server error")
             }
             ApiError::Conflict => RestError::conflict("Conflict"),
+            ApiError::ReadOnlyTemplateStorage => {
+                RestError::conflict(value.to_string())
+            }
             ApiError::BadRequest => {
                 RestError::bad_request("Bad request", Default::default())
             }
+            ApiError::CommandBusError(
+                CommandBusError::CreateChannelCommandError(
+                    CreateChannelCommandError::InvalidConfiguration(e),
+                )
+                | CommandBusError::UpdateChannelCommandError(
+                    UpdateChannelCommandError::InvalidConfiguration(e),
+                ),
+            ) => RestError::bad_request(e.to_string(), e.into()),
+            ApiError::CommandBusError(
+                CommandBusError::CreateChannelCommandError(
+                    e @ CreateChannelCommandError::PluginNotFound(_),
+                ),
+            ) => RestError::bad_request(
+                e.to_string(),
+                FieldErrors(vec![FieldError::new(
+                    "provider_id",
+                    "not_found",
+                    e.to_string(),
+                )]),
+            ),
+            ApiError::CommandBusError(
+                CommandBusError::UpdateChannelCommandError(e),
+            ) => match e {
+                UpdateChannelCommandError::ChannelNotFound => {
+                    RestError::not_found(e.to_string())
+                }
+                _ => RestError::bad_request(e.to_string(), Default::default()),
+            },
+            ApiError::CommandBusError(
+                CommandBusError::RedriveDeadLetterCommandError(e),
+            ) => match e {
+                RedriveDeadLetterCommandError::DeadLetterNotFound => {
+                    RestError::not_found(e.to_string())
+                }
+                RedriveDeadLetterCommandError::NotRedrivable(_) => {
+                    RestError::bad_request(e.to_string(), Default::default())
+                }
+                _ => RestError::conflict(e.to_string()),
+            },
+            ApiError::CommandBusError(
+                CommandBusError::CreateMessageCommandError(e),
+            ) => match e {
+                CreateMessageCommandError::DuplicateKey(_) => {
+                    RestError::conflict(e.to_string())
+                }
+                CreateMessageCommandError::InvalidSchema(_) => {
+                    RestError::internal_server(e.to_string())
+                }
+                _ => RestError::bad_request(e.to_string(), e.into()),
+            },
+            ApiError::CommandBusError(
+                CommandBusError::RescheduleMessageCommandError(e),
+            ) => match e {
+                RescheduleMessageCommandError::MessageNotFound => {
+                    RestError::not_found(e.to_string())
+                }
+                RescheduleMessageCommandError::NotScheduled(_) => {
+                    RestError::conflict(e.to_string())
+                }
+                RescheduleMessageCommandError::ScheduledAtInPast => {
+                    RestError::bad_request(e.to_string(), Default::default())
+                }
+            },
+            ApiError::CommandBusError(
+                CommandBusError::CancelMessageCommandError(e),
+            ) => match e {
+                CancelMessageCommandError::MessageNotFound => {
+                    RestError::not_found(e.to_string())
+                }
+                CancelMessageCommandError::NotScheduled(_) => {
+                    RestError::conflict(e.to_string())
+                }
+            },
+            ApiError::CommandBusError(
+                CommandBusError::PublishTemplateVersionCommandError(e),
+            ) => match e {
+                PublishTemplateVersionCommandError::VersionNotFound(_) => {
+                    RestError::not_found(e.to_string())
+                }
+                PublishTemplateVersionCommandError::NotDraft(_) => {
+                    RestError::conflict(e.to_string())
+                }
+            },
+            ApiError::CommandBusError(
+                CommandBusError::RollbackTemplateVersionCommandError(e),
+            ) => match e {
+                RollbackTemplateVersionCommandError::VersionNotFound(_) => {
+                    RestError::not_found(e.to_string())
+                }
+                RollbackTemplateVersionCommandError::NotArchived(_) => {
+                    RestError::conflict(e.to_string())
+                }
+            },
             ApiError::CommandBusError(e) => {
                 RestError::internal_server(e.to_string())
             }
             ApiError::QueryBusError(e) => {
                 RestError::internal_server(e.to_string())
             }
+            ApiError::InvalidIdempotencyKeyError(e) => RestError::bad_request(
+                e.to_string(),
+                FieldErrors(vec![FieldError::new(
+                    "key",
+                    "invalid",
+                    e.to_string(),
+                )]),
+            ),
+            ApiError::InvalidLocaleError(e) => RestError::bad_request(
+                e.to_string(),
+                FieldErrors(vec![FieldError::new(
+                    "locale",
+                    "invalid",
+                    e.to_string(),
+                )]),
+            ),
+            ApiError::InvalidRecipientError(e) => RestError::bad_request(
+                e.to_string(),
+                FieldErrors(vec![FieldError::new(
+                    "recipient",
+                    "invalid",
+                    e.to_string(),
+                )]),
+            ),
+            ApiError::InvalidSchemaError(e) => RestError::bad_request(
+                e.to_string(),
+                FieldErrors(vec![FieldError::new(
+                    "schema",
+                    "invalid",
+                    e.to_string(),
+                )]),
+            ),
+            ApiError::TemplateRepositoryError(e) => match e {
+                TemplateRepositoryError::PartialNotFound(_) => {
+                    RestError::not_found(e.to_string())
+                }
+                TemplateRepositoryError::InvalidContent(_) => {
+                    RestError::bad_request(
+                        e.to_string(),
+                        FieldErrors(vec![FieldError::new(
+                            "content",
+                            "invalid",
+                            e.to_string(),
+                        )]),
+                    )
+                }
+                TemplateRepositoryError::InvalidPartialName(_) => {
+                    RestError::bad_request(
+                        e.to_string(),
+                        FieldErrors(vec![FieldError::new(
+                            "name",
+                            "invalid",
+                            e.to_string(),
+                        )]),
+                    )
+                }
+                _ => RestError::internal_server(e.to_string()),
+            },
             ApiError::JsonPayloadError(ref e) => {
                 RestError::bad_request(e.to_string(), Default::default())
             }
@@ -192,6 +383,71 @@ impl From<&ValidationErrors> for FieldErrors {
     }
 }
 
+impl From<&ConfigurationError> for FieldErrors {
+    fn from(value: &ConfigurationError) -> Self {
+        match value {
+            ConfigurationError::UnsupportedDispatchType(_) => {
+                Self(vec![FieldError::new(
+                    "dispatch_type",
+                    "unsupported",
+                    value.to_string(),
+                )])
+            }
+            ConfigurationError::InvalidProperties(errors) => Self(
+                errors
+                    .iter()
+                    .map(|e| {
+                        FieldError::new(
+                            &format!("configuration.{}", e.property()),
+                            e.kind().code(),
+                            e.kind().to_string(),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&CreateMessageCommandError> for FieldErrors {
+    fn from(value: &CreateMessageCommandError) -> Self {
+        let field = |path: &str, code: &str| {
+            Self(vec![FieldError::new(path, code, value.to_string())])
+        };
+
+        match value {
+            CreateMessageCommandError::MessageTypeNotFound(_) => {
+                field("message_type_id", "not_found")
+            }
+            CreateMessageCommandError::MessageTypeDisabled(_) => {
+                field("message_type_id", "disabled")
+            }
+            CreateMessageCommandError::BusinessUnitNotFound(_) => {
+                field("business_unit_id", "not_found")
+            }
+            CreateMessageCommandError::RecipientMismatch(_, _) => {
+                field("recipient", "mismatch")
+            }
+            CreateMessageCommandError::InvalidPayload(violations) => Self(
+                violations
+                    .iter()
+                    .map(|v| {
+                        FieldError::new(
+                            &format!("/payload{}", v.path()),
+                            "schema",
+                            v.message().clone(),
+                        )
+                    })
+                    .collect(),
+            ),
+            CreateMessageCommandError::DuplicateKey(_) => {
+                field("key", "duplicate")
+            }
+            CreateMessageCommandError::InvalidSchema(_) => Self::default(),
+        }
+    }
+}
+
 #[derive(serde::Serialize, Debug)]
 pub struct FieldError {
     path: String,
@@ -201,6 +457,15 @@ pub struct FieldError {
 }
 
 impl FieldError {
+    pub fn new(path: &str, code: &str, message: String) -> Self {
+        Self {
+            path: path.to_string(),
+            code: code.to_string(),
+            message: Some(message),
+            params: None,
+        }
+    }
+
     pub fn from(path: &str, error: ValidationError) -> Self {
         Self {
             path: path.to_string(),
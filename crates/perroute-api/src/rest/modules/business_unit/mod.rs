@@ -1,7 +1,9 @@
 use super::{
     channel::{self, service::ChannelRestService},
+    partial::{self, service::PartialRestService},
     route::{self, service::RouteRestService},
     template_assignment,
+    template_coverage::{self, service::TemplateCoverageRestService},
 };
 use actix_web::{web, Scope};
 use handlers::{
@@ -18,7 +20,12 @@ const BUSINESS_UNIT_COLLECTION_RESOURCE_NAME: &str = "business_units";
 const BUSINESS_UNIT_RESOURCE_NAME: &str = "business_unit";
 
 pub fn scope<
-    RS: BusinessUnitRestService + ChannelRestService + RouteRestService + 'static,
+    RS: BusinessUnitRestService
+        + ChannelRestService
+        + RouteRestService
+        + PartialRestService
+        + TemplateCoverageRestService
+        + 'static,
 >() -> Scope {
     web::scope("/business_units")
         .service(
@@ -38,6 +45,8 @@ pub fn scope<
                 )
                 .service(channel::scope::<RS>())
                 .service(route::scope::<RS>())
+                .service(partial::scope::<RS>())
+                .service(template_coverage::scope::<RS>())
                 .service(template_assignment::scope()),
         )
 }
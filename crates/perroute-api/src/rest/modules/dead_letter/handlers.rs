@@ -0,0 +1,33 @@
+use super::{
+    models::{DeadLetterCollectionPath, DeadLetterModel, DeadLetterPath},
+    service::DeadLetterRestService,
+};
+use crate::rest::{
+    models::{
+        resource::{ResourceModel, ResourceModelCollection},
+        ApiResponse,
+    },
+    modules::ApiResult,
+};
+use actix_web::web::Data;
+use actix_web_validator::Path;
+use perroute_commons::types::actor::Actor;
+
+pub async fn query_dead_letters<RS: DeadLetterRestService + 'static>(
+    service: Data<RS>,
+) -> ApiResult<ResourceModelCollection<DeadLetterModel>> {
+    service
+        .query(&Actor::System, &DeadLetterCollectionPath)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn redrive_dead_letter<RS: DeadLetterRestService + 'static>(
+    service: Data<RS>,
+    path: Path<DeadLetterPath>,
+) -> ApiResult<ResourceModel<DeadLetterModel>> {
+    service
+        .redrive(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
@@ -0,0 +1,24 @@
+use actix_web::{web, Scope};
+use handlers::{query_dead_letters, redrive_dead_letter};
+use service::DeadLetterRestService;
+
+pub mod handlers;
+pub mod models;
+pub mod service;
+
+const DEAD_LETTER_COLLECTION_RESOURCE_NAME: &str = "dead_letters";
+const DEAD_LETTER_REDRIVE_RESOURCE_NAME: &str = "dead_letter_redrive";
+
+pub fn scope<RS: DeadLetterRestService + 'static>() -> Scope {
+    web::scope("/dead_letters")
+        .service(
+            web::resource("")
+                .name(DEAD_LETTER_COLLECTION_RESOURCE_NAME)
+                .route(web::get().to(query_dead_letters::<RS>)),
+        )
+        .service(
+            web::resource("/{dead_letter_id}/redrive")
+                .name(DEAD_LETTER_REDRIVE_RESOURCE_NAME)
+                .route(web::post().to(redrive_dead_letter::<RS>)),
+        )
+}
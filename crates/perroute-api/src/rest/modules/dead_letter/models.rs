@@ -0,0 +1,107 @@
+use super::{
+    DEAD_LETTER_COLLECTION_RESOURCE_NAME, DEAD_LETTER_REDRIVE_RESOURCE_NAME,
+};
+use crate::rest::models::{
+    link::{Relation, ResourcePath},
+    resource::{ResourceModel, ResourceModelCollection},
+};
+use chrono::NaiveDateTime;
+use perroute_commons::types::id::Id;
+use perroute_storage::models::dead_letter::DeadLetter;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Clone, Validate, PartialEq, Eq)]
+pub struct DeadLetterPath {
+    dead_letter_id: String,
+}
+
+impl DeadLetterPath {
+    pub fn new(id: &str) -> Self {
+        DeadLetterPath {
+            dead_letter_id: id.to_string(),
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        Id::from(&self.dead_letter_id)
+    }
+}
+
+impl ResourcePath for DeadLetterPath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(DEAD_LETTER_REDRIVE_RESOURCE_NAME, [&self.dead_letter_id])
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct DeadLetterCollectionPath;
+
+impl Validate for DeadLetterCollectionPath {
+    fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        Ok(())
+    }
+}
+
+impl ResourcePath for DeadLetterCollectionPath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for_static(DEAD_LETTER_COLLECTION_RESOURCE_NAME)
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetterModel {
+    id: String,
+    message_id: Option<String>,
+    queue: String,
+    body: String,
+    reason: String,
+    receive_count: i32,
+    created_at: NaiveDateTime,
+    redriven_at: Option<NaiveDateTime>,
+}
+
+impl From<DeadLetter> for DeadLetterModel {
+    fn from(dead_letter: DeadLetter) -> Self {
+        DeadLetterModel {
+            id: dead_letter.id().to_string(),
+            message_id: dead_letter.message_id().as_ref().map(Id::to_string),
+            queue: dead_letter.queue().to_string(),
+            body: dead_letter.body().to_string(),
+            reason: dead_letter.reason().to_string(),
+            receive_count: *dead_letter.receive_count(),
+            created_at: **dead_letter.created_at(),
+            redriven_at: dead_letter.redriven_at().as_ref().map(|t| **t),
+        }
+    }
+}
+
+impl From<DeadLetter> for ResourceModel<DeadLetterModel> {
+    fn from(value: DeadLetter) -> Self {
+        let path = DeadLetterPath::new(value.id().as_ref());
+        let redrivable = value.message_id().is_some();
+        let model = ResourceModel::new(value.into()).with_link(
+            Relation::Static("dead_letters"),
+            DeadLetterCollectionPath,
+        );
+
+        // Dead letters without a message cannot be redriven.
+        if redrivable {
+            model.with_link(Relation::Static("redrive"), path)
+        } else {
+            model
+        }
+    }
+}
+
+impl From<Vec<DeadLetter>> for ResourceModelCollection<DeadLetterModel> {
+    fn from(value: Vec<DeadLetter>) -> Self {
+        ResourceModelCollection::new(
+            value.into_iter().map(Into::into).collect(),
+        )
+        .with_link(Relation::Self_, DeadLetterCollectionPath)
+    }
+}
@@ -0,0 +1,67 @@
+use super::models::{DeadLetterCollectionPath, DeadLetterModel, DeadLetterPath};
+use crate::rest::{
+    service::RestService, ResourceModelCollectionResult, ResourceModelResult,
+};
+use perroute_command_bus::{
+    commands::dead_letter::redrive::{
+        RedriveDeadLetterCommand, RedriveDeadLetterCommandHandler,
+    },
+    CommandBus,
+};
+use perroute_commons::types::actor::Actor;
+use perroute_query_bus::{
+    queries::dead_letter::{PendingDeadLettersQuery, QueryDeadLettersHandler},
+    QueryBus,
+};
+use std::future::Future;
+
+pub trait DeadLetterRestService {
+    fn query(
+        &self,
+        actor: &Actor,
+        path: &DeadLetterCollectionPath,
+    ) -> impl Future<Output = ResourceModelCollectionResult<DeadLetterModel>>;
+
+    fn redrive(
+        &self,
+        actor: &Actor,
+        path: &DeadLetterPath,
+    ) -> impl Future<Output = ResourceModelResult<DeadLetterModel>>;
+}
+
+impl<CB: CommandBus, QB: QueryBus> DeadLetterRestService
+    for RestService<CB, QB>
+{
+    async fn query(
+        &self,
+        actor: &Actor,
+        _path: &DeadLetterCollectionPath,
+    ) -> ResourceModelCollectionResult<DeadLetterModel> {
+        let dead_letters = self
+            .query_bus()
+            .execute::<_, QueryDeadLettersHandler, _>(
+                actor,
+                &PendingDeadLettersQuery,
+            )
+            .await?;
+
+        Ok(dead_letters.into())
+    }
+
+    async fn redrive(
+        &self,
+        actor: &Actor,
+        path: &DeadLetterPath,
+    ) -> ResourceModelResult<DeadLetterModel> {
+        let cmd = RedriveDeadLetterCommand::builder()
+            .dead_letter_id(path.id())
+            .build();
+
+        let (dead_letter, _) = self
+            .command_bus()
+            .execute::<_, RedriveDeadLetterCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok(dead_letter.into())
+    }
+}
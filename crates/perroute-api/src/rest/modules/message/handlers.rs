@@ -1,9 +1,49 @@
-use actix_web::{HttpResponse, Responder};
+use super::{
+    models::{
+        CreateMessageRequest, MessageModel, MessagePath,
+        RescheduleMessageRequest,
+    },
+    service::MessageRestService,
+};
+use crate::rest::{
+    models::{resource::ResourceModel, ApiResponse},
+    modules::ApiResult,
+};
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpResponse, Responder,
+};
+use perroute_commons::types::actor::Actor;
 
 pub async fn get() -> impl Responder {
     HttpResponse::Ok().finish()
 }
 
-pub async fn create() -> impl Responder {
-    HttpResponse::Ok().finish()
+pub async fn create<RS: MessageRestService + 'static>(
+    service: Data<RS>,
+    payload: Json<CreateMessageRequest>,
+) -> ApiResult<ResourceModel<MessageModel>> {
+    let message = service.create(&Actor::System, &payload).await?;
+    Ok(ApiResponse::created(message))
+}
+
+pub async fn reschedule<RS: MessageRestService + 'static>(
+    service: Data<RS>,
+    path: Path<MessagePath>,
+    payload: Json<RescheduleMessageRequest>,
+) -> ApiResult<ResourceModel<MessageModel>> {
+    service
+        .reschedule(&Actor::System, &path, &payload)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn cancel<RS: MessageRestService + 'static>(
+    service: Data<RS>,
+    path: Path<MessagePath>,
+) -> ApiResult<ResourceModel<MessageModel>> {
+    service
+        .cancel(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
 }
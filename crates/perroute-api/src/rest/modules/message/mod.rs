@@ -1,20 +1,32 @@
 mod handlers;
-mod models;
+pub mod models;
+pub mod service;
 
 use actix_web::{web, Scope};
+use service::MessageRestService;
 
-pub fn scope() -> Scope {
+const MESSAGE_RESOURCE_NAME: &str = "message_resource";
+const MESSAGE_SCHEDULE_RESOURCE_NAME: &str = "message_schedule_resource";
+
+pub fn scope<RS: MessageRestService + 'static>() -> Scope {
     web::scope("/messages")
         .service(
             web::resource("")
                 .name("messages_resource")
-                .route(web::post().to(handlers::create)),
+                .route(web::post().to(handlers::create::<RS>)),
         )
         .service(
-            web::scope("/{message_id}").service(
-                web::resource("")
-                    .name("message_resource")
-                    .route(web::get().to(handlers::get)),
-            ),
+            web::scope("/{message_id}")
+                .service(
+                    web::resource("")
+                        .name(MESSAGE_RESOURCE_NAME)
+                        .route(web::get().to(handlers::get)),
+                )
+                .service(
+                    web::resource("/schedule")
+                        .name(MESSAGE_SCHEDULE_RESOURCE_NAME)
+                        .route(web::put().to(handlers::reschedule::<RS>))
+                        .route(web::delete().to(handlers::cancel::<RS>)),
+                ),
         )
 }
@@ -1 +1,159 @@
+use super::{MESSAGE_RESOURCE_NAME, MESSAGE_SCHEDULE_RESOURCE_NAME};
+use crate::rest::models::{
+    link::{Relation, ResourcePath},
+    resource::ResourceModel,
+};
+use chrono::NaiveDateTime;
+use perroute_commons::types::{
+    dispatch_type::DispatchType, id::Id, idempotency_key::IdempotencyKey,
+    locale::Locale, recipient::Recipient, Payload, Tags, Timestamp,
+};
+use perroute_storage::models::message::Message;
+use crate::rest::error::ApiError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use url::Url;
+use validator::Validate;
 
+#[derive(Debug, Deserialize, Clone, Validate, PartialEq, Eq)]
+pub struct MessagePath {
+    message_id: String,
+}
+
+impl MessagePath {
+    pub fn new(id: &str) -> Self {
+        MessagePath {
+            message_id: id.to_string(),
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        Id::from(&self.message_id)
+    }
+}
+
+impl ResourcePath for MessagePath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(MESSAGE_RESOURCE_NAME, [&self.message_id])
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct MessageSchedulePath(MessagePath);
+
+impl ResourcePath for MessageSchedulePath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(MESSAGE_SCHEDULE_RESOURCE_NAME, [&self.0.message_id])
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateMessageRequest {
+    key: Option<String>,
+    message_type_id: String,
+    business_unit_id: String,
+    payload: Value,
+    dispatch_type: DispatchType,
+    recipient: Value,
+    locale: Option<String>,
+    scheduled_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    tags: HashSet<String>,
+}
+
+impl CreateMessageRequest {
+    pub fn key(&self) -> Result<Option<IdempotencyKey>, ApiError> {
+        Ok(self
+            .key
+            .as_deref()
+            .map(IdempotencyKey::try_from)
+            .transpose()?)
+    }
+
+    pub fn message_type_id(&self) -> Id {
+        Id::from(&self.message_type_id)
+    }
+
+    pub fn business_unit_id(&self) -> Id {
+        Id::from(&self.business_unit_id)
+    }
+
+    pub fn payload(&self) -> Payload {
+        Payload::new(self.payload.clone())
+    }
+
+    pub fn dispatch_type(&self) -> DispatchType {
+        self.dispatch_type
+    }
+
+    pub fn recipient(&self) -> Result<Recipient, ApiError> {
+        Ok(Recipient::try_from(&self.recipient)?)
+    }
+
+    /// Picks the localized variant of the template when one exists.
+    pub fn locale(&self) -> Result<Option<Locale>, ApiError> {
+        Ok(self.locale.as_deref().map(Locale::try_from).transpose()?)
+    }
+
+    pub fn scheduled_at(&self) -> Option<Timestamp> {
+        self.scheduled_at.map(Timestamp::from)
+    }
+
+    pub fn tags(&self) -> Tags {
+        Tags::from(self.tags.clone())
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RescheduleMessageRequest {
+    scheduled_at: NaiveDateTime,
+}
+
+impl RescheduleMessageRequest {
+    pub fn scheduled_at(&self) -> Timestamp {
+        Timestamp::from(self.scheduled_at)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessageModel {
+    id: String,
+    key: Option<String>,
+    message_type_id: String,
+    business_unit_id: String,
+    dispatch_type: String,
+    locale: Option<String>,
+    status: String,
+    scheduled_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<Message> for MessageModel {
+    fn from(message: Message) -> Self {
+        MessageModel {
+            id: message.id().to_string(),
+            key: message.key().as_ref().map(IdempotencyKey::to_string),
+            message_type_id: message.message_type_id().to_string(),
+            business_unit_id: message.business_unit_id().to_string(),
+            dispatch_type: message.dispatch_type().to_string(),
+            locale: message.locale().as_ref().map(Locale::to_string),
+            status: message.status().to_string(),
+            scheduled_at: message.scheduled_at().as_ref().map(|t| **t),
+            created_at: **message.created_at(),
+            updated_at: **message.updated_at(),
+        }
+    }
+}
+
+impl From<Message> for ResourceModel<MessageModel> {
+    fn from(value: Message) -> Self {
+        let path = MessagePath::new(value.id().as_ref());
+        ResourceModel::new(value.into())
+            .with_link(Relation::Self_, path.clone())
+            .with_link(Relation::Static("schedule"), MessageSchedulePath(path))
+    }
+}
@@ -0,0 +1,102 @@
+use super::models::{
+    CreateMessageRequest, MessageModel, MessagePath, RescheduleMessageRequest,
+};
+use crate::rest::{service::RestService, ResourceModelResult};
+use perroute_command_bus::{
+    commands::message::{
+        cancel::{CancelMessageCommand, CancelMessageCommandHandler},
+        create::{CreateMessageCommand, CreateMessageCommandHandler},
+        reschedule::{
+            RescheduleMessageCommand, RescheduleMessageCommandHandler,
+        },
+    },
+    CommandBus,
+};
+use perroute_commons::types::{actor::Actor, id::Id};
+use perroute_query_bus::QueryBus;
+use std::future::Future;
+
+pub trait MessageRestService {
+    fn create(
+        &self,
+        actor: &Actor,
+        payload: &CreateMessageRequest,
+    ) -> impl Future<Output = ResourceModelResult<MessageModel>>;
+
+    fn reschedule(
+        &self,
+        actor: &Actor,
+        path: &MessagePath,
+        payload: &RescheduleMessageRequest,
+    ) -> impl Future<Output = ResourceModelResult<MessageModel>>;
+
+    fn cancel(
+        &self,
+        actor: &Actor,
+        path: &MessagePath,
+    ) -> impl Future<Output = ResourceModelResult<MessageModel>>;
+}
+
+impl<CB: CommandBus, QB: QueryBus> MessageRestService for RestService<CB, QB> {
+    async fn create(
+        &self,
+        actor: &Actor,
+        payload: &CreateMessageRequest,
+    ) -> ResourceModelResult<MessageModel> {
+        let cmd = CreateMessageCommand::builder()
+            .message_id(Id::new())
+            .maybe_key(payload.key()?)
+            .message_type_id(payload.message_type_id())
+            .business_unit_id(payload.business_unit_id())
+            .payload(payload.payload())
+            .dispatch_type(payload.dispatch_type())
+            .recipient(payload.recipient()?)
+            .maybe_locale(payload.locale()?)
+            .maybe_scheduled_at(payload.scheduled_at())
+            .tags(payload.tags())
+            .build();
+
+        let message = self
+            .command_bus()
+            .execute::<_, CreateMessageCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok(message.into())
+    }
+
+    async fn reschedule(
+        &self,
+        actor: &Actor,
+        path: &MessagePath,
+        payload: &RescheduleMessageRequest,
+    ) -> ResourceModelResult<MessageModel> {
+        let cmd = RescheduleMessageCommand::builder()
+            .message_id(path.id())
+            .scheduled_at(payload.scheduled_at())
+            .build();
+
+        let message = self
+            .command_bus()
+            .execute::<_, RescheduleMessageCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok(message.into())
+    }
+
+    async fn cancel(
+        &self,
+        actor: &Actor,
+        path: &MessagePath,
+    ) -> ResourceModelResult<MessageModel> {
+        let cmd = CancelMessageCommand::builder()
+            .message_id(path.id())
+            .build();
+
+        let message = self
+            .command_bus()
+            .execute::<_, CancelMessageCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok(message.into())
+    }
+}
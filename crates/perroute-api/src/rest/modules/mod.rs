@@ -1,17 +1,28 @@
 pub mod business_unit;
 pub mod channel;
+pub mod dead_letter;
 pub mod health;
 pub mod message;
 pub mod message_type;
+pub mod partial;
 pub mod route;
+pub mod template;
 pub mod template_assignment;
+pub mod template_coverage;
+pub mod template_preview;
 pub mod user;
 
 use actix_web::{web, Scope};
 use business_unit::service::BusinessUnitRestService;
 use channel::service::ChannelRestService;
+use dead_letter::service::DeadLetterRestService;
+use message::service::MessageRestService;
 use message_type::service::MessageTypeRestService;
+use partial::service::PartialRestService;
 use route::service::RouteRestService;
+use template::service::TemplateRestService;
+use template_coverage::service::TemplateCoverageRestService;
+use template_preview::service::TemplatePreviewRestService;
 
 use super::{error::ApiError, models::ApiResponse};
 
@@ -22,14 +33,23 @@ pub fn routes<
         + MessageTypeRestService
         + ChannelRestService
         + RouteRestService
+        + DeadLetterRestService
+        + MessageRestService
+        + TemplateRestService
+        + TemplatePreviewRestService
+        + PartialRestService
+        + TemplateCoverageRestService
         + 'static,
 >() -> Scope {
     web::scope("").service(health::routes()).service(
         web::scope("/api").service(
             web::scope("/v1")
                 .service(business_unit::scope::<RS>())
-                .service(message::scope())
+                .service(dead_letter::scope::<RS>())
+                .service(message::scope::<RS>())
                 .service(message_type::scope::<RS>())
+                .service(template_preview::scope::<RS>())
+                .service(template::scope::<RS>())
                 .service(user::scope()),
         ),
     )
@@ -0,0 +1,54 @@
+use super::{
+    models::{PartialModel, PartialPath, PartialsPath, SavePartialRequest},
+    service::PartialRestService,
+};
+use crate::rest::{
+    models::{
+        resource::{ResourceModel, ResourceModelCollection},
+        ApiResponse,
+    },
+    modules::ApiResult,
+};
+use actix_web::web::{Data, Json, Path};
+use perroute_commons::types::actor::Actor;
+
+pub async fn query<RS: PartialRestService>(
+    service: Data<RS>,
+    path: Path<PartialsPath>,
+) -> ApiResult<ResourceModelCollection<PartialModel>> {
+    service
+        .query(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn get<RS: PartialRestService>(
+    service: Data<RS>,
+    path: Path<PartialPath>,
+) -> ApiResult<ResourceModel<PartialModel>> {
+    service
+        .get(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn save<RS: PartialRestService>(
+    service: Data<RS>,
+    path: Path<PartialPath>,
+    request: Json<SavePartialRequest>,
+) -> ApiResult<ResourceModel<PartialModel>> {
+    service
+        .save(&Actor::System, &path, &request)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn delete<RS: PartialRestService>(
+    service: Data<RS>,
+    path: Path<PartialPath>,
+) -> ApiResult<()> {
+    service
+        .delete(&Actor::System, &path)
+        .await
+        .map(|_| ApiResponse::no_content())
+}
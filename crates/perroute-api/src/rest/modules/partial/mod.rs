@@ -0,0 +1,25 @@
+mod handlers;
+pub mod models;
+pub mod service;
+
+use actix_web::{web, Scope};
+use service::PartialRestService;
+
+const PARTIALS_RESOURCE_NAME: &str = "partials";
+const PARTIAL_RESOURCE_NAME: &str = "partial";
+
+pub fn scope<RS: PartialRestService + 'static>() -> Scope {
+    web::scope("/partials")
+        .service(
+            web::resource("")
+                .name(PARTIALS_RESOURCE_NAME)
+                .route(web::get().to(handlers::query::<RS>)),
+        )
+        .service(
+            web::resource("/{name}")
+                .name(PARTIAL_RESOURCE_NAME)
+                .route(web::get().to(handlers::get::<RS>))
+                .route(web::put().to(handlers::save::<RS>))
+                .route(web::delete().to(handlers::delete::<RS>)),
+        )
+}
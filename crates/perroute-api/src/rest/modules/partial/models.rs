@@ -0,0 +1,117 @@
+use super::{PARTIALS_RESOURCE_NAME, PARTIAL_RESOURCE_NAME};
+use crate::rest::{
+    models::{
+        link::{Relation, ResourcePath},
+        resource::{ResourceModel, ResourceModelCollection},
+    },
+    modules::business_unit::models::BusinessUnitPath,
+};
+use perroute_commons::types::id::Id;
+use perroute_template::partial::Partials;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct PartialsPath {
+    business_unit_id: String,
+}
+
+impl PartialsPath {
+    pub fn business_unit_id(&self) -> Id {
+        Id::from(&self.business_unit_id)
+    }
+
+    pub fn into_collection(
+        self,
+        partials: Partials,
+    ) -> ResourceModelCollection<PartialModel> {
+        let mut partials = partials
+            .iter()
+            .map(|(name, content)| PartialModel::new(name, content))
+            .collect::<Vec<_>>();
+        partials.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let business_unit = BusinessUnitPath::new(&self.business_unit_id);
+        ResourceModelCollection::new(
+            partials
+                .into_iter()
+                .map(|partial| partial.into_resource(&self.business_unit_id))
+                .collect(),
+        )
+        .with_link(Relation::Self_, self)
+        .with_link(Relation::Static("business_unit"), business_unit)
+    }
+}
+
+impl ResourcePath for PartialsPath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(PARTIALS_RESOURCE_NAME, [&self.business_unit_id])
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct PartialPath {
+    business_unit_id: String,
+    name: String,
+}
+
+impl PartialPath {
+    pub fn business_unit_id(&self) -> Id {
+        Id::from(&self.business_unit_id)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ResourcePath for PartialPath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(PARTIAL_RESOURCE_NAME, [&self.business_unit_id, &self.name])
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavePartialRequest {
+    content: String,
+}
+
+impl SavePartialRequest {
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartialModel {
+    name: String,
+    content: String,
+}
+
+impl PartialModel {
+    pub fn new(name: &str, content: &str) -> Self {
+        PartialModel {
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    pub fn into_resource(
+        self,
+        business_unit_id: &str,
+    ) -> ResourceModel<PartialModel> {
+        let path = PartialPath {
+            business_unit_id: business_unit_id.to_string(),
+            name: self.name.clone(),
+        };
+        let partials = PartialsPath {
+            business_unit_id: business_unit_id.to_string(),
+        };
+
+        ResourceModel::new(self)
+            .with_link(Relation::Self_, path)
+            .with_link(Relation::Static("partials"), partials)
+    }
+}
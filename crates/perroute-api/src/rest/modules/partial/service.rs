@@ -0,0 +1,171 @@
+use super::models::{PartialModel, PartialPath, PartialsPath, SavePartialRequest};
+use crate::rest::{
+    error::ApiError, service::RestService, ResourceModelCollectionResult,
+    ResourceModelResult,
+};
+use perroute_command_bus::CommandBus;
+use perroute_commons::types::actor::Actor;
+use perroute_query_bus::QueryBus;
+use std::future::Future;
+
+pub trait PartialRestService {
+    fn query(
+        &self,
+        actor: &Actor,
+        path: &PartialsPath,
+    ) -> impl Future<Output = ResourceModelCollectionResult<PartialModel>>;
+
+    fn get(
+        &self,
+        actor: &Actor,
+        path: &PartialPath,
+    ) -> impl Future<Output = ResourceModelResult<PartialModel>>;
+
+    /// Creates or replaces the partial.
+    fn save(
+        &self,
+        actor: &Actor,
+        path: &PartialPath,
+        request: &SavePartialRequest,
+    ) -> impl Future<Output = ResourceModelResult<PartialModel>>;
+
+    fn delete(
+        &self,
+        actor: &Actor,
+        path: &PartialPath,
+    ) -> impl Future<Output = Result<(), ApiError>>;
+}
+
+impl<CB: CommandBus, QB: QueryBus> PartialRestService for RestService<CB, QB> {
+    async fn query(
+        &self,
+        _actor: &Actor,
+        path: &PartialsPath,
+    ) -> ResourceModelCollectionResult<PartialModel> {
+        let partials = self
+            .template_repository()
+            .partials(&path.business_unit_id())
+            .await?;
+
+        Ok(path.clone().into_collection(partials))
+    }
+
+    async fn get(
+        &self,
+        _actor: &Actor,
+        path: &PartialPath,
+    ) -> ResourceModelResult<PartialModel> {
+        let business_unit_id = path.business_unit_id();
+        let partials = self
+            .template_repository()
+            .partials(&business_unit_id)
+            .await?;
+
+        partials
+            .get(path.name())
+            .map(|content| {
+                PartialModel::new(path.name(), content)
+                    .into_resource(business_unit_id.as_ref())
+            })
+            .ok_or(ApiError::NotFound)
+    }
+
+    async fn save(
+        &self,
+        actor: &Actor,
+        path: &PartialPath,
+        request: &SavePartialRequest,
+    ) -> ResourceModelResult<PartialModel> {
+        self.ensure_template_writes()?;
+        let business_unit_id = path.business_unit_id();
+        self.template_repository()
+            .save_partial(
+                &business_unit_id,
+                path.name(),
+                request.content(),
+                actor,
+            )
+            .await?;
+
+        Ok(PartialModel::new(path.name(), request.content())
+            .into_resource(business_unit_id.as_ref()))
+    }
+
+    async fn delete(
+        &self,
+        _actor: &Actor,
+        path: &PartialPath,
+    ) -> Result<(), ApiError> {
+        self.ensure_template_writes()?;
+        Ok(self
+            .template_repository()
+            .delete_partial(&path.business_unit_id(), path.name())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::service::tests::service;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn path(name: &str) -> PartialPath {
+        serde_json::from_value(
+            json!({"business_unit_id": "bu-1", "name": name}),
+        )
+        .unwrap()
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../../perroute-storage/fixtures",
+            scripts("messages")
+        )
+    )]
+    async fn saves_partials_delivered_from_postgres(pool: PgPool) {
+        let service = service(
+            &pool,
+            json!({"template_storage": {"backend": "postgres"}}),
+        )
+        .await;
+        let request: SavePartialRequest =
+            serde_json::from_value(json!({"content": "Bye"})).unwrap();
+
+        service
+            .save(&Actor::System, &path("footer"), &request)
+            .await
+            .unwrap();
+
+        let partials = service
+            .template_lookup()
+            .partials(&"bu-1".into())
+            .await
+            .unwrap();
+        assert_eq!(partials.get("footer"), Some("Bye"));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(migrations = "../perroute-storage/migrations")]
+    async fn refuses_changes_to_partials_delivered_from_elsewhere(
+        pool: PgPool,
+    ) {
+        let service = service(
+            &pool,
+            json!({"template_directory": {"path": "templates"}}),
+        )
+        .await;
+        let request: SavePartialRequest =
+            serde_json::from_value(json!({"content": "Bye"})).unwrap();
+
+        let saved = service
+            .save(&Actor::System, &path("footer"), &request)
+            .await;
+        assert!(matches!(saved, Err(ApiError::ReadOnlyTemplateStorage)));
+        let deleted = service.delete(&Actor::System, &path("footer")).await;
+        assert!(matches!(deleted, Err(ApiError::ReadOnlyTemplateStorage)));
+    }
+}
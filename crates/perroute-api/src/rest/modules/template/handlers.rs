@@ -0,0 +1,73 @@
+use super::{
+    models::{TemplatePath, TemplateVersionModel, TemplateVersionPath},
+    service::TemplateRestService,
+};
+use crate::rest::{
+    models::{
+        resource::{ResourceModel, ResourceModelCollection},
+        ApiResponse,
+    },
+    modules::ApiResult,
+};
+use actix_web::web::{Data, Json, Path};
+use perroute_commons::types::actor::Actor;
+use serde_json::Value;
+
+pub async fn get<RS: TemplateRestService>(
+    service: Data<RS>,
+    path: Path<TemplatePath>,
+) -> ApiResult<ResourceModel<TemplateVersionModel>> {
+    service
+        .get(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn create<RS: TemplateRestService>(
+    service: Data<RS>,
+    path: Path<TemplatePath>,
+    payload: Json<Value>,
+) -> ApiResult<ResourceModel<TemplateVersionModel>> {
+    let version = service.create(&Actor::System, &path, &payload).await?;
+    Ok(ApiResponse::created(version))
+}
+
+pub async fn versions<RS: TemplateRestService>(
+    service: Data<RS>,
+    path: Path<TemplatePath>,
+) -> ApiResult<ResourceModelCollection<TemplateVersionModel>> {
+    service
+        .versions(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn version<RS: TemplateRestService>(
+    service: Data<RS>,
+    path: Path<TemplateVersionPath>,
+) -> ApiResult<ResourceModel<TemplateVersionModel>> {
+    service
+        .version(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn publish<RS: TemplateRestService>(
+    service: Data<RS>,
+    path: Path<TemplateVersionPath>,
+) -> ApiResult<ResourceModel<TemplateVersionModel>> {
+    service
+        .publish(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
+
+pub async fn rollback<RS: TemplateRestService>(
+    service: Data<RS>,
+    path: Path<TemplateVersionPath>,
+) -> ApiResult<ResourceModel<TemplateVersionModel>> {
+    service
+        .rollback(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
@@ -0,0 +1,41 @@
+mod handlers;
+pub mod models;
+pub mod service;
+
+use actix_web::{web, Scope};
+use service::TemplateRestService;
+
+const TEMPLATE_RESOURCE_NAME: &str = "template";
+const TEMPLATE_VERSIONS_RESOURCE_NAME: &str = "template_versions";
+const TEMPLATE_VERSION_RESOURCE_NAME: &str = "template_version";
+
+pub fn scope<RS: TemplateRestService + 'static>() -> Scope {
+    web::scope("/templates/{template_id}/{dispatch_type}")
+        .service(
+            web::resource("")
+                .name(TEMPLATE_RESOURCE_NAME)
+                .route(web::get().to(handlers::get::<RS>))
+                .route(web::put().to(handlers::create::<RS>)),
+        )
+        .service(
+            web::resource("/versions")
+                .name(TEMPLATE_VERSIONS_RESOURCE_NAME)
+                .route(web::get().to(handlers::versions::<RS>)),
+        )
+        .service(
+            web::scope("/versions/{version}")
+                .service(
+                    web::resource("")
+                        .name(TEMPLATE_VERSION_RESOURCE_NAME)
+                        .route(web::get().to(handlers::version::<RS>)),
+                )
+                .service(
+                    web::resource("/publish")
+                        .route(web::post().to(handlers::publish::<RS>)),
+                )
+                .service(
+                    web::resource("/rollback")
+                        .route(web::post().to(handlers::rollback::<RS>)),
+                ),
+        )
+}
@@ -0,0 +1,188 @@
+use super::{
+    TEMPLATE_RESOURCE_NAME, TEMPLATE_VERSIONS_RESOURCE_NAME,
+    TEMPLATE_VERSION_RESOURCE_NAME,
+};
+use crate::rest::{
+    error::ApiError,
+    models::{
+        link::{Relation, ResourcePath},
+        resource::{ResourceModel, ResourceModelCollection},
+    },
+};
+use chrono::NaiveDateTime;
+use perroute_commons::types::{
+    actor::Actor, dispatch_type::DispatchType, id::Id, TemplateVersionStatus,
+};
+use perroute_template::{lint::LintIssue, version::TemplateVersion};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TemplatePath {
+    template_id: String,
+    dispatch_type: String,
+}
+
+impl TemplatePath {
+    pub fn new(id: &str, dispatch_type: &DispatchType) -> Self {
+        TemplatePath {
+            template_id: id.to_string(),
+            dispatch_type: dispatch_type.to_string().to_lowercase(),
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        Id::from(&self.template_id)
+    }
+
+    pub fn dispatch_type(&self) -> Result<DispatchType, ApiError> {
+        match self.dispatch_type.as_str() {
+            "email" => Ok(DispatchType::Email),
+            "sms" => Ok(DispatchType::Sms),
+            "push" => Ok(DispatchType::Push),
+            _ => Err(ApiError::NotFound),
+        }
+    }
+}
+
+impl ResourcePath for TemplatePath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(
+            TEMPLATE_RESOURCE_NAME,
+            [&self.template_id, &self.dispatch_type],
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TemplateVersionsPath(TemplatePath);
+
+impl TemplateVersionsPath {
+    pub fn new(template: TemplatePath) -> Self {
+        TemplateVersionsPath(template)
+    }
+
+    pub fn into_collection(
+        self,
+        versions: Vec<TemplateVersion>,
+    ) -> ResourceModelCollection<TemplateVersionModel> {
+        ResourceModelCollection::new(
+            versions.into_iter().map(Into::into).collect(),
+        )
+        .with_link(Relation::Self_, self)
+    }
+}
+
+impl ResourcePath for TemplateVersionsPath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(
+            TEMPLATE_VERSIONS_RESOURCE_NAME,
+            [&self.0.template_id, &self.0.dispatch_type],
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TemplateVersionPath {
+    template_id: String,
+    dispatch_type: String,
+    version: i32,
+}
+
+impl TemplateVersionPath {
+    pub fn template(&self) -> TemplatePath {
+        TemplatePath {
+            template_id: self.template_id.clone(),
+            dispatch_type: self.dispatch_type.clone(),
+        }
+    }
+
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+}
+
+impl ResourcePath for TemplateVersionPath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(
+            TEMPLATE_VERSION_RESOURCE_NAME,
+            [
+                &self.template_id,
+                &self.dispatch_type,
+                &self.version.to_string(),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateVersionModel {
+    template_id: String,
+    dispatch_type: DispatchType,
+    version: i32,
+    status: TemplateVersionStatus,
+    content: Value,
+    created_by: Actor,
+    created_at: NaiveDateTime,
+    published_by: Option<Actor>,
+    published_at: Option<NaiveDateTime>,
+    /// Issues found when the version was saved.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    lint: Vec<LintIssue>,
+}
+
+impl From<TemplateVersion> for TemplateVersionModel {
+    fn from(version: TemplateVersion) -> Self {
+        TemplateVersionModel {
+            template_id: version.template_id().to_string(),
+            dispatch_type: version.template().dispatch_type(),
+            version: *version.version(),
+            status: *version.status(),
+            content: version.template().to_value(),
+            created_by: version.created_by().clone(),
+            created_at: **version.created_at(),
+            published_by: version.published_by().clone(),
+            published_at: version.published_at().as_ref().map(|t| **t),
+            lint: vec![],
+        }
+    }
+}
+
+impl From<TemplateVersion> for ResourceModel<TemplateVersionModel> {
+    fn from(value: TemplateVersion) -> Self {
+        (value, vec![]).into()
+    }
+}
+
+impl From<(TemplateVersion, Vec<LintIssue>)>
+    for ResourceModel<TemplateVersionModel>
+{
+    fn from((value, lint): (TemplateVersion, Vec<LintIssue>)) -> Self {
+        let template = TemplatePath::new(
+            value.template_id().as_ref(),
+            &value.template().dispatch_type(),
+        );
+        let path = TemplateVersionPath {
+            template_id: template.template_id.clone(),
+            dispatch_type: template.dispatch_type.clone(),
+            version: *value.version(),
+        };
+
+        let model = TemplateVersionModel {
+            lint,
+            ..value.into()
+        };
+
+        ResourceModel::new(model)
+            .with_link(Relation::Self_, path)
+            .with_link(
+                Relation::Static("versions"),
+                TemplateVersionsPath(template.clone()),
+            )
+            .with_link(Relation::Static("template"), template)
+    }
+}
@@ -0,0 +1,312 @@
+use super::models::{
+    TemplatePath, TemplateVersionModel, TemplateVersionPath,
+    TemplateVersionsPath,
+};
+use crate::rest::{
+    error::ApiError, service::RestService, ResourceModelCollectionResult,
+    ResourceModelResult,
+};
+use perroute_command_bus::{
+    commands::template::{
+        create_version::{
+            CreateTemplateVersionCommand, CreateTemplateVersionCommandHandler,
+        },
+        publish::{
+            PublishTemplateVersionCommand, PublishTemplateVersionCommandHandler,
+        },
+        rollback::{
+            RollbackTemplateVersionCommand,
+            RollbackTemplateVersionCommandHandler,
+        },
+    },
+    CommandBus,
+};
+use perroute_commons::types::actor::Actor;
+use perroute_query_bus::{
+    queries::template_lint::{TemplateLintHandler, TemplateLintQuery},
+    QueryBus,
+};
+use perroute_template::{
+    lint::{LintIssue, TemplateLinter},
+    repository::TemplateRepositoryError,
+    template::{NotRenderedTemplateState, Template},
+    version::TemplateVersion,
+};
+use serde_json::Value;
+use std::future::Future;
+
+pub trait TemplateRestService {
+    /// The published version of the template.
+    fn get(
+        &self,
+        actor: &Actor,
+        path: &TemplatePath,
+    ) -> impl Future<Output = ResourceModelResult<TemplateVersionModel>>;
+
+    /// Saves the content as a new draft version. Issues found linting it
+    /// against the message types of the assignments using the template are
+    /// reported with the version but do not prevent saving it.
+    fn create(
+        &self,
+        actor: &Actor,
+        path: &TemplatePath,
+        content: &Value,
+    ) -> impl Future<Output = ResourceModelResult<TemplateVersionModel>>;
+
+    fn versions(
+        &self,
+        actor: &Actor,
+        path: &TemplatePath,
+    ) -> impl Future<Output = ResourceModelCollectionResult<TemplateVersionModel>>;
+
+    fn version(
+        &self,
+        actor: &Actor,
+        path: &TemplateVersionPath,
+    ) -> impl Future<Output = ResourceModelResult<TemplateVersionModel>>;
+
+    fn publish(
+        &self,
+        actor: &Actor,
+        path: &TemplateVersionPath,
+    ) -> impl Future<Output = ResourceModelResult<TemplateVersionModel>>;
+
+    fn rollback(
+        &self,
+        actor: &Actor,
+        path: &TemplateVersionPath,
+    ) -> impl Future<Output = ResourceModelResult<TemplateVersionModel>>;
+}
+
+impl<CB: CommandBus, QB: QueryBus> TemplateRestService for RestService<CB, QB> {
+    async fn get(
+        &self,
+        _actor: &Actor,
+        path: &TemplatePath,
+    ) -> ResourceModelResult<TemplateVersionModel> {
+        self.template_repository()
+            .published(&path.id(), &path.dispatch_type()?)
+            .await?
+            .map(Into::into)
+            .ok_or(ApiError::NotFound)
+    }
+
+    async fn create(
+        &self,
+        actor: &Actor,
+        path: &TemplatePath,
+        content: &Value,
+    ) -> ResourceModelResult<TemplateVersionModel> {
+        self.ensure_template_writes()?;
+        let template =
+            Template::from_value(&path.dispatch_type()?, content.clone())
+                .map_err(TemplateRepositoryError::from)?;
+
+        let lint = self.lint_template(actor, path, &template).await?;
+        let cmd = CreateTemplateVersionCommand::builder()
+            .template_id(path.id())
+            .dispatch_type(template.dispatch_type())
+            .content(
+                serde_json::to_value(&template)
+                    .map_err(TemplateRepositoryError::from)?,
+            )
+            .build();
+        let version = self
+            .command_bus()
+            .execute::<_, CreateTemplateVersionCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok((TemplateVersion::try_from(version)?, lint).into())
+    }
+
+    async fn versions(
+        &self,
+        _actor: &Actor,
+        path: &TemplatePath,
+    ) -> ResourceModelCollectionResult<TemplateVersionModel> {
+        let versions = self
+            .template_repository()
+            .versions(&path.id(), &path.dispatch_type()?)
+            .await?;
+
+        Ok(TemplateVersionsPath::new(path.clone()).into_collection(versions))
+    }
+
+    async fn version(
+        &self,
+        _actor: &Actor,
+        path: &TemplateVersionPath,
+    ) -> ResourceModelResult<TemplateVersionModel> {
+        let template = path.template();
+        self.template_repository()
+            .version(&template.id(), &template.dispatch_type()?, path.version())
+            .await?
+            .map(Into::into)
+            .ok_or(ApiError::NotFound)
+    }
+
+    async fn publish(
+        &self,
+        actor: &Actor,
+        path: &TemplateVersionPath,
+    ) -> ResourceModelResult<TemplateVersionModel> {
+        self.ensure_template_writes()?;
+        let template = path.template();
+        let cmd = PublishTemplateVersionCommand::builder()
+            .template_id(template.id())
+            .dispatch_type(template.dispatch_type()?)
+            .version(path.version())
+            .build();
+        let (version, _) = self
+            .command_bus()
+            .execute::<_, PublishTemplateVersionCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok(TemplateVersion::try_from(version)?.into())
+    }
+
+    async fn rollback(
+        &self,
+        actor: &Actor,
+        path: &TemplateVersionPath,
+    ) -> ResourceModelResult<TemplateVersionModel> {
+        self.ensure_template_writes()?;
+        let template = path.template();
+        let cmd = RollbackTemplateVersionCommand::builder()
+            .template_id(template.id())
+            .dispatch_type(template.dispatch_type()?)
+            .version(path.version())
+            .build();
+        let (version, _) = self
+            .command_bus()
+            .execute::<_, RollbackTemplateVersionCommandHandler, _>(actor, &cmd)
+            .await?;
+
+        Ok(TemplateVersion::try_from(version)?.into())
+    }
+}
+
+impl<CB: CommandBus, QB: QueryBus> RestService<CB, QB> {
+    async fn lint_template(
+        &self,
+        actor: &Actor,
+        path: &TemplatePath,
+        template: &Template<NotRenderedTemplateState>,
+    ) -> Result<Vec<LintIssue>, ApiError> {
+        let query = TemplateLintQuery::builder()
+            .template_id(path.id())
+            .dispatch_type(path.dispatch_type()?)
+            .build();
+        let contexts = self
+            .query_bus()
+            .execute::<_, TemplateLintHandler, _>(actor, &query)
+            .await?;
+
+        if contexts.is_empty() {
+            return Ok(TemplateLinter::default().lint(template));
+        }
+
+        let mut issues = vec![];
+        for ctx in &contexts {
+            let linter = TemplateLinter::builder()
+                .schema(ctx.schema())
+                .vars(ctx.vars())
+                .build();
+            for issue in linter.lint(template) {
+                if !issues.contains(&issue) {
+                    issues.push(issue);
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::service::tests::service;
+    use perroute_commons::types::dispatch_type::DispatchType;
+    use perroute_template::repository::TemplateId;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn version_path(version: i32) -> TemplateVersionPath {
+        serde_json::from_value(json!({
+            "template_id": "tpl-1",
+            "dispatch_type": "email",
+            "version": version,
+        }))
+        .unwrap()
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn published_versions_are_delivered_from_postgres(pool: PgPool) {
+        let template = Template::email("Hi", "<p>Hi</p>", "Hi");
+        sqlx::query(
+            "update template_versions set content = $1 where template_id = 'tpl-1'",
+        )
+        .bind(serde_json::to_value(&template).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+        let service = service(
+            &pool,
+            json!({"template_storage": {"backend": "postgres"}}),
+        )
+        .await;
+
+        service
+            .publish(&Actor::System, &version_path(3))
+            .await
+            .unwrap();
+
+        let found = service
+            .template_lookup()
+            .get(&TemplateId::new(
+                &"ta-1".into(),
+                &DispatchType::Email,
+                &"tpl-1".into(),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.version(), Some(3));
+        assert_eq!(found.template(), &template);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn refuses_changes_to_templates_delivered_from_elsewhere(
+        pool: PgPool,
+    ) {
+        let service = service(
+            &pool,
+            json!({"template_directory": {"path": "templates"}}),
+        )
+        .await;
+
+        let published = service.publish(&Actor::System, &version_path(3)).await;
+        assert!(matches!(published, Err(ApiError::ReadOnlyTemplateStorage)));
+        let rolled_back =
+            service.rollback(&Actor::System, &version_path(1)).await;
+        assert!(matches!(
+            rolled_back,
+            Err(ApiError::ReadOnlyTemplateStorage)
+        ));
+    }
+}
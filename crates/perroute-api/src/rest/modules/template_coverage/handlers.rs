@@ -0,0 +1,20 @@
+use super::{
+    models::{TemplateCoverageModel, TemplateCoveragePath},
+    service::TemplateCoverageRestService,
+};
+use crate::rest::{
+    models::{resource::ResourceModelCollection, ApiResponse},
+    modules::ApiResult,
+};
+use actix_web::web::{Data, Path};
+use perroute_commons::types::actor::Actor;
+
+pub async fn query<RS: TemplateCoverageRestService>(
+    service: Data<RS>,
+    path: Path<TemplateCoveragePath>,
+) -> ApiResult<ResourceModelCollection<TemplateCoverageModel>> {
+    service
+        .query(&Actor::System, &path)
+        .await
+        .map(ApiResponse::ok)
+}
@@ -0,0 +1,16 @@
+mod handlers;
+pub mod models;
+pub mod service;
+
+use actix_web::{web, Scope};
+use service::TemplateCoverageRestService;
+
+const TEMPLATE_COVERAGE_RESOURCE_NAME: &str = "template_coverage";
+
+pub fn scope<RS: TemplateCoverageRestService + 'static>() -> Scope {
+    web::scope("/template_coverage").service(
+        web::resource("")
+            .name(TEMPLATE_COVERAGE_RESOURCE_NAME)
+            .route(web::get().to(handlers::query::<RS>)),
+    )
+}
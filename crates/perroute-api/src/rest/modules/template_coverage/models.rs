@@ -0,0 +1,116 @@
+use super::TEMPLATE_COVERAGE_RESOURCE_NAME;
+use crate::rest::{
+    models::{
+        link::{Relation, ResourcePath},
+        resource::{ResourceModel, ResourceModelCollection},
+    },
+    modules::business_unit::models::BusinessUnitPath,
+};
+use perroute_commons::types::{dispatch_type::DispatchType, id::Id, locale::Locale};
+use perroute_storage::models::template_assignment::TemplateAssignment;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use url::Url;
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct TemplateCoveragePath {
+    business_unit_id: String,
+}
+
+impl TemplateCoveragePath {
+    pub fn business_unit_id(&self) -> Id {
+        Id::from(&self.business_unit_id)
+    }
+
+    pub fn into_collection(
+        self,
+        coverage: Vec<TemplateCoverageModel>,
+    ) -> ResourceModelCollection<TemplateCoverageModel> {
+        let business_unit = BusinessUnitPath::new(&self.business_unit_id);
+        ResourceModelCollection::new(
+            coverage.into_iter().map(ResourceModel::new).collect(),
+        )
+        .with_link(Relation::Self_, self)
+        .with_link(Relation::Static("business_unit"), business_unit)
+    }
+}
+
+impl ResourcePath for TemplateCoveragePath {
+    fn url(&self, req: &actix_web::HttpRequest) -> Url {
+        req.url_for(TEMPLATE_COVERAGE_RESOURCE_NAME, [&self.business_unit_id])
+            .unwrap()
+    }
+}
+
+/// Locales translated by an assignment and, per dispatch type, which of them
+/// lack a published template and fall back to a less specific one.
+#[derive(Debug, Serialize)]
+pub struct TemplateCoverageModel {
+    template_assignment_id: String,
+    message_type_id: String,
+    locales: BTreeSet<Locale>,
+    templates: Vec<DispatchTypeCoverageModel>,
+}
+
+impl TemplateCoverageModel {
+    pub fn new(
+        assignment: &TemplateAssignment,
+        locales: BTreeSet<Locale>,
+        templates: Vec<DispatchTypeCoverageModel>,
+    ) -> Self {
+        TemplateCoverageModel {
+            template_assignment_id: assignment.id().to_string(),
+            message_type_id: assignment.message_type_id().to_string(),
+            locales,
+            templates,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DispatchTypeCoverageModel {
+    dispatch_type: DispatchType,
+    default: Option<TemplateStatusModel>,
+    localized: BTreeMap<Locale, TemplateStatusModel>,
+    missing: Vec<Locale>,
+}
+
+impl DispatchTypeCoverageModel {
+    /// A locale is missing when it has no published template of its own.
+    pub fn new(
+        dispatch_type: DispatchType,
+        locales: &BTreeSet<Locale>,
+        default: Option<TemplateStatusModel>,
+        localized: BTreeMap<Locale, TemplateStatusModel>,
+    ) -> Self {
+        let missing = locales
+            .iter()
+            .filter(|locale| {
+                !localized.get(*locale).is_some_and(|t| t.published)
+            })
+            .cloned()
+            .collect();
+
+        DispatchTypeCoverageModel {
+            dispatch_type,
+            default,
+            localized,
+            missing,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateStatusModel {
+    template_id: String,
+    published: bool,
+}
+
+impl TemplateStatusModel {
+    pub fn new(template_id: &Id, published: bool) -> Self {
+        TemplateStatusModel {
+            template_id: template_id.to_string(),
+            published,
+        }
+    }
+}
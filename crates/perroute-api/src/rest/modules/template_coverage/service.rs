@@ -0,0 +1,116 @@
+use super::models::{
+    DispatchTypeCoverageModel, TemplateCoverageModel, TemplateCoveragePath,
+    TemplateStatusModel,
+};
+use crate::rest::{
+    error::ApiError, service::RestService, ResourceModelCollectionResult,
+};
+use perroute_command_bus::CommandBus;
+use perroute_commons::types::{actor::Actor, dispatch_type::DispatchType, id::Id};
+use perroute_query_bus::{
+    queries::template_assignment::{
+        BusinessUnitTemplateAssignmentsQuery, QueryTemplateAssignmentsHandler,
+    },
+    QueryBus,
+};
+use perroute_storage::models::template_assignment::TemplateAssignment;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+};
+
+pub trait TemplateCoverageRestService {
+    /// Translation coverage of every template assignment of the business
+    /// unit.
+    fn query(
+        &self,
+        actor: &Actor,
+        path: &TemplateCoveragePath,
+    ) -> impl Future<Output = ResourceModelCollectionResult<TemplateCoverageModel>>;
+}
+
+impl<CB: CommandBus, QB: QueryBus> RestService<CB, QB> {
+    async fn template_status(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> Result<TemplateStatusModel, ApiError> {
+        let published = self
+            .template_repository()
+            .published(template_id, dispatch_type)
+            .await?
+            .is_some();
+
+        Ok(TemplateStatusModel::new(template_id, published))
+    }
+
+    async fn coverage(
+        &self,
+        assignment: &TemplateAssignment,
+    ) -> Result<TemplateCoverageModel, ApiError> {
+        let locales = assignment
+            .localized_templates()
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        let mut templates = vec![];
+        for dispatch_type in
+            [DispatchType::Email, DispatchType::Sms, DispatchType::Push]
+        {
+            let default = match assignment.template_id(&dispatch_type) {
+                Some(id) => {
+                    Some(self.template_status(id, &dispatch_type).await?)
+                }
+                None => None,
+            };
+
+            let mut localized = BTreeMap::new();
+            for (locale, ids) in assignment.localized_templates().iter() {
+                if let Some(id) = ids.template_id(&dispatch_type) {
+                    localized.insert(
+                        locale.clone(),
+                        self.template_status(id, &dispatch_type).await?,
+                    );
+                }
+            }
+
+            if default.is_some() || !localized.is_empty() {
+                templates.push(DispatchTypeCoverageModel::new(
+                    dispatch_type,
+                    &locales,
+                    default,
+                    localized,
+                ));
+            }
+        }
+
+        Ok(TemplateCoverageModel::new(assignment, locales, templates))
+    }
+}
+
+impl<CB: CommandBus, QB: QueryBus> TemplateCoverageRestService
+    for RestService<CB, QB>
+{
+    async fn query(
+        &self,
+        actor: &Actor,
+        path: &TemplateCoveragePath,
+    ) -> ResourceModelCollectionResult<TemplateCoverageModel> {
+        let query = BusinessUnitTemplateAssignmentsQuery::builder()
+            .business_unit_id(path.business_unit_id())
+            .build();
+
+        let assignments = self
+            .query_bus()
+            .execute::<_, QueryTemplateAssignmentsHandler, _>(actor, &query)
+            .await?;
+
+        let mut coverage = Vec::with_capacity(assignments.len());
+        for assignment in &assignments {
+            coverage.push(self.coverage(assignment).await?);
+        }
+
+        Ok(path.clone().into_collection(coverage))
+    }
+}
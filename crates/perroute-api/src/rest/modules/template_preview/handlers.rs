@@ -0,0 +1,20 @@
+use super::{
+    models::{TemplatePreviewModel, TemplatePreviewRequest},
+    service::TemplatePreviewRestService,
+};
+use crate::rest::{
+    models::{resource::ResourceModelCollection, ApiResponse},
+    modules::ApiResult,
+};
+use actix_web::web::{Data, Json};
+use perroute_commons::types::actor::Actor;
+
+pub async fn preview<RS: TemplatePreviewRestService>(
+    service: Data<RS>,
+    payload: Json<TemplatePreviewRequest>,
+) -> ApiResult<ResourceModelCollection<TemplatePreviewModel>> {
+    service
+        .preview(&Actor::System, &payload)
+        .await
+        .map(ApiResponse::ok)
+}
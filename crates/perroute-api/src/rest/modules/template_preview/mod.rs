@@ -0,0 +1,16 @@
+mod handlers;
+pub mod models;
+pub mod service;
+
+use actix_web::{web, Scope};
+use service::TemplatePreviewRestService;
+
+const TEMPLATE_PREVIEW_RESOURCE_NAME: &str = "template_preview";
+
+pub fn scope<RS: TemplatePreviewRestService + 'static>() -> Scope {
+    web::scope("/templates/preview").service(
+        web::resource("")
+            .name(TEMPLATE_PREVIEW_RESOURCE_NAME)
+            .route(web::post().to(handlers::preview::<RS>)),
+    )
+}
@@ -0,0 +1,115 @@
+use crate::rest::{error::ApiError, models::resource::ResourceModel};
+use perroute_commons::types::{
+    dispatch_type::DispatchType, id::Id, locale::Locale, Payload,
+};
+use perroute_template::{
+    render::RenderError,
+    repository::TemplateRepositoryError,
+    template::{NotRenderedTemplateState, RenderedTemplateState, Template},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Renders either the template published for the assignment, in the given
+/// locale when it is translated, or an inline template. Without a payload,
+/// every payload example of the message type is rendered.
+#[derive(Debug, Deserialize)]
+pub struct TemplatePreviewRequest {
+    business_unit_id: String,
+    message_type_id: String,
+    dispatch_type: DispatchType,
+    template_assignment_id: Option<String>,
+    locale: Option<String>,
+    template: Option<Value>,
+    payload: Option<Value>,
+}
+
+impl TemplatePreviewRequest {
+    pub fn business_unit_id(&self) -> Id {
+        Id::from(&self.business_unit_id)
+    }
+
+    pub fn message_type_id(&self) -> Id {
+        Id::from(&self.message_type_id)
+    }
+
+    pub fn dispatch_type(&self) -> &DispatchType {
+        &self.dispatch_type
+    }
+
+    pub fn template_assignment_id(&self) -> Option<Id> {
+        self.template_assignment_id.as_ref().map(Id::from)
+    }
+
+    pub fn locale(&self) -> Result<Option<Locale>, ApiError> {
+        Ok(self.locale.as_deref().map(Locale::try_from).transpose()?)
+    }
+
+    pub fn template(
+        &self,
+    ) -> Result<Option<Template<NotRenderedTemplateState>>, ApiError> {
+        self.template
+            .clone()
+            .map(|value| Template::from_value(&self.dispatch_type, value))
+            .transpose()
+            .map_err(|e| TemplateRepositoryError::from(e).into())
+    }
+
+    pub fn payload(&self) -> Option<Payload> {
+        self.payload.clone().map(Payload::new)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplatePreviewModel {
+    payload_example: Option<String>,
+    payload: Value,
+    rendered: Option<Value>,
+    error: Option<RenderErrorModel>,
+}
+
+impl TemplatePreviewModel {
+    pub fn new(
+        payload_example: Option<String>,
+        payload: &Payload,
+        result: Result<Template<RenderedTemplateState>, RenderError>,
+    ) -> Self {
+        let (rendered, error) = match result {
+            Ok(template) => (Some(template.to_value()), None),
+            Err(e) => (None, Some(RenderErrorModel::from(&e))),
+        };
+
+        TemplatePreviewModel {
+            payload_example,
+            payload: (**payload).clone(),
+            rendered,
+            error,
+        }
+    }
+}
+
+impl From<TemplatePreviewModel> for ResourceModel<TemplatePreviewModel> {
+    fn from(value: TemplatePreviewModel) -> Self {
+        ResourceModel::new(value)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderErrorModel {
+    part: Option<&'static str>,
+    message: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl From<&RenderError> for RenderErrorModel {
+    fn from(error: &RenderError) -> Self {
+        let position = error.position();
+        RenderErrorModel {
+            part: error.part(),
+            message: error.to_string(),
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+        }
+    }
+}
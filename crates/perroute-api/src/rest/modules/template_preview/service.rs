@@ -0,0 +1,183 @@
+use super::models::{TemplatePreviewModel, TemplatePreviewRequest};
+use crate::rest::{
+    error::ApiError, models::resource::ResourceModelCollection,
+    service::RestService, ResourceModelCollectionResult,
+};
+use perroute_command_bus::CommandBus;
+use perroute_commons::types::{actor::Actor, Payload};
+use perroute_query_bus::{
+    queries::template_preview::{
+        TemplatePreviewContext, TemplatePreviewHandler, TemplatePreviewQuery,
+    },
+    QueryBus,
+};
+use perroute_template::{
+    render::TemplateRenderContext,
+    repository::TemplateId,
+    template::{NotRenderedTemplateState, Template},
+};
+use serde_json::json;
+use std::future::Future;
+
+pub trait TemplatePreviewRestService {
+    fn preview(
+        &self,
+        actor: &Actor,
+        request: &TemplatePreviewRequest,
+    ) -> impl Future<Output = ResourceModelCollectionResult<TemplatePreviewModel>>;
+}
+
+impl<CB: CommandBus, QB: QueryBus> RestService<CB, QB> {
+    /// The inline template of the request, or the one delivered for the
+    /// assignment by the configured store.
+    async fn preview_template(
+        &self,
+        request: &TemplatePreviewRequest,
+        ctx: &TemplatePreviewContext,
+    ) -> Result<Template<NotRenderedTemplateState>, ApiError> {
+        if let Some(template) = request.template()? {
+            return Ok(template);
+        }
+
+        let assignment = ctx
+            .template_assignment()
+            .as_ref()
+            .ok_or(ApiError::BadRequest)?;
+        let locale = request.locale()?;
+        let (_, template_id) = assignment
+            .localized_template_id(request.dispatch_type(), locale.as_ref())
+            .ok_or(ApiError::NotFound)?;
+
+        self.template_lookup()
+            .get(&TemplateId::new(
+                assignment.id(),
+                request.dispatch_type(),
+                template_id,
+            ))
+            .await?
+            .map(|found| found.template().clone())
+            .ok_or(ApiError::NotFound)
+    }
+}
+
+impl<CB: CommandBus, QB: QueryBus> TemplatePreviewRestService
+    for RestService<CB, QB>
+{
+    async fn preview(
+        &self,
+        actor: &Actor,
+        request: &TemplatePreviewRequest,
+    ) -> ResourceModelCollectionResult<TemplatePreviewModel> {
+        let query = TemplatePreviewQuery::builder()
+            .business_unit_id(request.business_unit_id())
+            .message_type_id(request.message_type_id())
+            .maybe_template_assignment_id(request.template_assignment_id())
+            .build();
+
+        let ctx = self
+            .query_bus()
+            .execute::<_, TemplatePreviewHandler, _>(actor, &query)
+            .await?
+            .ok_or(ApiError::NotFound)?;
+
+        let template = self.preview_template(request, &ctx).await?;
+        let partials = self
+            .template_lookup()
+            .partials(&request.business_unit_id())
+            .await?;
+
+        let payloads = match request.payload() {
+            Some(payload) => vec![(None, payload)],
+            None if ctx.payload_examples().is_empty() => {
+                vec![(None, Payload::new(json!({})))]
+            }
+            None => ctx
+                .payload_examples()
+                .iter()
+                .map(|example| {
+                    (
+                        Some(example.name().to_string()),
+                        example.payload().clone(),
+                    )
+                })
+                .collect(),
+        };
+
+        let previews = payloads
+            .into_iter()
+            .map(|(name, payload)| {
+                let renderer = self.template_render_plugin().renderer(
+                    TemplateRenderContext::new(&payload, ctx.vars())
+                        .with_partials(&partials)
+                        .with_engine(template.engine()),
+                );
+                let result =
+                    template.render(renderer.as_ref()).and_then(|rendered| {
+                        self.render_limits().check(&rendered)?;
+                        Ok(rendered)
+                    });
+                TemplatePreviewModel::new(name, &payload, result).into()
+            })
+            .collect();
+
+        Ok(ResourceModelCollection::new(previews))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::service::tests::service;
+    use sqlx::PgPool;
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../../perroute-storage/fixtures",
+            scripts("messages", "template_assignments", "template_versions")
+        )
+    )]
+    async fn previews_the_template_delivered_for_the_assignment(pool: PgPool) {
+        let template =
+            Template::email("Hi {{payload.name}}", "<p>{{> footer}}</p>", "Hi");
+        sqlx::query(
+            "update template_versions set content = $1 where template_id = 'tpl-1' and version = 2",
+        )
+        .bind(serde_json::to_value(&template).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "update template_assignments set email_template_id = 'tpl-1' where id = 'ta-low'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"insert into template_partials values ('bu-1', 'footer', 'Bye', '"System"', now())"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let service = service(
+            &pool,
+            json!({"template_storage": {"backend": "postgres"}}),
+        )
+        .await;
+        let request: TemplatePreviewRequest = serde_json::from_value(json!({
+            "business_unit_id": "bu-1",
+            "message_type_id": "mt-1",
+            "dispatch_type": "Email",
+            "template_assignment_id": "ta-low",
+            "payload": {"name": "John"},
+        }))
+        .unwrap();
+
+        let previews = service.preview(&Actor::System, &request).await.unwrap();
+
+        let previews = format!("{previews:?}");
+        assert!(previews.contains("Hi John"), "{previews}");
+        assert!(previews.contains("<p>Bye</p>"), "{previews}");
+    }
+}
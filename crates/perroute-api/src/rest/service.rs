@@ -1,20 +1,51 @@
 use perroute_command_bus::CommandBus;
 use perroute_query_bus::QueryBus;
+use crate::rest::error::ApiError;
+use perroute_template::{
+    limits::RenderLimits,
+    render::TemplateRenderPlugin,
+    repository::{TemplateLookup, TemplateRepository},
+};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct RestService<CB, QB> {
     command_bus: CB,
     query_bus: QB,
+    template_repository: Arc<dyn TemplateRepository + Send + Sync>,
+    template_lookup: Arc<dyn TemplateLookup + Send + Sync>,
+    template_writes: bool,
+    template_render_plugin: Arc<dyn TemplateRenderPlugin + Send + Sync>,
+    render_limits: RenderLimits,
 }
 
 impl<CB: CommandBus, QB: QueryBus> RestService<CB, QB> {
-    pub fn new(command_bus: CB, query_bus: QB) -> Self {
+    pub fn new(
+        command_bus: CB,
+        query_bus: QB,
+        template_repository: Arc<dyn TemplateRepository + Send + Sync>,
+        template_lookup: Arc<dyn TemplateLookup + Send + Sync>,
+        template_render_plugin: Arc<dyn TemplateRenderPlugin + Send + Sync>,
+        render_limits: RenderLimits,
+    ) -> Self {
         Self {
             command_bus,
             query_bus,
+            template_repository,
+            template_lookup,
+            template_writes: false,
+            template_render_plugin,
+            render_limits,
         }
     }
 
+    /// Accepts changes to templates and partials. Only enabled when they are
+    /// delivered from the store the changes are written to.
+    pub fn with_template_writes(mut self, enabled: bool) -> Self {
+        self.template_writes = enabled;
+        self
+    }
+
     pub fn command_bus(&self) -> &CB {
         &self.command_bus
     }
@@ -22,4 +53,73 @@ impl<CB: CommandBus, QB: QueryBus> RestService<CB, QB> {
     pub fn query_bus(&self) -> &QB {
         &self.query_bus
     }
+
+    pub fn template_repository(
+        &self,
+    ) -> &(dyn TemplateRepository + Send + Sync) {
+        self.template_repository.as_ref()
+    }
+
+    /// The store templates are delivered from.
+    pub fn template_lookup(&self) -> &(dyn TemplateLookup + Send + Sync) {
+        self.template_lookup.as_ref()
+    }
+
+    pub fn ensure_template_writes(&self) -> Result<(), ApiError> {
+        match self.template_writes {
+            true => Ok(()),
+            false => Err(ApiError::ReadOnlyTemplateStorage),
+        }
+    }
+
+    pub fn template_render_plugin(
+        &self,
+    ) -> &(dyn TemplateRenderPlugin + Send + Sync) {
+        self.template_render_plugin.as_ref()
+    }
+
+    pub fn render_limits(&self) -> &RenderLimits {
+        &self.render_limits
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use perroute_command_bus::create_command_bus;
+    use perroute_commons::configuration::settings::Settings;
+    use perroute_query_bus::create_query_bus;
+    use perroute_storage::active_record::datasource::DataSource;
+    use perroute_template::{
+        render::handlebars::HandlebarsPlugin,
+        repository::{self, postgres::PgTemplateRepository},
+    };
+    use serde_json::Value;
+    use sqlx::PgPool;
+
+    /// A service wired to the database as the API binary does, for the
+    /// given settings.
+    pub(crate) async fn service(
+        pool: &PgPool,
+        settings: Value,
+    ) -> RestService<impl CommandBus, impl QueryBus> {
+        let settings: Settings = serde_json::from_value(settings).unwrap();
+        let datasource = DataSource::new(pool.clone());
+
+        RestService::new(
+            create_command_bus(
+                datasource.clone(),
+                perroute_connectors::plugin_repository(),
+                &settings,
+            ),
+            create_query_bus(datasource.clone()),
+            Arc::new(PgTemplateRepository::new(datasource.clone())),
+            repository::configured(&settings, &datasource)
+                .await
+                .unwrap(),
+            Arc::new(HandlebarsPlugin::new()),
+            RenderLimits::default(),
+        )
+        .with_template_writes(settings.delivers_templates_from_postgres())
+    }
 }
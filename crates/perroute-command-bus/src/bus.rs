@@ -4,8 +4,15 @@ use perroute_commons::{
     types::{actor::Actor, Timestamp},
 };
 use perroute_connectors::ProviderPluginRepository;
-use perroute_storage::active_record::datasource::{
-    Connection, DataSource, NonTransactionalDataSource,
+use perroute_storage::{
+    active_record::{
+        datasource::{
+            Connection, DataSource, NonTransactionalDataSource,
+            TransactionalDataSource,
+        },
+        ActiveRecord,
+    },
+    models::event::DbEvent,
 };
 use std::{
     any::{Any, TypeId},
@@ -115,25 +122,20 @@ impl CommandBus for DefaultCommandBus {
             &self.plugin_repository,
         );
 
-        match handler.handle(&ctx).await {
-            Ok(output) => {
-                if let Some(event) = H::into_event(command, &output) {
-                    //DbEvent::try_from(event.to_event(actor, created_at));
-                    println!("Event: {:?}", event);
+        let output = match handler.handle(&ctx).await {
+            Ok(output) => match H::into_event(command, &output) {
+                Some(event) => {
+                    save_event(&ds_tx, event, actor, ctx.timestamp())
+                        .await
+                        .map(|_| output)
                 }
+                None => Ok(output),
+            },
+            Err(e) => Err(e),
+        };
 
-                // EventRepository::save(
-                //     &tx,
-                //     DbEvent::try_from(output.event.to_event(actor, created_at))
-                //         .tap_err(|e| {
-                //             log::error!(
-                //                 "Failed to convert event to DbEvent: {e}"
-                //             )
-                //         })?,
-                // )
-                // .await
-                // .tap_err(|e| log::error!("Failed to persist event: {}", e))?;
-
+        match output {
+            Ok(output) => {
                 ds_tx.commit().await.tap_err(|e| {
                     log::error!("Failed to commit transaction: {e}")
                 })?;
@@ -150,6 +152,24 @@ impl CommandBus for DefaultCommandBus {
     }
 }
 
+/// Writes the event to the outbox in the command's transaction, so it is
+/// published only if the command commits.
+async fn save_event<E: ApplicationEvent>(
+    tx: &DataSource<TransactionalDataSource>,
+    event: E,
+    actor: &Actor,
+    timestamp: &Timestamp,
+) -> CommandBusResult<()> {
+    let event = DbEvent::try_from(event.to_event(actor, timestamp))
+        .tap_err(|e| log::error!("Failed to convert event to DbEvent: {e}"))?;
+
+    DbEvent::create(tx, event)
+        .await
+        .tap_err(|e| log::error!("Failed to persist event: {e}"))?;
+
+    Ok(())
+}
+
 pub struct CommandBusContext<'c, C, CMD> {
     conn: &'c C,
     timestamp: Timestamp,
@@ -10,7 +10,7 @@ use perroute_commons::{
         ProviderId,
     },
 };
-use perroute_connectors::ProviderPluginRepository;
+use perroute_connectors::{types::ConfigurationError, ProviderPluginRepository};
 use perroute_storage::{
     active_record::{
         business_unit::BusinessUnitQuery, channel::CreateChannel,
@@ -27,6 +27,9 @@ pub enum CreateChannelCommandError {
 
     #[error("Plugin {0} not found")]
     PluginNotFound(ProviderId),
+
+    #[error("{0}")]
+    InvalidConfiguration(#[from] ConfigurationError),
 }
 
 impl_command!(CreateChannelCommand, {
@@ -45,15 +48,22 @@ pub struct CreateChannelCommandHandler;
 impl CreateChannelCommandHandler {
     fn check_plugin(
         plugin_repository: &ProviderPluginRepository,
-        id: &ProviderId,
+        command: &CreateChannelCommand,
     ) -> CommandHandlerResult<()> {
-        match plugin_repository.get(id) {
-            Some(_) => Ok(()),
-            None => {
-                Err(CreateChannelCommandError::PluginNotFound(id.clone())
-                    .into())
-            }
-        }
+        let plugin = plugin_repository.get(&command.provider_id).ok_or(
+            CreateChannelCommandError::PluginNotFound(
+                command.provider_id.clone(),
+            ),
+        )?;
+
+        plugin
+            .validate_configuration(
+                &command.dispatch_type,
+                &command.configuration,
+            )
+            .map_err(CreateChannelCommandError::from)?;
+
+        Ok(())
     }
 
     async fn check_bu_existence<C: AsRef<Connection>>(
@@ -104,10 +114,7 @@ impl CommandHandler for CreateChannelCommandHandler {
         )
         .await?;
 
-        Self::check_plugin(
-            ctx.plugin_repository(),
-            &ctx.command().provider_id,
-        )?;
+        Self::check_plugin(ctx.plugin_repository(), ctx.command())?;
 
         Channel::create(ctx.datasource(), ctx.into())
             .await
@@ -5,8 +5,9 @@ use crate::{
 };
 use perroute_commons::{
     events::ChannelUpdatedEvent,
-    types::{id::Id, name::Name, Configuration},
+    types::{id::Id, name::Name, Configuration, ProviderId},
 };
+use perroute_connectors::types::ConfigurationError;
 use perroute_storage::{
     active_record::{channel::ChannelQuery, ActiveRecord},
     models::channel::Channel,
@@ -17,6 +18,12 @@ use tap::TapFallible;
 pub enum UpdateChannelCommandError {
     #[error("Channel not found")]
     ChannelNotFound,
+
+    #[error("Plugin {0} not found")]
+    PluginNotFound(ProviderId),
+
+    #[error("{0}")]
+    InvalidConfiguration(#[from] ConfigurationError),
 }
 
 impl_command!(UpdateChannelCommand, {
@@ -61,6 +68,14 @@ impl CommandHandler for UpdateChannelCommandHandler {
         .ok_or(UpdateChannelCommandError::ChannelNotFound)?;
 
         if let Some(c) = ctx.command().configuration.as_ref() {
+            ctx.plugin_repository()
+                .get(channel.provider_id())
+                .ok_or(UpdateChannelCommandError::PluginNotFound(
+                    channel.provider_id().clone(),
+                ))?
+                .validate_configuration(channel.dispatch_type(), c)
+                .map_err(UpdateChannelCommandError::from)?;
+
             channel = channel.set_configuration(c);
         }
 
@@ -0,0 +1,216 @@
+use crate::{
+    bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
+    commands::Command,
+    impl_command,
+};
+use perroute_commons::{
+    events::MessageCreatedEvent,
+    types::{id::Id, MessageStatus},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection, dead_letter::DeadLetterQuery,
+        message::MessageQuery, ActiveRecord,
+    },
+    models::{dead_letter::DeadLetter, message::Message},
+};
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RedriveDeadLetterCommandError {
+    #[error("Dead letter not found")]
+    DeadLetterNotFound,
+
+    #[error("Dead letter {0} was already redriven")]
+    AlreadyRedriven(Id),
+
+    #[error(
+        "Dead letter {0} is not bound to a message and cannot be redriven"
+    )]
+    NotRedrivable(Id),
+
+    #[error("Message {0} not found")]
+    MessageNotFound(Id),
+}
+
+impl_command!(RedriveDeadLetterCommand, {
+    dead_letter_id: Id,
+});
+
+/// Resets the dead-lettered message so it is dispatched from scratch and
+/// publishes it again through a [`MessageCreatedEvent`].
+pub struct RedriveDeadLetterCommandHandler;
+
+impl CommandHandler for RedriveDeadLetterCommandHandler {
+    type Command = RedriveDeadLetterCommand;
+    type Output = (DeadLetter, Message);
+    type E = MessageCreatedEvent;
+
+    fn into_event(
+        _command: &Self::Command,
+        (_, message): &Self::Output,
+    ) -> Option<Self::E> {
+        Some(MessageCreatedEvent::from(message))
+    }
+
+    async fn handle<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, Self::Command>,
+    ) -> CommandHandlerResult<Self::Output> {
+        let dead_letter_id = &ctx.command().dead_letter_id;
+        let dead_letter = DeadLetter::fetch_optional(
+            ctx.datasource(),
+            DeadLetterQuery::ById(dead_letter_id),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch dead letter: {e}"))?
+        .ok_or(RedriveDeadLetterCommandError::DeadLetterNotFound)?;
+
+        let message_id = dead_letter.message_id().clone().ok_or(
+            RedriveDeadLetterCommandError::NotRedrivable(
+                dead_letter_id.clone(),
+            ),
+        )?;
+
+        if dead_letter.redriven_at().is_some() {
+            return Err(RedriveDeadLetterCommandError::AlreadyRedriven(
+                dead_letter_id.clone(),
+            )
+            .into());
+        }
+
+        let message = Message::fetch_optional(
+            ctx.datasource(),
+            MessageQuery::ById(&message_id),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch message: {e}"))?
+        .ok_or(RedriveDeadLetterCommandError::MessageNotFound(message_id))?
+        .set_status(MessageStatus::Received)
+        .set_attempts(0)
+        .set_updated_at(ctx.timestamp())
+        .update(ctx.datasource())
+        .await
+        .tap_err(|e| log::error!("Failed to reset message: {e}"))?;
+
+        let dead_letter = dead_letter
+            .set_redriven_at(Some(ctx.timestamp().clone()))
+            .update(ctx.datasource())
+            .await
+            .tap_err(|e| log::error!("Failed to update dead letter: {e}"))?;
+
+        Ok((dead_letter, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_command_bus, CommandBus, CommandBusError};
+    use perroute_commons::{configuration::settings::Settings, types::actor::Actor};
+    use perroute_connectors::plugin_repository;
+    use perroute_storage::active_record::datasource::DataSource;
+    use sqlx::PgPool;
+
+    async fn redrive(
+        pool: PgPool,
+        dead_letter_id: &str,
+    ) -> Result<(DeadLetter, Message), CommandBusError> {
+        create_command_bus(
+            DataSource::new(pool),
+            plugin_repository(),
+            &Settings::default(),
+        )
+        .execute::<_, RedriveDeadLetterCommandHandler, _>(
+            &Actor::System,
+            &RedriveDeadLetterCommand::builder()
+                .dead_letter_id(Id::from(dead_letter_id))
+                .build(),
+        )
+        .await
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "dead_letters")
+        )
+    )]
+    async fn redrive_resets_the_message_and_marks_the_dead_letter(
+        pool: PgPool,
+    ) {
+        let (dead_letter, message) =
+            redrive(pool.clone(), "dl-bound").await.unwrap();
+
+        assert!(dead_letter.redriven_at().is_some());
+        assert_eq!(message.status(), &MessageStatus::Received);
+        assert_eq!(message.attempts(), &0);
+
+        let events: i64 = sqlx::query_scalar(
+            "select count(*) from event_messages where entity_id = 'msg-1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(events, 1);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "dead_letters")
+        )
+    )]
+    async fn redrive_rejects_dead_letters_without_a_message(pool: PgPool) {
+        let error = redrive(pool, "dl-unbound").await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandBusError::RedriveDeadLetterCommandError(
+                RedriveDeadLetterCommandError::NotRedrivable(_)
+            )
+        ));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "dead_letters")
+        )
+    )]
+    async fn redrive_rejects_dead_letters_already_redriven(pool: PgPool) {
+        let error = redrive(pool, "dl-redriven").await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandBusError::RedriveDeadLetterCommandError(
+                RedriveDeadLetterCommandError::AlreadyRedriven(_)
+            )
+        ));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "dead_letters")
+        )
+    )]
+    async fn redrive_rejects_unknown_dead_letters(pool: PgPool) {
+        let error = redrive(pool, "dl-missing").await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandBusError::RedriveDeadLetterCommandError(
+                RedriveDeadLetterCommandError::DeadLetterNotFound
+            )
+        ));
+    }
+}
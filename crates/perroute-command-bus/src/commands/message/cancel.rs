@@ -0,0 +1,67 @@
+use crate::{
+    bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
+    commands::Command,
+    impl_command,
+};
+use perroute_commons::{
+    events::MessageCreatedEvent,
+    types::{id::Id, MessageStatus},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection, message::MessageQuery, ActiveRecord,
+    },
+    models::message::Message,
+};
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CancelMessageCommandError {
+    #[error("Message not found")]
+    MessageNotFound,
+
+    #[error("Message {0} is not scheduled")]
+    NotScheduled(Id),
+}
+
+impl_command!(CancelMessageCommand, {
+    message_id: Id,
+});
+
+/// Cancels a message still waiting to be released by the scheduler, so it
+/// is never dispatched.
+pub struct CancelMessageCommandHandler;
+
+impl CommandHandler for CancelMessageCommandHandler {
+    type Command = CancelMessageCommand;
+    type Output = Message;
+    type E = MessageCreatedEvent;
+
+    async fn handle<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, Self::Command>,
+    ) -> CommandHandlerResult<Self::Output> {
+        let message_id = &ctx.command().message_id;
+        let message = Message::fetch_optional(
+            ctx.datasource(),
+            MessageQuery::ById(message_id),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch message: {e}"))?
+        .ok_or(CancelMessageCommandError::MessageNotFound)?;
+
+        if *message.status() != MessageStatus::Scheduled {
+            return Err(CancelMessageCommandError::NotScheduled(
+                message_id.clone(),
+            )
+            .into());
+        }
+
+        Ok(message
+            .set_status(MessageStatus::Cancelled)
+            .set_updated_at(ctx.timestamp())
+            .update(ctx.datasource())
+            .await
+            .tap_err(|e| log::error!("Failed to cancel message: {e}"))?)
+    }
+}
@@ -1,45 +1,543 @@
 use crate::{
     bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
-    commands::{Command},
+    commands::Command,
     impl_command,
 };
 use perroute_commons::{
     events::MessageCreatedEvent,
     types::{
-        dispatch_type::DispatchType, id::Id, recipient::Recipient, Payload,
-        Tags, Timestamp,
+        dispatch_type::DispatchType,
+        id::Id,
+        idempotency_key::IdempotencyKey,
+        locale::Locale,
+        recipient::Recipient,
+        schema::{InvalidSchemaError, PayloadValidationError, SchemaViolation},
+        MessageStatus, Payload, Tags, Timestamp,
     },
 };
-use perroute_storage::{models::message::Message};
+use perroute_storage::{
+    active_record::{
+        business_unit::BusinessUnitQuery,
+        datasource::Connection,
+        message::{CreateMessage, IdempotentMessageActiveRecord, MessageQuery},
+        message_type::MessageTypeQuery,
+        ActiveRecord,
+    },
+    models::{
+        business_unit::BusinessUnit, message::Message,
+        message_type::MessageType,
+    },
+};
+use std::time::Duration;
+use tap::TapFallible;
+
+/// How long an idempotency key keeps pointing at the message it created,
+/// unless [configured](CreateMessageCommandHandler::with_key_retention).
+/// Past this window the key is released and may create a new message.
+pub const DEFAULT_IDEMPOTENCY_KEY_RETENTION: Duration =
+    Duration::from_secs(24 * 60 * 60);
 
 #[derive(Debug, thiserror::Error)]
-pub enum CreateMessageCommandError {}
+pub enum CreateMessageCommandError {
+    #[error("A message with key {0} is already being created")]
+    DuplicateKey(IdempotencyKey),
+
+    #[error("Message type {0} not found")]
+    MessageTypeNotFound(Id),
+
+    #[error("Message type {0} is disabled")]
+    MessageTypeDisabled(Id),
+
+    #[error("Business unit {0} not found")]
+    BusinessUnitNotFound(Id),
+
+    #[error("Recipient {0} does not match dispatch type {1}")]
+    RecipientMismatch(DispatchType, DispatchType),
+
+    #[error("Message type schema is invalid: {0}")]
+    InvalidSchema(InvalidSchemaError),
+
+    #[error("Payload does not match the message type schema")]
+    InvalidPayload(Vec<SchemaViolation>),
+}
+
+impl From<PayloadValidationError> for CreateMessageCommandError {
+    fn from(value: PayloadValidationError) -> Self {
+        match value {
+            PayloadValidationError::InvalidSchema(e) => Self::InvalidSchema(e),
+            PayloadValidationError::Violations(violations) => {
+                Self::InvalidPayload(violations)
+            }
+        }
+    }
+}
 
 impl_command!(CreateMessageCommand, {
      message_id: Id,
+     key: Option<IdempotencyKey>,
      message_type_id: Id,
      business_unit_id: Id,
      payload: Payload,
      dispatch_type: DispatchType,
      recipient: Recipient,
+     locale: Option<Locale>,
      scheduled_at: Option<Timestamp>,
      tags: Tags,
 });
 
-pub struct CreateMessageCommandHandler;
+pub struct CreateMessageCommandHandler {
+    key_retention: Duration,
+}
+
+impl Default for CreateMessageCommandHandler {
+    fn default() -> Self {
+        Self {
+            key_retention: DEFAULT_IDEMPOTENCY_KEY_RETENTION,
+        }
+    }
+}
+
+impl CreateMessageCommandHandler {
+    pub fn with_key_retention(mut self, key_retention: Duration) -> Self {
+        self.key_retention = key_retention;
+        self
+    }
+
+    /// Message previously created with the command's key, if it is still
+    /// within the retention window. An expired key is released so the unique
+    /// index lets the new message take it.
+    async fn find_by_key<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, CreateMessageCommand>,
+        key: &IdempotencyKey,
+    ) -> CommandHandlerResult<Option<Message>> {
+        let cmd = ctx.command();
+        let Some(message) = Message::fetch_optional(
+            ctx.datasource(),
+            MessageQuery::ByKey(&cmd.business_unit_id, key),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch message by key: {e}"))?
+        else {
+            return Ok(None);
+        };
+
+        let age = ctx
+            .timestamp()
+            .signed_duration_since(**message.created_at())
+            .to_std()
+            .unwrap_or_default();
+        if age < self.key_retention {
+            return Ok(Some(message));
+        }
+
+        message
+            .set_key(None)
+            .update(ctx.datasource())
+            .await
+            .tap_err(|e| log::error!("Failed to release message key: {e}"))?;
+
+        Ok(None)
+    }
+
+    async fn message_type<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, CreateMessageCommand>,
+    ) -> CommandHandlerResult<MessageType> {
+        let message_type_id = &ctx.command().message_type_id;
+        let message_type = MessageType::fetch_optional(
+            ctx.datasource(),
+            MessageTypeQuery::ById(message_type_id),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch message type: {e}"))?
+        .ok_or(CreateMessageCommandError::MessageTypeNotFound(
+            message_type_id.clone(),
+        ))?;
+
+        match message_type.enabled() {
+            true => Ok(message_type),
+            false => Err(CreateMessageCommandError::MessageTypeDisabled(
+                message_type_id.clone(),
+            )
+            .into()),
+        }
+    }
+
+    async fn check_business_unit<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, CreateMessageCommand>,
+    ) -> CommandHandlerResult<()> {
+        let business_unit_id = &ctx.command().business_unit_id;
+        let exists = BusinessUnit::exists(
+            ctx.datasource(),
+            BusinessUnitQuery::ById(business_unit_id),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch business unit: {e}"))?;
+
+        match exists {
+            true => Ok(()),
+            false => Err(CreateMessageCommandError::BusinessUnitNotFound(
+                business_unit_id.clone(),
+            )
+            .into()),
+        }
+    }
+}
+
+fn check_recipient(cmd: &CreateMessageCommand) -> CommandHandlerResult<()> {
+    match cmd.recipient.dispatch_type() {
+        dispatch_type if dispatch_type == cmd.dispatch_type => Ok(()),
+        dispatch_type => Err(CreateMessageCommandError::RecipientMismatch(
+            dispatch_type,
+            cmd.dispatch_type,
+        )
+        .into()),
+    }
+}
 
 impl CommandHandler for CreateMessageCommandHandler {
     type Command = CreateMessageCommand;
     type Output = Message;
     type E = MessageCreatedEvent;
 
-    async fn handle<
-        C: AsRef<perroute_storage::active_record::datasource::Connection>,
-    >(
-        &self,
+    /// Replays of an idempotency key return the original message, which was
+    /// already published when it was created. Scheduled messages are
+    /// published by the scheduler once they are due.
+    fn into_event(
+        command: &Self::Command,
+        output: &Self::Output,
+    ) -> Option<Self::E> {
+        (output.id() == &command.message_id
+            && *output.status() != MessageStatus::Scheduled)
+            .then(|| MessageCreatedEvent::from(output))
+    }
 
+    async fn handle<C: AsRef<Connection>>(
+        &self,
         ctx: &CommandBusContext<'_, C, Self::Command>,
     ) -> CommandHandlerResult<Self::Output> {
-        todo!()
+        let cmd = ctx.command();
+
+        if let Some(key) = &cmd.key {
+            Message::lock_key(ctx.datasource(), &cmd.business_unit_id, key)
+                .await
+                .tap_err(|e| log::error!("Failed to lock message key: {e}"))?;
+            if let Some(message) = self.find_by_key(ctx, key).await? {
+                log::info!(
+                    "Message {} already created with key {key}",
+                    message.id()
+                );
+                return Ok(message);
+            }
+        }
+
+        check_recipient(cmd)?;
+        self.check_business_unit(ctx).await?;
+        let message_type = self.message_type(ctx).await?;
+        message_type
+            .schema()
+            .validate(&cmd.payload)
+            .map_err(CreateMessageCommandError::from)?;
+
+        let create = CreateMessage::builder()
+            .id(&cmd.message_id)
+            .maybe_key(cmd.key.clone())
+            .message_type_id(&cmd.message_type_id)
+            .business_unit_id(&cmd.business_unit_id)
+            .payload(cmd.payload.clone())
+            .recipient(cmd.recipient.clone())
+            .maybe_locale(cmd.locale.clone())
+            .dispatch_type(cmd.dispatch_type)
+            .status(MessageStatus::initial(
+                cmd.scheduled_at.as_ref(),
+                ctx.timestamp(),
+            ))
+            .tags(cmd.tags.clone())
+            .maybe_scheduled_at(cmd.scheduled_at.clone())
+            .timestamp(ctx.timestamp())
+            .build();
+
+        Message::create(ctx.datasource(), create)
+            .await
+            .map_err(|e| match &cmd.key {
+                Some(key) if e.is_unique_violation() => {
+                    CreateMessageCommandError::DuplicateKey(key.clone()).into()
+                }
+                _ => e.into(),
+            })
+            .tap_err(|e| log::error!("Failed to create message: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_command_bus, CommandBus, CommandBusError};
+    use perroute_commons::{
+        configuration::settings::Settings,
+        types::{
+            actor::Actor,
+            recipient::{EmailAddress, EmailRecipient},
+        },
+    };
+    use perroute_connectors::plugin_repository;
+    use perroute_storage::active_record::datasource::DataSource;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use std::collections::HashSet;
+
+    fn command(message_type_id: &str, payload: Value) -> CreateMessageCommand {
+        let recipient = EmailRecipient::new(
+            EmailAddress::try_from("user@example.com").unwrap(),
+        );
+        CreateMessageCommand::builder()
+            .message_id(Id::new())
+            .message_type_id(Id::from(message_type_id))
+            .business_unit_id(Id::from("bu-1"))
+            .payload(Payload::new(payload))
+            .dispatch_type(DispatchType::Email)
+            .recipient(Recipient::Email(recipient))
+            .tags(Tags::from(HashSet::new()))
+            .build()
+    }
+
+    async fn execute(
+        bus: &impl CommandBus,
+        command: &CreateMessageCommand,
+    ) -> Result<Message, CommandBusError> {
+        bus.execute::<_, CreateMessageCommandHandler, _>(
+            &Actor::System,
+            command,
+        )
+        .await
+    }
+
+    async fn create(
+        pool: PgPool,
+        command: &CreateMessageCommand,
+    ) -> Result<Message, CommandBusError> {
+        execute(
+            &create_command_bus(
+                DataSource::new(pool),
+                plugin_repository(),
+                &Settings::default(),
+            ),
+            command,
+        )
+        .await
+    }
+
+    async fn created_events(pool: &PgPool) -> i64 {
+        sqlx::query_scalar(
+            "select count(*) from event_messages where event_type = 'MessageCreated'",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    fn error(
+        result: Result<Message, CommandBusError>,
+    ) -> CreateMessageCommandError {
+        match result {
+            Err(CommandBusError::CreateMessageCommandError(e)) => e,
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "message_types")
+        )
+    )]
+    async fn create_persists_the_message_and_publishes_it(pool: PgPool) {
+        let command = command("mt-schema", json!({ "name": "Ana" }));
+
+        let message = create(pool.clone(), &command).await.unwrap();
+
+        assert_eq!(message.id(), &command.message_id);
+        assert_eq!(message.status(), &MessageStatus::Received);
+        assert_eq!(created_events(&pool).await, 1);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "message_types")
+        )
+    )]
+    async fn create_reports_payload_violations_with_their_paths(pool: PgPool) {
+        let command = command("mt-schema", json!({ "name": 42 }));
+
+        let result = create(pool.clone(), &command).await;
+
+        let CreateMessageCommandError::InvalidPayload(violations) =
+            error(result)
+        else {
+            panic!("expected payload violations");
+        };
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path(), "/name");
+        assert_eq!(created_events(&pool).await, 0);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "message_types")
+        )
+    )]
+    async fn create_rejects_unknown_or_disabled_message_types(pool: PgPool) {
+        let missing =
+            create(pool.clone(), &command("mt-missing", json!({}))).await;
+        let disabled = create(pool, &command("mt-disabled", json!({}))).await;
+
+        assert!(matches!(
+            error(missing),
+            CreateMessageCommandError::MessageTypeNotFound(_)
+        ));
+        assert!(matches!(
+            error(disabled),
+            CreateMessageCommandError::MessageTypeDisabled(_)
+        ));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "message_types")
+        )
+    )]
+    async fn create_rejects_unknown_business_units(pool: PgPool) {
+        let mut command = command("mt-1", json!({}));
+        command.business_unit_id = Id::from("bu-missing");
+
+        assert!(matches!(
+            error(create(pool, &command).await),
+            CreateMessageCommandError::BusinessUnitNotFound(_)
+        ));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages", "message_types")
+        )
+    )]
+    async fn create_rejects_recipients_of_another_dispatch_type(pool: PgPool) {
+        let mut command = command("mt-1", json!({}));
+        command.dispatch_type = DispatchType::Sms;
+
+        assert!(matches!(
+            error(create(pool, &command).await),
+            CreateMessageCommandError::RecipientMismatch(
+                DispatchType::Email,
+                DispatchType::Sms
+            )
+        ));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages")
+        )
+    )]
+    async fn concurrent_creates_with_the_same_key_return_the_original(
+        pool: PgPool,
+    ) {
+        let bus = create_command_bus(
+            DataSource::new(pool.clone()),
+            plugin_repository(),
+            &Settings::default(),
+        );
+        let mut first = command("mt-1", json!({}));
+        first.key = Some(IdempotencyKey::try_from("order-42").unwrap());
+        let mut second = command("mt-1", json!({}));
+        second.key = first.key.clone();
+
+        let (first, second) = futures::future::join(
+            execute(&bus, &first),
+            execute(&bus, &second),
+        )
+        .await;
+
+        assert_eq!(first.unwrap().id(), second.unwrap().id());
+        assert_eq!(created_events(&pool).await, 1);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages")
+        )
+    )]
+    async fn keys_are_released_after_the_configured_retention(pool: PgPool) {
+        let settings: Settings = serde_json::from_value(json!({
+            "messages": {"idempotency_key_retention": 60}
+        }))
+        .unwrap();
+        let bus = create_command_bus(
+            DataSource::new(pool.clone()),
+            plugin_repository(),
+            &settings,
+        );
+        let mut first = command("mt-1", json!({}));
+        first.key = Some(IdempotencyKey::try_from("order-42").unwrap());
+        let mut replay = command("mt-1", json!({}));
+        replay.key = first.key.clone();
+        let mut late = command("mt-1", json!({}));
+        late.key = first.key.clone();
+
+        let first = execute(&bus, &first).await.unwrap();
+        let replay = execute(&bus, &replay).await.unwrap();
+        assert_eq!(replay.id(), first.id());
+
+        sqlx::query(
+            "update messages set created_at = created_at - interval '2 minutes' where id = $1",
+        )
+        .bind(first.id())
+        .execute(&pool)
+        .await
+        .unwrap();
+        let late = execute(&bus, &late).await.unwrap();
+        assert_ne!(late.id(), first.id());
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("messages")
+        )
+    )]
+    async fn scheduled_messages_are_not_published_when_created(pool: PgPool) {
+        let mut command = command("mt-1", json!({}));
+        command.scheduled_at = Some(Timestamp::from(
+            *Timestamp::now() + Duration::from_secs(5 * 60),
+        ));
+
+        let message = create(pool.clone(), &command).await.unwrap();
+
+        assert_eq!(*message.status(), MessageStatus::Scheduled);
+        assert_eq!(created_events(&pool).await, 0);
     }
 }
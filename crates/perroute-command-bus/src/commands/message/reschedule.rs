@@ -0,0 +1,75 @@
+use crate::{
+    bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
+    commands::Command,
+    impl_command,
+};
+use perroute_commons::{
+    events::MessageCreatedEvent,
+    types::{id::Id, MessageStatus, Timestamp},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection, message::MessageQuery, ActiveRecord,
+    },
+    models::message::Message,
+};
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RescheduleMessageCommandError {
+    #[error("Message not found")]
+    MessageNotFound,
+
+    #[error("Message {0} is not scheduled")]
+    NotScheduled(Id),
+
+    #[error("Scheduled date must be in the future")]
+    ScheduledAtInPast,
+}
+
+impl_command!(RescheduleMessageCommand, {
+    message_id: Id,
+    scheduled_at: Timestamp,
+});
+
+/// Moves a message still waiting to be released by the scheduler to a new
+/// date.
+pub struct RescheduleMessageCommandHandler;
+
+impl CommandHandler for RescheduleMessageCommandHandler {
+    type Command = RescheduleMessageCommand;
+    type Output = Message;
+    type E = MessageCreatedEvent;
+
+    async fn handle<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, Self::Command>,
+    ) -> CommandHandlerResult<Self::Output> {
+        let cmd = ctx.command();
+        if *cmd.scheduled_at <= **ctx.timestamp() {
+            return Err(RescheduleMessageCommandError::ScheduledAtInPast.into());
+        }
+
+        let message = Message::fetch_optional(
+            ctx.datasource(),
+            MessageQuery::ById(&cmd.message_id),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch message: {e}"))?
+        .ok_or(RescheduleMessageCommandError::MessageNotFound)?;
+
+        if *message.status() != MessageStatus::Scheduled {
+            return Err(RescheduleMessageCommandError::NotScheduled(
+                cmd.message_id.clone(),
+            )
+            .into());
+        }
+
+        Ok(message
+            .set_scheduled_at(Some(cmd.scheduled_at.clone()))
+            .set_updated_at(ctx.timestamp())
+            .update(ctx.datasource())
+            .await
+            .tap_err(|e| log::error!("Failed to reschedule message: {e}"))?)
+    }
+}
@@ -1,8 +1,10 @@
 pub mod business_unit;
 pub mod channel;
+pub mod dead_letter;
 pub mod message;
 pub mod message_type;
 pub mod route;
+pub mod template;
 pub mod template_assignment;
 
 pub trait Command {}
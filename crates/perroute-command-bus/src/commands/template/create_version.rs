@@ -0,0 +1,158 @@
+use crate::{
+    bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
+    commands::Command,
+    impl_command,
+};
+use perroute_commons::{
+    events::TemplateVersionCreatedEvent,
+    types::{dispatch_type::DispatchType, id::Id},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection,
+        template_version::{
+            CreateTemplateVersion, TemplateVersionActiveRecord,
+        },
+        ActiveRecord,
+    },
+    models::template_version::TemplateVersion,
+};
+use serde_json::Value;
+use tap::TapFallible;
+
+impl_command!(CreateTemplateVersionCommand, {
+    template_id: Id,
+    dispatch_type: DispatchType,
+    content: Value,
+});
+
+/// Saves the content as a new draft numbered after the latest version of
+/// the template. The content is expected to be validated by the caller.
+pub struct CreateTemplateVersionCommandHandler;
+
+impl CommandHandler for CreateTemplateVersionCommandHandler {
+    type Command = CreateTemplateVersionCommand;
+    type Output = TemplateVersion;
+    type E = TemplateVersionCreatedEvent;
+
+    fn into_event(
+        _command: &Self::Command,
+        output: &Self::Output,
+    ) -> Option<Self::E> {
+        Some(
+            TemplateVersionCreatedEvent::builder()
+                .template_id(output.template_id())
+                .dispatch_type(*output.dispatch_type())
+                .version(*output.version())
+                .build(),
+        )
+    }
+
+    async fn handle<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, Self::Command>,
+    ) -> CommandHandlerResult<Self::Output> {
+        let command = ctx.command();
+        TemplateVersion::lock(
+            ctx.datasource(),
+            &command.template_id,
+            &command.dispatch_type,
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to lock template: {e}"))?;
+
+        let create = CreateTemplateVersion::builder()
+            .template_id(&command.template_id)
+            .dispatch_type(command.dispatch_type)
+            .content(command.content.clone())
+            .created_by(ctx.actor().clone())
+            .timestamp(ctx.timestamp())
+            .build();
+
+        Ok(TemplateVersion::create(ctx.datasource(), create)
+            .await
+            .tap_err(|e| {
+                log::error!("Failed to create template version: {e}")
+            })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_command_bus, CommandBus, CommandBusError};
+    use perroute_commons::{
+        configuration::settings::Settings,
+        types::{actor::Actor, TemplateVersionStatus},
+    };
+    use perroute_connectors::plugin_repository;
+    use perroute_storage::active_record::datasource::DataSource;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    async fn create(
+        bus: &impl CommandBus,
+        dispatch_type: DispatchType,
+    ) -> Result<TemplateVersion, CommandBusError> {
+        bus.execute::<_, CreateTemplateVersionCommandHandler, _>(
+            &Actor::System,
+            &CreateTemplateVersionCommand::builder()
+                .template_id(Id::from("tpl-1"))
+                .dispatch_type(dispatch_type)
+                .content(json!({}))
+                .build(),
+        )
+        .await
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(migrations = "../perroute-storage/migrations")]
+    async fn create_numbers_versions_per_template_and_dispatch_type(
+        pool: PgPool,
+    ) {
+        let bus = create_command_bus(
+            DataSource::new(pool),
+            plugin_repository(),
+            &Settings::default(),
+        );
+
+        let first = create(&bus, DispatchType::Email).await.unwrap();
+        let second = create(&bus, DispatchType::Email).await.unwrap();
+        let other = create(&bus, DispatchType::Sms).await.unwrap();
+
+        assert_eq!(first.version(), &1);
+        assert_eq!(second.version(), &2);
+        assert_eq!(other.version(), &1);
+        assert_eq!(second.status(), &TemplateVersionStatus::Draft);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(migrations = "../perroute-storage/migrations")]
+    async fn concurrent_creates_get_distinct_versions(pool: PgPool) {
+        let bus = create_command_bus(
+            DataSource::new(pool.clone()),
+            plugin_repository(),
+            &Settings::default(),
+        );
+
+        let results = futures::future::join_all(
+            (0..5).map(|_| create(&bus, DispatchType::Email)),
+        )
+        .await;
+
+        let mut versions = results
+            .into_iter()
+            .map(|result| *result.unwrap().version())
+            .collect::<Vec<_>>();
+        versions.sort();
+        assert_eq!(versions, vec![1, 2, 3, 4, 5]);
+
+        let events: i64 = sqlx::query_scalar(
+            "select count(*) from event_messages where entity_id = 'tpl-1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(events, 5);
+    }
+}
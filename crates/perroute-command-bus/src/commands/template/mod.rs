@@ -0,0 +1,53 @@
+pub mod create_version;
+pub mod publish;
+pub mod rollback;
+
+use crate::bus::CommandHandlerResult;
+use perroute_commons::types::{actor::Actor, TemplateVersionStatus, Timestamp};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection, template_version::TemplateVersionQuery,
+        ActiveRecord,
+    },
+    models::template_version::TemplateVersion,
+};
+use tap::TapFallible;
+
+/// Publishes `target`, archiving the version it replaces. Returns the
+/// published version along with the number of the archived one. Callers
+/// hold the template lock, so no other version is published meanwhile.
+async fn release<C: AsRef<Connection>>(
+    conn: &C,
+    target: TemplateVersion,
+    actor: &Actor,
+    timestamp: &Timestamp,
+) -> CommandHandlerResult<(TemplateVersion, Option<i32>)> {
+    let archived = match TemplateVersion::fetch_optional(
+        conn,
+        TemplateVersionQuery::Published(
+            target.template_id(),
+            target.dispatch_type(),
+        ),
+    )
+    .await
+    .tap_err(|e| log::error!("Failed to fetch published version: {e}"))?
+    {
+        Some(current) => Some(
+            *current
+                .set_status(TemplateVersionStatus::Archived)
+                .update(conn)
+                .await
+                .tap_err(|e| log::error!("Failed to archive version: {e}"))?
+                .version(),
+        ),
+        None => None,
+    };
+
+    let published = target
+        .into_published(actor, timestamp)
+        .update(conn)
+        .await
+        .tap_err(|e| log::error!("Failed to publish version: {e}"))?;
+
+    Ok((published, archived))
+}
@@ -0,0 +1,195 @@
+use super::release;
+use crate::{
+    bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
+    commands::Command,
+    impl_command,
+};
+use perroute_commons::{
+    events::TemplateVersionPublishedEvent,
+    types::{dispatch_type::DispatchType, id::Id, TemplateVersionStatus},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection,
+        template_version::{TemplateVersionActiveRecord, TemplateVersionQuery},
+        ActiveRecord,
+    },
+    models::template_version::TemplateVersion,
+};
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PublishTemplateVersionCommandError {
+    #[error("Template version {0} not found")]
+    VersionNotFound(i32),
+
+    #[error("Template version {0} is not a draft")]
+    NotDraft(i32),
+}
+
+impl_command!(PublishTemplateVersionCommand, {
+    template_id: Id,
+    dispatch_type: DispatchType,
+    version: i32,
+});
+
+/// Publishes a draft, archiving the version it replaces.
+pub struct PublishTemplateVersionCommandHandler;
+
+impl CommandHandler for PublishTemplateVersionCommandHandler {
+    type Command = PublishTemplateVersionCommand;
+    type Output = (TemplateVersion, Option<i32>);
+    type E = TemplateVersionPublishedEvent;
+
+    fn into_event(
+        _command: &Self::Command,
+        (published, archived): &Self::Output,
+    ) -> Option<Self::E> {
+        Some(
+            TemplateVersionPublishedEvent::builder()
+                .template_id(published.template_id())
+                .dispatch_type(*published.dispatch_type())
+                .version(*published.version())
+                .maybe_archived_version(*archived)
+                .build(),
+        )
+    }
+
+    async fn handle<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, Self::Command>,
+    ) -> CommandHandlerResult<Self::Output> {
+        let command = ctx.command();
+        TemplateVersion::lock(
+            ctx.datasource(),
+            &command.template_id,
+            &command.dispatch_type,
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to lock template: {e}"))?;
+
+        let target = TemplateVersion::fetch_optional(
+            ctx.datasource(),
+            TemplateVersionQuery::ByVersion(
+                &command.template_id,
+                &command.dispatch_type,
+                command.version,
+            ),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch template version: {e}"))?
+        .ok_or(PublishTemplateVersionCommandError::VersionNotFound(
+            command.version,
+        ))?;
+
+        if *target.status() != TemplateVersionStatus::Draft {
+            return Err(PublishTemplateVersionCommandError::NotDraft(
+                command.version,
+            )
+            .into());
+        }
+
+        release(ctx.datasource(), target, ctx.actor(), ctx.timestamp()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_command_bus, CommandBus, CommandBusError};
+    use perroute_commons::{configuration::settings::Settings, types::actor::Actor};
+    use perroute_connectors::plugin_repository;
+    use perroute_storage::active_record::datasource::DataSource;
+    use sqlx::PgPool;
+
+    async fn publish(
+        pool: PgPool,
+        version: i32,
+    ) -> Result<(TemplateVersion, Option<i32>), CommandBusError> {
+        create_command_bus(
+            DataSource::new(pool),
+            plugin_repository(),
+            &Settings::default(),
+        )
+        .execute::<_, PublishTemplateVersionCommandHandler, _>(
+            &Actor::System,
+            &PublishTemplateVersionCommand::builder()
+                .template_id(Id::from("tpl-1"))
+                .dispatch_type(DispatchType::Email)
+                .version(version)
+                .build(),
+        )
+        .await
+    }
+
+    async fn status(pool: &PgPool, version: i32) -> TemplateVersionStatus {
+        *TemplateVersion::fetch_one(
+            &DataSource::new(pool.clone()),
+            TemplateVersionQuery::ByVersion(
+                &Id::from("tpl-1"),
+                &DispatchType::Email,
+                version,
+            ),
+        )
+        .await
+        .unwrap()
+        .status()
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn publish_archives_the_published_version(pool: PgPool) {
+        let (published, archived) = publish(pool.clone(), 3).await.unwrap();
+
+        assert_eq!(published.status(), &TemplateVersionStatus::Published);
+        assert!(published.published_at().is_some());
+        assert_eq!(archived, Some(2));
+        assert_eq!(status(&pool, 2).await, TemplateVersionStatus::Archived);
+        assert_eq!(status(&pool, 1).await, TemplateVersionStatus::Archived);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn publish_rejects_versions_that_are_not_drafts(pool: PgPool) {
+        let error = publish(pool.clone(), 1).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandBusError::PublishTemplateVersionCommandError(
+                PublishTemplateVersionCommandError::NotDraft(1)
+            )
+        ));
+        assert_eq!(status(&pool, 2).await, TemplateVersionStatus::Published);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn publish_rejects_unknown_versions(pool: PgPool) {
+        let error = publish(pool, 9).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandBusError::PublishTemplateVersionCommandError(
+                PublishTemplateVersionCommandError::VersionNotFound(9)
+            )
+        ));
+    }
+}
@@ -0,0 +1,170 @@
+use super::release;
+use crate::{
+    bus::{CommandBusContext, CommandHandler, CommandHandlerResult},
+    commands::Command,
+    impl_command,
+};
+use perroute_commons::{
+    events::TemplateVersionRolledBackEvent,
+    types::{dispatch_type::DispatchType, id::Id, TemplateVersionStatus},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::Connection,
+        template_version::{TemplateVersionActiveRecord, TemplateVersionQuery},
+        ActiveRecord,
+    },
+    models::template_version::TemplateVersion,
+};
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RollbackTemplateVersionCommandError {
+    #[error("Template version {0} not found")]
+    VersionNotFound(i32),
+
+    #[error("Template version {0} was not published before")]
+    NotArchived(i32),
+}
+
+impl_command!(RollbackTemplateVersionCommand, {
+    template_id: Id,
+    dispatch_type: DispatchType,
+    version: i32,
+});
+
+/// Publishes again a version that was archived by a later publication,
+/// archiving the version it replaces.
+pub struct RollbackTemplateVersionCommandHandler;
+
+impl CommandHandler for RollbackTemplateVersionCommandHandler {
+    type Command = RollbackTemplateVersionCommand;
+    type Output = (TemplateVersion, Option<i32>);
+    type E = TemplateVersionRolledBackEvent;
+
+    fn into_event(
+        _command: &Self::Command,
+        (published, archived): &Self::Output,
+    ) -> Option<Self::E> {
+        Some(
+            TemplateVersionRolledBackEvent::builder()
+                .template_id(published.template_id())
+                .dispatch_type(*published.dispatch_type())
+                .version(*published.version())
+                .maybe_archived_version(*archived)
+                .build(),
+        )
+    }
+
+    async fn handle<C: AsRef<Connection>>(
+        &self,
+        ctx: &CommandBusContext<'_, C, Self::Command>,
+    ) -> CommandHandlerResult<Self::Output> {
+        let command = ctx.command();
+        TemplateVersion::lock(
+            ctx.datasource(),
+            &command.template_id,
+            &command.dispatch_type,
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to lock template: {e}"))?;
+
+        let target = TemplateVersion::fetch_optional(
+            ctx.datasource(),
+            TemplateVersionQuery::ByVersion(
+                &command.template_id,
+                &command.dispatch_type,
+                command.version,
+            ),
+        )
+        .await
+        .tap_err(|e| log::error!("Failed to fetch template version: {e}"))?
+        .ok_or(
+            RollbackTemplateVersionCommandError::VersionNotFound(
+                command.version,
+            ),
+        )?;
+
+        if *target.status() != TemplateVersionStatus::Archived {
+            return Err(RollbackTemplateVersionCommandError::NotArchived(
+                command.version,
+            )
+            .into());
+        }
+
+        release(ctx.datasource(), target, ctx.actor(), ctx.timestamp()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_command_bus, CommandBus, CommandBusError};
+    use perroute_commons::{configuration::settings::Settings, types::actor::Actor};
+    use perroute_connectors::plugin_repository;
+    use perroute_storage::active_record::datasource::DataSource;
+    use sqlx::PgPool;
+
+    async fn rollback(
+        pool: PgPool,
+        version: i32,
+    ) -> Result<(TemplateVersion, Option<i32>), CommandBusError> {
+        create_command_bus(
+            DataSource::new(pool),
+            plugin_repository(),
+            &Settings::default(),
+        )
+        .execute::<_, RollbackTemplateVersionCommandHandler, _>(
+            &Actor::System,
+            &RollbackTemplateVersionCommand::builder()
+                .template_id(Id::from("tpl-1"))
+                .dispatch_type(DispatchType::Email)
+                .version(version)
+                .build(),
+        )
+        .await
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn rollback_publishes_an_archived_version(pool: PgPool) {
+        let (published, archived) = rollback(pool.clone(), 1).await.unwrap();
+
+        assert_eq!(published.version(), &1);
+        assert_eq!(published.status(), &TemplateVersionStatus::Published);
+        assert_eq!(archived, Some(2));
+
+        let events: i64 = sqlx::query_scalar(
+            "select count(*) from event_messages where event_type = 'TemplateVersionRolledBack'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(events, 1);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../../perroute-storage/fixtures",
+            scripts("template_versions")
+        )
+    )]
+    async fn rollback_rejects_versions_never_published(pool: PgPool) {
+        let error = rollback(pool, 3).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandBusError::RollbackTemplateVersionCommandError(
+                RollbackTemplateVersionCommandError::NotArchived(3)
+            )
+        ));
+    }
+}
@@ -12,7 +12,16 @@ use crate::commands::{
         create::CreateMessageTypeCommandError,
         update::UpdateMessageTypeCommandError,
     },
+    dead_letter::redrive::RedriveDeadLetterCommandError,
+    message::{
+        cancel::CancelMessageCommandError, create::CreateMessageCommandError,
+        reschedule::RescheduleMessageCommandError,
+    },
     route::{create::CreateRouteCommandError, update::UpdateRouteCommandError},
+    template::{
+        publish::PublishTemplateVersionCommandError,
+        rollback::RollbackTemplateVersionCommandError,
+    },
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -55,6 +64,28 @@ pub enum CommandBusError {
     #[error("Update route command error: {0}")]
     UpdateRouteCommandError(#[from] UpdateRouteCommandError),
 
+    #[error("Redrive dead letter command error: {0}")]
+    RedriveDeadLetterCommandError(#[from] RedriveDeadLetterCommandError),
+
+    #[error("Create message command error: {0}")]
+    CreateMessageCommandError(#[from] CreateMessageCommandError),
+
+    #[error("Reschedule message command error: {0}")]
+    RescheduleMessageCommandError(#[from] RescheduleMessageCommandError),
+
+    #[error("Cancel message command error: {0}")]
+    CancelMessageCommandError(#[from] CancelMessageCommandError),
+
+    #[error("Publish template version command error: {0}")]
+    PublishTemplateVersionCommandError(
+        #[from] PublishTemplateVersionCommandError,
+    ),
+
+    #[error("Rollback template version command error: {0}")]
+    RollbackTemplateVersionCommandError(
+        #[from] RollbackTemplateVersionCommandError,
+    ),
+
     #[error("{0}")]
     GeneralError(#[from] serde_json::Error),
 }
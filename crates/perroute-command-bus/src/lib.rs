@@ -32,7 +32,12 @@ use commands::{
         delete::DeleteChannelCommandHandler,
         update::UpdateChannelCommandHandler,
     },
-    message::create::CreateMessageCommandHandler,
+    dead_letter::redrive::RedriveDeadLetterCommandHandler,
+    message::{
+        cancel::CancelMessageCommandHandler,
+        create::CreateMessageCommandHandler,
+        reschedule::RescheduleMessageCommandHandler,
+    },
     message_type::{
         create::CreateMessageTypeCommandHandler,
         update::UpdateMessageTypeCommandHandler,
@@ -41,6 +46,11 @@ use commands::{
         create::CreateRouteCommandHandler, delete::DeleteRouteCommandHandler,
         update::UpdateRouteCommandHandler,
     },
+    template::{
+        create_version::CreateTemplateVersionCommandHandler,
+        publish::PublishTemplateVersionCommandHandler,
+        rollback::RollbackTemplateVersionCommandHandler,
+    },
     template_assignment::{
         create::CreateTemplateAssignmentCommandHandler,
         delete::DeleteTemplateAssignmentCommandHandler,
@@ -48,6 +58,7 @@ use commands::{
     },
 };
 pub use error::CommandBusError;
+use perroute_commons::configuration::settings::Settings;
 use perroute_connectors::ProviderPluginRepository;
 use perroute_storage::active_record::datasource::{
     DataSource, NonTransactionalDataSource,
@@ -57,7 +68,18 @@ pub type CommandBusResult<T> = Result<T, CommandBusError>;
 pub fn create_command_bus(
     datasource: DataSource<NonTransactionalDataSource>,
     plugin_repository: ProviderPluginRepository,
+    settings: &Settings,
 ) -> impl CommandBus + Clone {
+    let mut create_message = CreateMessageCommandHandler::default();
+    if let Some(retention) = settings
+        .messages
+        .as_ref()
+        .and_then(|messages| messages.idempotency_key_retention)
+    {
+        create_message = create_message
+            .with_key_retention(std::time::Duration::from_secs(retention));
+    }
+
     DefaultCommandBus::new(datasource, plugin_repository)
         .register(CreateBusinessUnitCommandHandler)
         .register(DeleteBusinessUnitCommandHandler)
@@ -74,5 +96,11 @@ pub fn create_command_bus(
         .register(CreateTemplateAssignmentCommandHandler)
         .register(UpdateTemplateAssignmentCommandHandler)
         .register(DeleteTemplateAssignmentCommandHandler)
-        .register(CreateMessageCommandHandler)
+        .register(create_message)
+        .register(RedriveDeadLetterCommandHandler)
+        .register(RescheduleMessageCommandHandler)
+        .register(CancelMessageCommandHandler)
+        .register(CreateTemplateVersionCommandHandler)
+        .register(PublishTemplateVersionCommandHandler)
+        .register(RollbackTemplateVersionCommandHandler)
 }
@@ -1,32 +1,105 @@
 use crate::configuration::env::Environment;
-use ::config::{Config, ConfigError};
+use ::config::{Config, ConfigError, File};
 use secrecy::SecretString;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
 use tap::{Tap, TapFallible};
 
+/// Directory holding `base.yaml` and the per environment files.
+const CONFIGURATION_DIR: &str = "configuration";
+const CONFIGURATION_DIR_ENV: &str = "PERROUTE_CONFIGURATION_DIR";
+
+/// Command line flag asking a binary to dump its settings and exit.
+pub const PRINT_CONFIG_FLAG: &str = "--print-config";
+
+const REDACTED: &str = "[REDACTED]";
+
 #[derive(Debug, thiserror::Error)]
 pub enum SettingsError {
     #[error("Failed to load settings: {0}")]
     ConfigError(#[from] ConfigError),
+
+    #[error("Failed to serialize settings: {0}")]
+    SerializationError(#[from] serde_json::Error),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// Whether the binary was started with [`PRINT_CONFIG_FLAG`].
+pub fn print_config_requested() -> bool {
+    std::env::args().any(|arg| arg == PRINT_CONFIG_FLAG)
+}
+
+fn redact<S: Serializer>(_: &SecretString, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(REDACTED)
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct Settings {
     pub server: Option<ServerSettings>,
     pub database: Option<DatabaseSettings>,
-    pub template_storage: Option<AwsS3TemplateStorageSettings>,
+    pub rabbitmq: Option<RabbitMqSettings>,
+    pub template_storage: Option<TemplateStorageSettings>,
+    pub template_directory: Option<FsTemplateStorageSettings>,
+    pub template_helpers: Option<TemplateHelpersSettings>,
+    pub template_limits: Option<TemplateLimitsSettings>,
     pub aws: Option<AwsSettings>,
     pub pooling: Option<EventPoolingSettings>,
     pub digester: Option<DigesterSettings>,
+    pub dispatcher: Option<DispatcherSettings>,
+    pub dispatch_queue: Option<DispatchQueueSettings>,
+    pub messages: Option<MessageSettings>,
+    pub otlp: Option<OtlpSettings>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DispatchQueueBackend {
+    #[default]
+    Sqs,
+    Postgres,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DispatchQueueSettings {
+    #[serde(default)]
+    pub backend: DispatchQueueBackend,
+    pub visibility_timeout: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DispatcherSettings {
+    pub retry: RetrySettings,
+    pub dead_letter: Option<DeadLetterSettings>,
+    pub scheduler: Option<SchedulerSettings>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SchedulerSettings {
+    pub interval: u64,
+    pub batch_size: i64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeadLetterSettings {
+    pub queue_url: Option<String>,
+    pub max_receive_count: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RetrySettings {
+    pub max_attempts: u32,
+    pub initial_delay: u64,
+    pub max_delay: u64,
+    pub multiplier: f64,
+    pub jitter: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct DigesterSettings {
     pub bucket_name: String,
     pub queue_arn: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct EventPoolingSettings {
     pub interval: u64,
     pub max_events: u64,
@@ -34,27 +107,75 @@ pub struct EventPoolingSettings {
     pub publishable_events: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct AwsSettings {
     pub dispatch_queue_url: String,
     pub digest_queue_url: String,
     pub event_topic_arn: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
-pub struct AwsS3TemplateStorageSettings {
-    pub bucket_name: String,
+/// Where the dispatcher reads published templates from. Postgres has to be
+/// chosen explicitly; S3 stays the default. The API only changes templates
+/// and partials with Postgres, the store it writes them to.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateStorageBackend {
+    #[default]
+    S3,
+    Postgres,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TemplateStorageSettings {
+    #[serde(default)]
+    pub backend: TemplateStorageBackend,
+    /// Required by the S3 backend.
+    pub bucket_name: Option<String>,
+    /// Seconds fetched templates are used before being read again from the
+    /// bucket. With Postgres, seconds the partials of a business unit are
+    /// used before being read again.
+    pub cache_ttl_seconds: Option<u64>,
+}
+
+/// Reads templates from a local directory instead of S3.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FsTemplateStorageSettings {
+    pub path: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// Extra Handlebars helpers, one Rhai script per helper named after the file.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TemplateHelpersSettings {
+    pub scripts_path: String,
+}
+
+/// How messages sent to the API are accepted.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct MessageSettings {
+    /// Seconds an idempotency key keeps pointing at the message it created,
+    /// 24 hours when unset.
+    pub idempotency_key_retention: Option<u64>,
+}
+
+/// Upper bounds on rendered templates. Unset limits are not enforced.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct TemplateLimitsSettings {
+    pub sms_segments: Option<usize>,
+    pub push_title_length: Option<usize>,
+    pub push_body_length: Option<usize>,
+    pub email_subject_length: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct ServerSettings {
     pub port: u16,
     pub host: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct DatabaseSettings {
     pub username: String,
+    #[serde(serialize_with = "redact")]
     pub password: SecretString,
     pub port: u16,
     pub host: String,
@@ -64,12 +185,25 @@ pub struct DatabaseSettings {
     pub migration: MigrationSettings,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RabbitMqSettings {
+    #[serde(serialize_with = "redact")]
+    pub uri: SecretString,
+}
+
+/// OpenTelemetry collector receiving traces over OTLP.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct OtlpSettings {
+    pub endpoint: String,
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct MigrationSettings {
     pub enabled: bool,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct PollSettings {
     pub max_connection: u32,
     pub max_lifetime: u64,
@@ -78,13 +212,26 @@ pub struct PollSettings {
 }
 
 impl Settings {
+    /// Layers `base.yaml`, then the file of the running environment, then
+    /// `PERROUTE__*` environment variables. Keys that do not map to a setting
+    /// are logged.
     pub fn load() -> Result<Self, SettingsError> {
         let env = Environment::which();
         log::info!(
             "Starting to loading configuration from {} environment",
             env
         );
-        let settings = Config::builder()
+        let dir = std::env::var(CONFIGURATION_DIR_ENV)
+            .unwrap_or_else(|_| CONFIGURATION_DIR.to_string());
+        let config = Config::builder()
+            .add_source(File::with_name(&format!("{dir}/base")).required(false))
+            .add_source(
+                File::with_name(&format!(
+                    "{dir}/{}",
+                    env.to_string().to_lowercase()
+                ))
+                .required(false),
+            )
             .add_source(
                 config::Environment::default()
                     .prefix("PERROUTE")
@@ -93,11 +240,115 @@ impl Settings {
             )
             .build()
             .tap_err(|e| log::error!("{:?}", e))?;
-        Ok(settings
-            .try_deserialize::<Self>()
-            .tap_err(|e| {
+
+        let settings =
+            config.clone().try_deserialize::<Self>().tap_err(|e| {
                 log::error!("Failed to deserialize settings. Error: {e}")
+            })?;
+
+        for key in unknown_keys(
+            &config.try_deserialize::<Value>()?,
+            &serde_json::to_value(&settings)?,
+        ) {
+            log::warn!("Unknown configuration key: {key}");
+        }
+
+        Ok(settings.tap(|s| log::debug!("Settings loaded: {s:#?}")))
+    }
+
+    /// Whether the templates delivered are the versions kept in Postgres, the
+    /// ones managed through the API. A template directory takes over the
+    /// template storage.
+    pub fn delivers_templates_from_postgres(&self) -> bool {
+        self.template_directory.is_none()
+            && self.template_storage.as_ref().is_some_and(|storage| {
+                storage.backend == TemplateStorageBackend::Postgres
             })
-            .tap(|s| log::debug!("Settings loaded: {s:#?}"))?)
+    }
+
+    /// Settings as pretty printed JSON, with secrets redacted.
+    pub fn dump(&self) -> Result<String, SettingsError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Dotted paths present in `raw` that the deserialized settings dropped.
+fn unknown_keys(raw: &Value, known: &Value) -> Vec<String> {
+    fn walk(raw: &Value, known: &Value, path: &str, keys: &mut Vec<String>) {
+        let (Value::Object(raw), Value::Object(known)) = (raw, known) else {
+            return;
+        };
+        for (key, value) in raw {
+            let path = match path.is_empty() {
+                true => key.clone(),
+                false => format!("{path}.{key}"),
+            };
+            match known.get(key) {
+                Some(known) => walk(value, known, &path, keys),
+                None => keys.push(path),
+            }
+        }
+    }
+
+    let mut keys = vec![];
+    walk(raw, known, "", &mut keys);
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn base() -> (Value, Settings) {
+        let config = Config::builder()
+            .add_source(File::with_name(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../configuration/base.yaml"
+            )))
+            .build()
+            .unwrap();
+
+        (
+            config.clone().try_deserialize::<Value>().unwrap(),
+            config.try_deserialize::<Settings>().unwrap(),
+        )
+    }
+
+    #[test]
+    fn unknown_keys_reports_dotted_paths_missing_from_the_settings() {
+        let raw = json!({
+            "server": { "port": 8080, "hots": "localhost" },
+            "database": null,
+            "metrics": { "enabled": true }
+        });
+        let known = json!({
+            "server": { "port": 8080, "host": null },
+            "database": null
+        });
+
+        assert_eq!(unknown_keys(&raw, &known), vec!["metrics", "server.hots"]);
+    }
+
+    #[test]
+    fn unknown_keys_is_empty_for_the_base_configuration() {
+        let (raw, settings) = base();
+
+        assert_eq!(
+            unknown_keys(&raw, &serde_json::to_value(&settings).unwrap()),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn dump_redacts_secrets() {
+        let (_, settings) = base();
+
+        let dump = settings.dump().unwrap();
+
+        assert!(!dump.contains("amqp://admin:admin"));
+        assert!(!dump.contains("\"password\": \"postgres\""));
+        assert_eq!(dump.matches(REDACTED).count(), 2);
+        assert!(dump.contains("\"bucket_name\": \"template-storage\""));
     }
 }
@@ -2,7 +2,8 @@ use crate::{
     event, impl_sqlx_type,
     types::{
         actor::Actor, code::Code, dispatch_type::DispatchType, entity::Entity,
-        id::Id, name::Name, vars::Vars, Configuration, ProviderId, Timestamp,
+        id::Id, name::Name, recipient::Recipient, vars::Vars, Configuration,
+        Payload, ProviderId, Tags, Timestamp,
     },
 };
 use bon::Builder;
@@ -41,6 +42,9 @@ pub enum EventType {
     TemplateAssignmentUpdated,
     TemplateAssignmentDeleted,
     MessageCreated,
+    TemplateVersionCreated,
+    TemplateVersionPublished,
+    TemplateVersionRolledBack,
 }
 
 impl From<&Self> for EventType {
@@ -83,6 +87,13 @@ pub enum Event {
     TemplateAssignmentDeleted(
         ApplicationEventData<TemplateAssignmentDeletedEvent>,
     ),
+    TemplateVersionCreated(ApplicationEventData<TemplateVersionCreatedEvent>),
+    TemplateVersionPublished(
+        ApplicationEventData<TemplateVersionPublishedEvent>,
+    ),
+    TemplateVersionRolledBack(
+        ApplicationEventData<TemplateVersionRolledBackEvent>,
+    ),
 }
 
 impl<'de> Deserialize<'de> for Event {
@@ -119,6 +130,9 @@ impl Event {
             Event::TemplateAssignmentCreated(data) => data.id(),
             Event::TemplateAssignmentUpdated(data) => data.id(),
             Event::TemplateAssignmentDeleted(data) => data.id(),
+            Event::TemplateVersionCreated(data) => data.id(),
+            Event::TemplateVersionPublished(data) => data.id(),
+            Event::TemplateVersionRolledBack(data) => data.id(),
         }
     }
 
@@ -140,6 +154,9 @@ impl Event {
             Event::TemplateAssignmentCreated(data) => data.event_type(),
             Event::TemplateAssignmentUpdated(data) => data.event_type(),
             Event::TemplateAssignmentDeleted(data) => data.event_type(),
+            Event::TemplateVersionCreated(data) => data.event_type(),
+            Event::TemplateVersionPublished(data) => data.event_type(),
+            Event::TemplateVersionRolledBack(data) => data.event_type(),
         }
     }
 
@@ -161,6 +178,9 @@ impl Event {
             Event::TemplateAssignmentCreated(data) => data.actor(),
             Event::TemplateAssignmentUpdated(data) => data.actor(),
             Event::TemplateAssignmentDeleted(data) => data.actor(),
+            Event::TemplateVersionCreated(data) => data.actor(),
+            Event::TemplateVersionPublished(data) => data.actor(),
+            Event::TemplateVersionRolledBack(data) => data.actor(),
         }
     }
 
@@ -182,6 +202,9 @@ impl Event {
             Event::TemplateAssignmentCreated(data) => data.entity_id(),
             Event::TemplateAssignmentUpdated(data) => data.entity_id(),
             Event::TemplateAssignmentDeleted(data) => data.entity_id(),
+            Event::TemplateVersionCreated(data) => data.entity_id(),
+            Event::TemplateVersionPublished(data) => data.entity_id(),
+            Event::TemplateVersionRolledBack(data) => data.entity_id(),
         }
     }
 
@@ -203,6 +226,9 @@ impl Event {
             Event::TemplateAssignmentCreated(data) => data.created_at(),
             Event::TemplateAssignmentUpdated(data) => data.created_at(),
             Event::TemplateAssignmentDeleted(data) => data.created_at(),
+            Event::TemplateVersionCreated(data) => data.created_at(),
+            Event::TemplateVersionPublished(data) => data.created_at(),
+            Event::TemplateVersionRolledBack(data) => data.created_at(),
         }
     }
 }
@@ -390,11 +416,29 @@ impl ApplicationEvent for RouteDeletedEvent {
     }
 }
 
-event!(MessageCreatedEvent, {});
+event!(MessageCreatedEvent, {
+    message_id: Id,
+    message_type_id: Id,
+    business_unit_id: Id,
+    dispatch_type: DispatchType,
+    recipient: Recipient,
+    payload: Payload,
+    tags: Tags,
+    scheduled_at: Option<Timestamp>,
+});
 
 impl ApplicationEvent for MessageCreatedEvent {
     fn to_event(self, actor: &Actor, created_at: &Timestamp) -> Event {
-        todo!()
+        Event::MessageCreated(
+            ApplicationEventData::builder()
+                .id(Id::new())
+                .created_at(created_at.clone())
+                .actor(actor.clone())
+                .event_type(EventType::MessageCreated)
+                .entity_id(self.message_id.clone())
+                .payload(self)
+                .build(),
+        )
     }
 }
 
@@ -421,3 +465,68 @@ impl ApplicationEvent for TemplateAssignmentDeletedEvent {
         todo!()
     }
 }
+
+event!(TemplateVersionCreatedEvent, {
+    template_id: Id,
+    dispatch_type: DispatchType,
+    version: i32,
+});
+
+impl ApplicationEvent for TemplateVersionCreatedEvent {
+    fn to_event(self, actor: &Actor, created_at: &Timestamp) -> Event {
+        Event::TemplateVersionCreated(
+            ApplicationEventData::builder()
+                .id(Id::new())
+                .created_at(created_at.clone())
+                .actor(actor.clone())
+                .event_type(EventType::TemplateVersionCreated)
+                .entity_id(self.template_id.clone())
+                .payload(self)
+                .build(),
+        )
+    }
+}
+
+event!(TemplateVersionPublishedEvent, {
+    template_id: Id,
+    dispatch_type: DispatchType,
+    version: i32,
+    archived_version: Option<i32>,
+});
+
+impl ApplicationEvent for TemplateVersionPublishedEvent {
+    fn to_event(self, actor: &Actor, created_at: &Timestamp) -> Event {
+        Event::TemplateVersionPublished(
+            ApplicationEventData::builder()
+                .id(Id::new())
+                .created_at(created_at.clone())
+                .actor(actor.clone())
+                .event_type(EventType::TemplateVersionPublished)
+                .entity_id(self.template_id.clone())
+                .payload(self)
+                .build(),
+        )
+    }
+}
+
+event!(TemplateVersionRolledBackEvent, {
+    template_id: Id,
+    dispatch_type: DispatchType,
+    version: i32,
+    archived_version: Option<i32>,
+});
+
+impl ApplicationEvent for TemplateVersionRolledBackEvent {
+    fn to_event(self, actor: &Actor, created_at: &Timestamp) -> Event {
+        Event::TemplateVersionRolledBack(
+            ApplicationEventData::builder()
+                .id(Id::new())
+                .created_at(created_at.clone())
+                .actor(actor.clone())
+                .event_type(EventType::TemplateVersionRolledBack)
+                .entity_id(self.template_id.clone())
+                .payload(self)
+                .build(),
+        )
+    }
+}
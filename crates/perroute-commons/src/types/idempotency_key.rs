@@ -0,0 +1,88 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::Type;
+
+/// Longest key accepted, bounded by the `messages.key` column.
+const MAX_LENGTH: usize = 50;
+
+/// Client supplied key identifying a message creation request, unique per
+/// business unit.
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize, Deserialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct IdempotencyKey(String);
+
+impl From<&IdempotencyKey> for IdempotencyKey {
+    fn from(key: &IdempotencyKey) -> Self {
+        key.clone()
+    }
+}
+
+impl Display for IdempotencyKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidIdempotencyKeyError(String);
+
+impl TryFrom<&str> for IdempotencyKey {
+    type Error = InvalidIdempotencyKeyError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if value.trim().is_empty() {
+            Err(InvalidIdempotencyKeyError(
+                "Idempotency key cannot be empty".to_string(),
+            ))
+        } else if value.len() > MAX_LENGTH {
+            Err(InvalidIdempotencyKeyError(format!(
+                "Idempotency key cannot be longer than {MAX_LENGTH} characters"
+            )))
+        } else if !value.chars().all(|c| c.is_ascii_graphic()) {
+            Err(InvalidIdempotencyKeyError(
+                "Idempotency key must only contain visible ASCII characters"
+                    .to_string(),
+            ))
+        } else {
+            Ok(Self(value.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_visible_ascii_keys() {
+        let key = IdempotencyKey::try_from("order-42:retry_1").unwrap();
+
+        assert_eq!(key.to_string(), "order-42:retry_1");
+        assert!(
+            IdempotencyKey::try_from("a".repeat(MAX_LENGTH).as_str()).is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_empty_keys() {
+        assert!(IdempotencyKey::try_from("").is_err());
+        assert!(IdempotencyKey::try_from("   ").is_err());
+    }
+
+    #[test]
+    fn rejects_keys_longer_than_the_column() {
+        let key = "a".repeat(MAX_LENGTH + 1);
+
+        assert!(IdempotencyKey::try_from(key.as_str()).is_err());
+    }
+
+    #[test]
+    fn rejects_keys_with_invalid_characters() {
+        assert!(IdempotencyKey::try_from("order 42").is_err());
+        assert!(IdempotencyKey::try_from("order\n42").is_err());
+        assert!(IdempotencyKey::try_from("pedido-ção").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::Type;
+
+/// Longest tag accepted, bounded by the `messages.locale` column.
+const MAX_LENGTH: usize = 35;
+
+/// A BCP 47 language tag such as `pt` or `pt-BR`, normalized so that the
+/// language is lowercase and the region uppercase.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Type,
+    Serialize,
+    Deserialize,
+)]
+#[sqlx(transparent)]
+#[serde(try_from = "String", into = "String")]
+pub struct Locale(String);
+
+impl Locale {
+    /// The locale followed by its less specific parents, e.g. `pt-BR` then
+    /// `pt`.
+    pub fn fallbacks(&self) -> impl Iterator<Item = Locale> + '_ {
+        let mut tag = Some(self.0.as_str());
+        std::iter::from_fn(move || {
+            let current = tag?;
+            tag = current.rsplit_once('-').map(|(parent, _)| parent);
+            Some(Locale(current.to_string()))
+        })
+    }
+
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or(&self.0)
+    }
+}
+
+impl From<&Locale> for Locale {
+    fn from(locale: &Locale) -> Self {
+        locale.clone()
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        locale.0
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidLocaleError(String);
+
+impl TryFrom<&str> for Locale {
+    type Error = InvalidLocaleError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let invalid = || InvalidLocaleError(format!("Invalid locale: {value}"));
+
+        if value.is_empty() || value.len() > MAX_LENGTH {
+            return Err(invalid());
+        }
+
+        let mut subtags = value.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default();
+        if !(2..=3).contains(&language.len())
+            || !language.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(invalid());
+        }
+
+        let mut tag = language.to_ascii_lowercase();
+        for subtag in subtags {
+            if !(2..=8).contains(&subtag.len())
+                || !subtag.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return Err(invalid());
+            }
+            tag.push('-');
+            match subtag.len() {
+                // region, e.g. BR
+                2 => tag.push_str(&subtag.to_ascii_uppercase()),
+                // script, e.g. Hant
+                4 if subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                    tag.push_str(&subtag[..1].to_ascii_uppercase());
+                    tag.push_str(&subtag[1..].to_ascii_lowercase());
+                }
+                _ => tag.push_str(&subtag.to_ascii_lowercase()),
+            }
+        }
+
+        Ok(Locale(tag))
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = InvalidLocaleError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Locale::try_from(value.as_str())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = InvalidLocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::try_from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_the_case_of_each_subtag() {
+        for (value, expected) in [
+            ("pt", "pt"),
+            ("PT-br", "pt-BR"),
+            ("pt_BR", "pt-BR"),
+            ("zh-hant-tw", "zh-Hant-TW"),
+            ("es-419", "es-419"),
+        ] {
+            assert_eq!(Locale::try_from(value).unwrap().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_tags() {
+        for value in ["", "p", "port", "pt-", "pt-B", "pt-BR!", "12-BR"] {
+            assert!(Locale::try_from(value).is_err(), "{value}");
+        }
+        assert!(Locale::try_from("pt-".repeat(MAX_LENGTH).as_str()).is_err());
+    }
+
+    #[test]
+    fn fallbacks_walk_from_the_most_specific_tag() {
+        let locale = Locale::try_from("zh-Hant-TW").unwrap();
+
+        let fallbacks = locale
+            .fallbacks()
+            .map(|locale| locale.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(fallbacks, vec!["zh-Hant-TW", "zh-Hant", "zh"]);
+        assert_eq!(locale.language(), "zh");
+    }
+}
@@ -3,6 +3,8 @@ pub mod code;
 pub mod dispatch_type;
 pub mod entity;
 pub mod id;
+pub mod idempotency_key;
+pub mod locale;
 pub mod name;
 pub mod priority;
 pub mod recipient;
@@ -38,6 +40,12 @@ impl From<&Timestamp> for Timestamp {
     }
 }
 
+impl From<NaiveDateTime> for Timestamp {
+    fn from(value: NaiveDateTime) -> Timestamp {
+        Self(value)
+    }
+}
+
 impl Default for Timestamp {
     fn default() -> Self {
         Self::now()
@@ -54,6 +62,7 @@ impl Deref for Timestamp {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Hash, Deserialize, Type)]
 #[serde(transparent)]
+#[sqlx(transparent)]
 pub struct ProviderId(String);
 
 impl From<&ProviderId> for ProviderId {
@@ -96,6 +105,12 @@ impl From<&Configuration> for Configuration {
     }
 }
 
+impl AsRef<Configuration> for Configuration {
+    fn as_ref(&self) -> &Configuration {
+        self
+    }
+}
+
 impl Configuration {
     pub fn new(value: &HashMap<String, String>) -> Self {
         Self(value.clone())
@@ -116,7 +131,7 @@ impl Deref for Configuration {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize, Deserialize)]
 #[sqlx(transparent)]
 #[serde(transparent)]
 pub struct Payload(Value);
@@ -152,6 +167,19 @@ pub enum MessageStatus {
     Received,
     Failed,
     Dispatched,
+    Scheduled,
+    Cancelled,
+}
+
+impl MessageStatus {
+    /// Status of a new message: held as scheduled while `scheduled_at` is in
+    /// the future, received otherwise.
+    pub fn initial(scheduled_at: Option<&Timestamp>, now: &Timestamp) -> Self {
+        match scheduled_at {
+            Some(scheduled_at) if **scheduled_at > **now => Self::Scheduled,
+            _ => Self::Received,
+        }
+    }
 }
 
 impl_sqlx_type!(MessageStatus as String);
@@ -162,9 +190,37 @@ impl From<&MessageStatus> for MessageStatus {
     }
 }
 
+/// Lifecycle of a template version. Exactly one version of a template is
+/// published at a time; the ones it replaced are archived.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    strum::EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    Hash,
+    Copy,
+)]
+pub enum TemplateVersionStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl_sqlx_type!(TemplateVersionStatus as String);
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tags(HashSet<String>);
 
+impl From<HashSet<String>> for Tags {
+    fn from(value: HashSet<String>) -> Self {
+        Self(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Username(String);
 
@@ -173,3 +229,42 @@ pub struct Email(String);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Password(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn message_without_schedule_is_received() {
+        let now = Timestamp::now();
+
+        assert_eq!(MessageStatus::initial(None, &now), MessageStatus::Received);
+    }
+
+    #[test]
+    fn message_scheduled_in_the_future_is_scheduled() {
+        let now = Timestamp::now();
+        let scheduled_at = Timestamp::from(*now + TimeDelta::minutes(5));
+
+        assert_eq!(
+            MessageStatus::initial(Some(&scheduled_at), &now),
+            MessageStatus::Scheduled
+        );
+    }
+
+    #[test]
+    fn message_scheduled_in_the_past_or_now_is_received() {
+        let now = Timestamp::now();
+        let scheduled_at = Timestamp::from(*now - TimeDelta::minutes(5));
+
+        assert_eq!(
+            MessageStatus::initial(Some(&scheduled_at), &now),
+            MessageStatus::Received
+        );
+        assert_eq!(
+            MessageStatus::initial(Some(&now), &now),
+            MessageStatus::Received
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::Deref;
 use serde::Serialize;
 use sqlx::prelude::Type;
 
-#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Type, Serialize)]
 #[sqlx(transparent)]
 #[serde(transparent)]
 pub struct Priority(i64);
@@ -1,7 +1,72 @@
+use std::fmt::Display;
+
+use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use super::dispatch_type::DispatchType;
 
+/// Longest phone number allowed by E.164, country code included.
+const MAX_PHONE_NUMBER_DIGITS: usize = 15;
+const MIN_PHONE_NUMBER_DIGITS: usize = 7;
+
+/// Country calling codes assigned by the ITU, including the shared ones of
+/// global services.
+const CALLING_CODES: &[u16] = &[
+    1, 7, 20, 27, 30, 31, 32, 33, 34, 36, 39, 40, 41, 43, 44, 45, 46, 47, 48,
+    49, 51, 52, 53, 54, 55, 56, 57, 58, 60, 61, 62, 63, 64, 65, 66, 81, 82, 84,
+    86, 90, 91, 92, 93, 94, 95, 98, 211, 212, 213, 216, 218, 220, 221, 222,
+    223, 224, 225, 226, 227, 228, 229, 230, 231, 232, 233, 234, 235, 236, 237,
+    238, 239, 240, 241, 242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252,
+    253, 254, 255, 256, 257, 258, 260, 261, 262, 263, 264, 265, 266, 267, 268,
+    269, 290, 291, 297, 298, 299, 350, 351, 352, 353, 354, 355, 356, 357, 358,
+    359, 370, 371, 372, 373, 374, 375, 376, 377, 378, 379, 380, 381, 382, 383,
+    385, 386, 387, 389, 420, 421, 423, 500, 501, 502, 503, 504, 505, 506, 507,
+    508, 509, 590, 591, 592, 593, 594, 595, 596, 597, 598, 599, 670, 672, 673,
+    674, 675, 676, 677, 678, 679, 680, 681, 682, 683, 685, 686, 687, 688, 689,
+    690, 691, 692, 800, 808, 850, 852, 853, 855, 856, 870, 878, 880, 881, 882,
+    883, 886, 888, 960, 961, 962, 963, 964, 965, 966, 967, 968, 970, 971, 972,
+    973, 974, 975, 976, 977, 979, 992, 993, 994, 995, 996, 998,
+];
+
+/// Prefix dialed before national numbers within a country, when it is not
+/// `0`. Countries such as Italy keep the leading `0` of their numbers in
+/// international format, so they have none.
+fn trunk_prefix(country_code: u16) -> Option<&'static str> {
+    match country_code {
+        1 => Some("1"),
+        7 | 375 => Some("8"),
+        36 => Some("06"),
+        30 | 34 | 39 | 45 | 47 | 48 | 52 | 65 | 298 | 299 | 350 | 351 | 352
+        | 354 | 356 | 357 | 371 | 372 | 376 | 377 | 378 | 379 | 420 | 423
+        | 502 | 503 | 504 | 505 | 506 | 507 | 852 | 853 | 965 | 968 | 973
+        | 974 => None,
+        _ => Some("0"),
+    }
+}
+
+/// Whether the digits of an international number start with an assigned
+/// country calling code.
+fn has_calling_code(digits: &str) -> bool {
+    !digits.starts_with('0')
+        && (1..=3).any(|len| {
+            digits
+                .get(..len)
+                .and_then(|code| code.parse().ok())
+                .is_some_and(|code| CALLING_CODES.contains(&code))
+        })
+}
+
+/// Longest display name accepted in an email header.
+const MAX_DISPLAY_NAME_LENGTH: usize = 256;
+
+/// Longest FCM registration token accepted.
+const MAX_FCM_TOKEN_LENGTH: usize = 4096;
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidRecipientError(String);
+
 #[derive(
     Debug,
     Clone,
@@ -28,11 +93,472 @@ impl Recipient {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SmsRecipient {}
+impl TryFrom<&Value> for Recipient {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value.clone())
+            .map_err(|e| InvalidRecipientError(e.to_string()))
+    }
+}
 
+/// A phone number normalized to E.164, e.g. `+5511987654321`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct EmailRecipient {}
+#[serde(try_from = "String", into = "String")]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// Parses a number written in international format (`+55 11 98765-4321`
+    /// or `0055 11 98765-4321`), or in national format when the country
+    /// calling code is known (`(011) 98765-4321` with `55`). The trunk
+    /// prefix of the country is dropped from national numbers.
+    pub fn parse(
+        value: &str,
+        country_code: Option<u16>,
+    ) -> Result<Self, InvalidRecipientError> {
+        let value = value.trim();
+        let invalid = |reason: &str| {
+            InvalidRecipientError(format!(
+                "Invalid phone number {value}: {reason}"
+            ))
+        };
+
+        let (international, number) = match value.strip_prefix('+') {
+            Some(number) => (true, number),
+            None => match value.strip_prefix("00") {
+                Some(number) => (true, number),
+                None => (false, value),
+            },
+        };
+
+        let mut digits = String::with_capacity(number.len());
+        for c in number.chars() {
+            match c {
+                '0'..='9' => digits.push(c),
+                ' ' | '-' | '.' | '(' | ')' => {}
+                _ => return Err(invalid("unexpected character")),
+            }
+        }
+
+        if international {
+            if !has_calling_code(&digits) {
+                return Err(invalid("unknown country calling code"));
+            }
+        } else {
+            let country_code = country_code
+                .ok_or_else(|| invalid("missing country calling code"))?;
+            if !CALLING_CODES.contains(&country_code) {
+                return Err(invalid("unknown country calling code"));
+            }
+            let national = trunk_prefix(country_code)
+                .and_then(|prefix| digits.strip_prefix(prefix))
+                .unwrap_or(&digits);
+            digits = format!("{country_code}{national}");
+        }
+
+        if !(MIN_PHONE_NUMBER_DIGITS..=MAX_PHONE_NUMBER_DIGITS)
+            .contains(&digits.len())
+        {
+            return Err(invalid(&format!(
+                "expected {MIN_PHONE_NUMBER_DIGITS} to {MAX_PHONE_NUMBER_DIGITS} digits"
+            )));
+        }
+
+        Ok(Self(format!("+{digits}")))
+    }
+}
+
+impl Display for PhoneNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for PhoneNumber {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value, None)
+    }
+}
+
+impl From<PhoneNumber> for String {
+    fn from(value: PhoneNumber) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[serde(try_from = "SmsRecipientModel")]
+pub struct SmsRecipient {
+    number: PhoneNumber,
+}
+
+impl SmsRecipient {
+    pub fn new(number: PhoneNumber) -> Self {
+        Self { number }
+    }
+}
+
+/// Accepts numbers in national format alongside their country calling code.
+#[derive(Deserialize)]
+struct SmsRecipientModel {
+    number: String,
+    country_code: Option<u16>,
+}
+
+impl TryFrom<SmsRecipientModel> for SmsRecipient {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: SmsRecipientModel) -> Result<Self, Self::Error> {
+        Ok(Self::new(PhoneNumber::parse(
+            &value.number,
+            value.country_code,
+        )?))
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct PushRecipient {}
+#[serde(try_from = "String", into = "String")]
+pub struct EmailAddress(String);
+
+impl Display for EmailAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<&str> for EmailAddress {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        match email_address::EmailAddress::is_valid(value) {
+            true => Ok(Self(value.to_string())),
+            false => Err(InvalidRecipientError(format!(
+                "Invalid email address: {value}"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for EmailAddress {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl From<EmailAddress> for String {
+    fn from(value: EmailAddress) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<str> for EmailAddress {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[serde(try_from = "EmailRecipientModel")]
+pub struct EmailRecipient {
+    address: EmailAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cc: Vec<EmailAddress>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    bcc: Vec<EmailAddress>,
+}
+
+impl EmailRecipient {
+    pub fn new(address: EmailAddress) -> Self {
+        Self {
+            address,
+            display_name: None,
+            cc: vec![],
+            bcc: vec![],
+        }
+    }
+
+    pub fn with_display_name(
+        mut self,
+        display_name: &str,
+    ) -> Result<Self, InvalidRecipientError> {
+        let display_name = display_name.trim();
+        if display_name.chars().any(char::is_control) {
+            return Err(InvalidRecipientError(
+                "Display name cannot contain control characters".to_string(),
+            ));
+        }
+        if display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+            return Err(InvalidRecipientError(format!(
+                "Display name cannot be longer than {MAX_DISPLAY_NAME_LENGTH} characters"
+            )));
+        }
+        self.display_name =
+            Some(display_name.to_string()).filter(|name| !name.is_empty());
+        Ok(self)
+    }
+
+    pub fn with_cc(mut self, cc: Vec<EmailAddress>) -> Self {
+        self.cc = cc;
+        self
+    }
+
+    pub fn with_bcc(mut self, bcc: Vec<EmailAddress>) -> Self {
+        self.bcc = bcc;
+        self
+    }
+}
+
+#[derive(Deserialize)]
+struct EmailRecipientModel {
+    address: EmailAddress,
+    display_name: Option<String>,
+    #[serde(default)]
+    cc: Vec<EmailAddress>,
+    #[serde(default)]
+    bcc: Vec<EmailAddress>,
+}
+
+impl TryFrom<EmailRecipientModel> for EmailRecipient {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: EmailRecipientModel) -> Result<Self, Self::Error> {
+        let recipient = Self::new(value.address)
+            .with_cc(value.cc)
+            .with_bcc(value.bcc);
+        match value.display_name {
+            Some(display_name) => recipient.with_display_name(&display_name),
+            None => Ok(recipient),
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, strum::Display, Serialize, Deserialize,
+)]
+pub enum PushPlatform {
+    Apns,
+    Fcm,
+    WebPush,
+}
+
+/// A device registration on a push platform. For web push the token is the
+/// subscription endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[serde(try_from = "DeviceTokenModel")]
+pub struct DeviceToken {
+    platform: PushPlatform,
+    token: String,
+}
+
+impl DeviceToken {
+    pub fn new(
+        platform: PushPlatform,
+        token: &str,
+    ) -> Result<Self, InvalidRecipientError> {
+        let token = token.trim();
+        let valid = match platform {
+            PushPlatform::Apns => {
+                token.len() >= 64
+                    && token.len().is_multiple_of(2)
+                    && token.chars().all(|c| c.is_ascii_hexdigit())
+            }
+            PushPlatform::Fcm => {
+                !token.is_empty()
+                    && token.len() <= MAX_FCM_TOKEN_LENGTH
+                    && token.chars().all(|c| {
+                        c.is_ascii_alphanumeric()
+                            || matches!(c, '-' | '_' | ':')
+                    })
+            }
+            PushPlatform::WebPush => {
+                token.len() > "https://".len()
+                    && token.starts_with("https://")
+                    && !token.chars().any(char::is_whitespace)
+            }
+        };
+
+        match valid {
+            true => Ok(Self {
+                platform,
+                token: token.to_string(),
+            }),
+            false => Err(InvalidRecipientError(format!(
+                "Invalid {platform} device token"
+            ))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeviceTokenModel {
+    platform: PushPlatform,
+    token: String,
+}
+
+impl TryFrom<DeviceTokenModel> for DeviceToken {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: DeviceTokenModel) -> Result<Self, Self::Error> {
+        Self::new(value.platform, &value.token)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[serde(try_from = "PushRecipientModel")]
+pub struct PushRecipient {
+    tokens: Vec<DeviceToken>,
+}
+
+impl PushRecipient {
+    pub fn new(
+        tokens: Vec<DeviceToken>,
+    ) -> Result<Self, InvalidRecipientError> {
+        match tokens.is_empty() {
+            true => Err(InvalidRecipientError(
+                "Push recipient needs at least one device token".to_string(),
+            )),
+            false => Ok(Self { tokens }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PushRecipientModel {
+    tokens: Vec<DeviceToken>,
+}
+
+impl TryFrom<PushRecipientModel> for PushRecipient {
+    type Error = InvalidRecipientError;
+
+    fn try_from(value: PushRecipientModel) -> Result<Self, Self::Error> {
+        Self::new(value.tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn phone_number_normalizes_international_formats() {
+        for value in ["+55 11 98765-4321", "0055 (11) 98765.4321"] {
+            let number = PhoneNumber::parse(value, None).unwrap();
+
+            assert_eq!(number.to_string(), "+5511987654321");
+        }
+    }
+
+    #[test]
+    fn phone_number_prefixes_national_numbers_with_the_country_code() {
+        let number = PhoneNumber::parse("(011) 98765-4321", Some(55)).unwrap();
+
+        assert_eq!(number.to_string(), "+5511987654321");
+    }
+
+    #[test]
+    fn phone_number_drops_the_trunk_prefix_of_the_country() {
+        for (value, country_code, expected) in [
+            ("020 7946 0958", 44, "+442079460958"),
+            ("06 6982 1234", 39, "+390669821234"),
+            ("333 123 4567", 39, "+393331234567"),
+            ("1 (212) 555-0123", 1, "+12125550123"),
+            ("(212) 555-0123", 1, "+12125550123"),
+            ("8 495 123-45-67", 7, "+74951234567"),
+            ("06 1 234 5678", 36, "+3612345678"),
+        ] {
+            let number = PhoneNumber::parse(value, Some(country_code)).unwrap();
+            assert_eq!(number.to_string(), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn phone_number_rejects_invalid_numbers() {
+        assert!(PhoneNumber::parse("(11) 98765-4321", None).is_err());
+        assert!(PhoneNumber::parse("+55 11 9876x-4321", None).is_err());
+        assert!(PhoneNumber::parse("+0 11 98765-4321", None).is_err());
+        assert!(PhoneNumber::parse("+55 123", None).is_err());
+        assert!(PhoneNumber::parse("+55 11 98765-4321 0000", None).is_err());
+        assert!(PhoneNumber::parse("+999 1234 5678", None).is_err());
+        assert!(PhoneNumber::parse("1234 5678", Some(999)).is_err());
+    }
+
+    #[test]
+    fn email_address_accepts_valid_addresses() {
+        let address = EmailAddress::try_from(" user@example.com ").unwrap();
+
+        assert_eq!(address.as_ref(), "user@example.com");
+    }
+
+    #[test]
+    fn email_address_rejects_invalid_addresses() {
+        assert!(EmailAddress::try_from("").is_err());
+        assert!(EmailAddress::try_from("user.example.com").is_err());
+        assert!(EmailAddress::try_from("user@").is_err());
+    }
+
+    #[test]
+    fn email_recipient_rejects_control_characters_in_the_display_name() {
+        let recipient = EmailRecipient::new(
+            EmailAddress::try_from("user@example.com").unwrap(),
+        );
+
+        assert!(recipient.with_display_name("User\r\nBcc: x").is_err());
+    }
+
+    #[test]
+    fn device_token_accepts_tokens_of_each_platform() {
+        assert!(DeviceToken::new(PushPlatform::Apns, &"a1".repeat(32)).is_ok());
+        assert!(DeviceToken::new(PushPlatform::Fcm, "dQw4:APA91b-x_Y").is_ok());
+        assert!(DeviceToken::new(
+            PushPlatform::WebPush,
+            "https://fcm.googleapis.com/fcm/send/abc"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn device_token_rejects_malformed_tokens() {
+        assert!(DeviceToken::new(PushPlatform::Apns, "a1b2").is_err());
+        assert!(DeviceToken::new(PushPlatform::Apns, &"zz".repeat(32)).is_err());
+        assert!(DeviceToken::new(PushPlatform::Fcm, "").is_err());
+        assert!(
+            DeviceToken::new(PushPlatform::Fcm, "token with spaces").is_err()
+        );
+        assert!(DeviceToken::new(
+            PushPlatform::Fcm,
+            &"a".repeat(MAX_FCM_TOKEN_LENGTH + 1)
+        )
+        .is_err());
+        assert!(DeviceToken::new(PushPlatform::WebPush, "http://push").is_err());
+        assert!(DeviceToken::new(PushPlatform::WebPush, "https://").is_err());
+    }
+
+    #[test]
+    fn push_recipient_needs_a_device_token() {
+        let token = DeviceToken::new(PushPlatform::Fcm, "token").unwrap();
+
+        assert!(PushRecipient::new(vec![token]).is_ok());
+        assert!(PushRecipient::new(vec![]).is_err());
+    }
+
+    #[test]
+    fn recipient_deserializes_national_sms_numbers() {
+        let recipient = Recipient::try_from(&json!({
+            "Sms": { "number": "11 98765-4321", "country_code": 55 }
+        }))
+        .unwrap();
+
+        assert_eq!(recipient.dispatch_type(), DispatchType::Sms);
+        assert!(
+            Recipient::try_from(&json!({ "Push": { "tokens": [] } })).is_err()
+        );
+    }
+}
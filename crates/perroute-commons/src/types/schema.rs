@@ -1,9 +1,30 @@
-use std::ops::Deref;
-
+use super::Payload;
+use derive_getters::Getters;
+use jsonschema::{draft202012, Validator};
+use lru::LruCache;
 use serde::Serialize;
 use serde_json::Value;
 use sqlx::prelude::Type;
+use std::{
+    num::NonZeroUsize,
+    ops::Deref,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+/// Compiled validators kept, one per distinct schema.
+const VALIDATORS_CAPACITY: usize = 256;
+
+/// Validators by the schema they were compiled from. Schemas are read along
+/// with their message type for each message, so they are compiled once here
+/// rather than every time a payload is validated.
+static VALIDATORS: LazyLock<Mutex<LruCache<String, Arc<Validator>>>> =
+    LazyLock::new(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(VALIDATORS_CAPACITY).unwrap(),
+        ))
+    });
 
+/// A JSON Schema (draft 2020-12) describing the payload of a message type.
 #[derive(Debug, Clone, PartialEq, Eq, Type, Serialize)]
 #[sqlx(transparent)]
 #[serde(transparent)]
@@ -13,17 +34,76 @@ impl Schema {
     pub fn new(value: Value) -> Self {
         Self(value)
     }
+
+    fn validator(&self) -> Result<Arc<Validator>, InvalidSchemaError> {
+        let key = self.0.to_string();
+        if let Some(validator) = VALIDATORS
+            .lock()
+            .ok()
+            .and_then(|mut validators| validators.get(&key).cloned())
+        {
+            return Ok(validator);
+        }
+
+        let validator = draft202012::new(&self.0)
+            .map(Arc::new)
+            .map_err(|e| InvalidSchemaError(e.to_string()))?;
+        if let Ok(mut validators) = VALIDATORS.lock() {
+            validators.put(key, validator.clone());
+        }
+        Ok(validator)
+    }
+
+    /// Checks the payload against the schema, collecting every violation
+    /// rather than stopping at the first one.
+    pub fn validate(
+        &self,
+        payload: &Payload,
+    ) -> Result<(), PayloadValidationError> {
+        let violations = self
+            .validator()?
+            .iter_errors(payload)
+            .map(|e| SchemaViolation {
+                path: e.instance_path.to_string(),
+                message: e.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(PayloadValidationError::Violations(violations)),
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error("{0}")]
 pub struct InvalidSchemaError(String);
 
+/// A payload value rejected by a schema, located by a JSON pointer into the
+/// payload.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct SchemaViolation {
+    path: String,
+    message: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayloadValidationError {
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(#[from] InvalidSchemaError),
+
+    #[error("Payload does not match the schema")]
+    Violations(Vec<SchemaViolation>),
+}
+
 impl TryFrom<&Value> for Schema {
     type Error = InvalidSchemaError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        Ok(Self(value.to_owned()))
+        let schema = Self(value.to_owned());
+        schema.validator()?;
+        Ok(schema)
     }
 }
 
@@ -34,3 +114,94 @@ impl Deref for Schema {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        Schema::try_from(&json!({
+            "type": "object",
+            "required": ["name", "order"],
+            "properties": {
+                "name": { "type": "string" },
+                "order": {
+                    "type": "object",
+                    "properties": {
+                        "total": { "type": "number", "minimum": 0 }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn compiles_each_schema_once() {
+        let compiled = schema().validator().unwrap();
+
+        assert!(Arc::ptr_eq(&compiled, &schema().validator().unwrap()));
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(Schema::try_from(&json!({ "type": "not-a-type" })).is_err());
+        assert!(Schema::try_from(&json!({ "minimum": "zero" })).is_err());
+    }
+
+    #[test]
+    fn accepts_payloads_matching_the_schema() {
+        let payload = Payload::new(json!({
+            "name": "Ana",
+            "order": { "total": 10.5 }
+        }));
+
+        assert!(schema().validate(&payload).is_ok());
+    }
+
+    #[test]
+    fn reports_every_violation_with_its_path() {
+        let payload = Payload::new(json!({
+            "name": 42,
+            "order": { "total": -1 }
+        }));
+
+        let Err(PayloadValidationError::Violations(violations)) =
+            schema().validate(&payload)
+        else {
+            panic!("expected violations");
+        };
+
+        let paths = violations
+            .iter()
+            .map(|violation| violation.path().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["/name", "/order/total"]);
+    }
+
+    #[test]
+    fn reports_missing_properties_at_the_root() {
+        let payload = Payload::new(json!({ "name": "Ana" }));
+
+        let Err(PayloadValidationError::Violations(violations)) =
+            schema().validate(&payload)
+        else {
+            panic!("expected violations");
+        };
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path(), "");
+        assert!(violations[0].message().contains("order"));
+    }
+
+    #[test]
+    fn reports_invalid_schemas_when_validating() {
+        let schema = Schema::new(json!({ "type": "not-a-type" }));
+
+        assert!(matches!(
+            schema.validate(&Payload::new(json!({}))),
+            Err(PayloadValidationError::InvalidSchema(_))
+        ));
+    }
+}
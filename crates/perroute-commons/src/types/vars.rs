@@ -12,6 +12,10 @@ impl Vars {
         vars.extend(other.0.clone());
         Self(vars)
     }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
 }
 
 impl From<&HashMap<String, String>> for Vars {
@@ -1,14 +1,18 @@
-use std::{collections::HashMap, future::Future, pin::Pin};
+use std::{collections::HashMap, fmt::Debug, future::Future, pin::Pin};
 use perroute_commons::types::{
     dispatch_type::DispatchType,
     id::Id,
     recipient::{EmailRecipient, PushRecipient, SmsRecipient},
-    Configuration,
+    Configuration, ProviderId,
 };
 use perroute_template::template::{
     EmailTemplate, PushTemplate, RenderedTemplateState, SmsTemplate,
 };
-use crate::types::Properties;
+use crate::{
+    sms::SmsEncoder,
+    types::{ConfigurationError, Properties, PropertyError},
+    DispatchResponse, PluginDispatchError,
+};
 
 pub type SmsCapbility =
     Capability<SmsRecipient, SmsTemplate<RenderedTemplateState>>;
@@ -25,16 +29,16 @@ pub trait ProviderPluginTrait {
         &self,
         cfg: &Configuration,
         request: &DispatchRequest<'_>,
-    ) -> Result<Response, Error>;
+    ) -> Result<DispatchResponse, Error>;
 }
 
 pub struct Dispatcher<'d, R, T> {
-    cfg: &'d Configuration,
+    cfg: Configuration,
     function: &'d DispatchFunction<R, T>,
 }
 
 type DispatchFunctionResult<'a> =
-    Pin<Box<dyn Future<Output = Result<Response, Error>> + 'a + Send + Sync>>;
+    Pin<Box<dyn Future<Output = Result<Response, Error>> + 'a + Send>>;
 
 type DispatchFunction<R, T> = Box<
     dyn for<'a> Fn(
@@ -58,12 +62,34 @@ where
     }
 }
 
+#[derive(Debug)]
 pub struct Response;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("")]
-    ValidationError,
+    #[error("Invalid configuration: {0:?}")]
+    ValidationError(Vec<PropertyError>),
+
+    #[error("Dispatch type {0} is not supported by the provider")]
+    UnsupportedDispatchType(DispatchType),
+
+    #[error("{0}")]
+    SmtpError(#[from] crate::plugins::smtp::Error),
+
+    #[error("{0}")]
+    SmsError(#[from] crate::sms::Error),
+}
+
+impl Error {
+    /// Whether the dispatch may succeed if attempted again later.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::ValidationError(_)
+            | Error::UnsupportedDispatchType(_)
+            | Error::SmsError(_) => false,
+            Error::SmtpError(e) => e.is_transient(),
+        }
+    }
 }
 
 pub enum DispatchRequest<'r> {
@@ -79,6 +105,28 @@ pub struct Request<'r, R, T> {
     template: &'r T,
 }
 
+impl<'r, R, T> Request<'r, R, T> {
+    pub fn new(message_id: &'r Id, recipient: &'r R, template: &'r T) -> Self {
+        Self {
+            message_id,
+            recipient,
+            template,
+        }
+    }
+
+    pub fn message_id(&self) -> &Id {
+        self.message_id
+    }
+
+    pub fn recipient(&self) -> &R {
+        self.recipient
+    }
+
+    pub fn template(&self) -> &T {
+        self.template
+    }
+}
+
 pub struct Capability<R, T> {
     properties: Properties,
     function: DispatchFunction<R, T>,
@@ -87,21 +135,26 @@ pub struct Capability<R, T> {
 impl<R: Send + Sync, T: Send + Sync> Capability<R, T> {
     pub fn dispatcher<'a>(
         &'a self,
-        cfg: &'a Configuration,
+        cfg: &Configuration,
     ) -> Result<Dispatcher<'a, R, T>, Error> {
         self.validate_configuration(cfg)
-            .map_err(|e| Error::ValidationError)?;
+            .map_err(Error::ValidationError)?;
         Ok(Dispatcher {
-            cfg,
+            cfg: self.properties.with_defaults(cfg),
             function: &self.function,
         })
     }
+
     pub fn validate_configuration(
         &self,
         cfg: &Configuration,
-    ) -> Result<(), Vec<String>> {
+    ) -> Result<(), Vec<PropertyError>> {
         self.properties.validate(cfg)
     }
+
+    pub fn properties(&self) -> &Properties {
+        &self.properties
+    }
 }
 
 pub struct ProviderPlugin {
@@ -138,19 +191,49 @@ impl ProviderPluginTrait for ProviderPlugin {
         &self,
         cfg: &Configuration,
         request: &DispatchRequest<'_>,
-    ) -> Result<Response, Error> {
+    ) -> Result<DispatchResponse, Error> {
         match request {
             DispatchRequest::Email(request) => match &self.email {
-                Some(c) => c.dispatcher(cfg)?.dispatch(request).await,
-                None => todo!(),
+                Some(c) => c
+                    .dispatcher(cfg)?
+                    .dispatch(request)
+                    .await
+                    .map(|_| DispatchResponse::default()),
+                None => {
+                    Err(Error::UnsupportedDispatchType(DispatchType::Email))
+                }
             },
             DispatchRequest::Sms(request) => match &self.sms {
-                Some(c) => c.dispatcher(cfg)?.dispatch(request).await,
-                None => todo!(),
+                Some(c) => {
+                    let dispatcher = c.dispatcher(cfg)?;
+                    let (template, metrics) =
+                        SmsEncoder::from_configuration(&dispatcher.cfg)
+                            .encode(request.template())?;
+                    log::info!(
+                        "SMS {} encoded as {} with {} characters in {} segments",
+                        request.message_id(),
+                        metrics.encoding(),
+                        metrics.characters(),
+                        metrics.segments()
+                    );
+                    dispatcher
+                        .dispatch(&Request::new(
+                            request.message_id(),
+                            request.recipient(),
+                            &template,
+                        ))
+                        .await
+                        .map(|_| DispatchResponse::sms(metrics))
+                }
+                None => Err(Error::UnsupportedDispatchType(DispatchType::Sms)),
             },
             DispatchRequest::Push(request) => match &self.push {
-                Some(c) => c.dispatcher(cfg)?.dispatch(request).await,
-                None => todo!(),
+                Some(c) => c
+                    .dispatcher(cfg)?
+                    .dispatch(request)
+                    .await
+                    .map(|_| DispatchResponse::default()),
+                None => Err(Error::UnsupportedDispatchType(DispatchType::Push)),
             },
         }
     }
@@ -190,7 +273,7 @@ impl ProviderPlugin {
         >,
     ) -> Self {
         self.sms = Some(Capability {
-            properties,
+            properties: properties.with_properties(SmsEncoder::properties()),
             function,
         });
         self
@@ -226,6 +309,74 @@ impl ProviderPlugin {
     pub fn sms_capability(&self) -> Option<&SmsCapbility> {
         self.sms.as_ref()
     }
+
+    pub fn properties(
+        &self,
+        dispatch_type: &DispatchType,
+    ) -> Option<&Properties> {
+        match dispatch_type {
+            DispatchType::Email => {
+                self.email.as_ref().map(Capability::properties)
+            }
+            DispatchType::Sms => self.sms.as_ref().map(Capability::properties),
+            DispatchType::Push => {
+                self.push.as_ref().map(Capability::properties)
+            }
+        }
+    }
+}
+
+impl Debug for ProviderPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderPlugin")
+            .field("id", &self.id)
+            .field("dispatch_types", &self.supported_dispatch_types())
+            .finish()
+    }
+}
+
+impl<'r> From<&crate::DispatchRequest<'r>> for DispatchRequest<'r> {
+    fn from(request: &crate::DispatchRequest<'r>) -> Self {
+        match request {
+            crate::DispatchRequest::Sms(r) => DispatchRequest::Sms(
+                Request::new(r.id, r.recipient, r.template),
+            ),
+            crate::DispatchRequest::Email(r) => DispatchRequest::Email(
+                Request::new(r.id, r.recipient, r.template),
+            ),
+            crate::DispatchRequest::Push(r) => DispatchRequest::Push(
+                Request::new(r.id, r.recipient, r.template),
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ProviderPlugin for ProviderPlugin {
+    fn id(&self) -> ProviderId {
+        ProviderId::from(self.id.as_str())
+    }
+
+    fn validate_configuration(
+        &self,
+        dispatch_type: &DispatchType,
+        cfg: &Configuration,
+    ) -> Result<(), ConfigurationError> {
+        self.properties(dispatch_type)
+            .ok_or(ConfigurationError::UnsupportedDispatchType(*dispatch_type))?
+            .validate(cfg)
+            .map_err(ConfigurationError::InvalidProperties)
+    }
+
+    async fn dispatch(
+        &self,
+        cfg: &Configuration,
+        request: &crate::DispatchRequest,
+    ) -> Result<DispatchResponse, PluginDispatchError> {
+        ProviderPluginTrait::dispatch(self, cfg, &request.into())
+            .await
+            .map_err(PluginDispatchError::from)
+    }
 }
 
 #[derive(Default)]
@@ -257,18 +408,68 @@ impl PluginRepository {
 #[cfg(test)]
 mod tests {
 
-    use perroute_commons::types::Configuration;
-    use crate::plugins::smtp::SmtpProvider;
-    use super::PluginRepository;
+    use perroute_commons::types::{
+        id::Id,
+        recipient::{PhoneNumber, SmsRecipient},
+        Configuration,
+    };
+    use perroute_template::{
+        render::{Escape, RenderError, Renderer},
+        template::Template,
+    };
+    use crate::{plugins::smtp::SmtpProvider, sms::SmsEncoding};
+    use super::*;
+
+    struct Verbatim;
+
+    impl Renderer for Verbatim {
+        fn render(
+            &self,
+            template: &str,
+            _escape: Escape,
+        ) -> Result<String, RenderError> {
+            Ok(template.to_string())
+        }
+    }
 
     #[test]
     fn name() {
         let repo = PluginRepository::default().add_plugin(SmtpProvider);
 
-        let plugin = repo.get("plugin_id").unwrap();
+        let plugin = repo.get("smtp").unwrap();
 
         //let capability = plugin.email_capability().unwrap();
 
         //let dispatcher = capability.dispatcher(&Configuration::default());
     }
+
+    #[tokio::test]
+    async fn reports_sms_metrics_in_the_response() {
+        let plugin = ProviderPlugin::new("sms").with_sms(
+            Properties::default(),
+            Box::new(|_, _| Box::pin(async { Ok(Response) })),
+        );
+        let id = Id::from("msg-1");
+        let recipient = SmsRecipient::new(
+            PhoneNumber::parse("+5511987654321", None).unwrap(),
+        );
+        let Template::Sms(template) =
+            Template::sms("Hello John").render(&Verbatim).unwrap()
+        else {
+            unreachable!()
+        };
+
+        let response = ProviderPluginTrait::dispatch(
+            &plugin,
+            &Configuration::default(),
+            &DispatchRequest::Sms(Request::new(&id, &recipient, &template)),
+        )
+        .await
+        .unwrap();
+
+        let metrics = response.sms_metrics().unwrap();
+        assert_eq!(metrics.encoding(), SmsEncoding::Gsm7);
+        assert_eq!(metrics.characters(), 10);
+        assert_eq!(metrics.segments(), 1);
+    }
 }
@@ -1,10 +1,12 @@
 //pub mod concrete_plugin;
 pub mod generic_plugins;
 pub mod plugins;
+pub mod sms;
 pub mod spi;
 pub mod types;
 
 use perroute_commons::types::{
+    dispatch_type::DispatchType,
     id::Id,
     recipient::{EmailRecipient, PushRecipient, Recipient, SmsRecipient},
     Configuration, ProviderId,
@@ -12,7 +14,9 @@ use perroute_commons::types::{
 use perroute_template::template::{
     EmailTemplate, PushTemplate, RenderedTemplateState, SmsTemplate, Template,
 };
+use sms::SmsMetrics;
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use types::ConfigurationError;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ProviderPluginError {
@@ -25,7 +29,7 @@ pub enum ProviderPluginError {
 // }
 
 pub fn plugin_repository() -> ProviderPluginRepository {
-    ProviderPluginRepository::default()
+    ProviderPluginRepository::default().with_plugin(plugins::smtp::SmtpProvider)
 }
 
 #[derive(Clone, Debug)]
@@ -46,13 +50,26 @@ impl ProviderPluginRepository {
         self.plugins.get(id)
     }
 
-    pub fn add(mut self, plugin: Arc<dyn ProviderPlugin>) {}
+    pub fn with_plugin(
+        mut self,
+        plugin: impl Into<Arc<dyn ProviderPlugin>>,
+    ) -> Self {
+        let plugin = plugin.into();
+        Arc::make_mut(&mut self.plugins).insert(plugin.id(), plugin);
+        self
+    }
 }
 
 #[async_trait::async_trait]
 pub trait ProviderPlugin: Send + Sync + Debug {
     fn id(&self) -> ProviderId;
 
+    fn validate_configuration(
+        &self,
+        dispatch_type: &DispatchType,
+        configuration: &Configuration,
+    ) -> Result<(), ConfigurationError>;
+
     async fn dispatch(
         &self,
         configuration: &Configuration,
@@ -145,7 +162,34 @@ impl<'r> Request<'r, PushRecipient, PushTemplate<RenderedTemplateState>> {
     }
 }
 
-pub struct DispatchResponse {}
+#[derive(Debug, Default)]
+pub struct DispatchResponse {
+    sms: Option<SmsMetrics>,
+}
+
+impl DispatchResponse {
+    pub fn sms(metrics: SmsMetrics) -> Self {
+        Self { sms: Some(metrics) }
+    }
+
+    /// How the SMS went over the air, for SMS dispatches.
+    pub fn sms_metrics(&self) -> Option<&SmsMetrics> {
+        self.sms.as_ref()
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
-pub enum PluginDispatchError {}
+pub enum PluginDispatchError {
+    #[error("{0}")]
+    ProviderError(#[from] generic_plugins::Error),
+}
+
+impl PluginDispatchError {
+    /// Transient errors (timeouts, throttling, provider outages) are worth
+    /// retrying, permanent ones (invalid recipient, bad credentials) are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PluginDispatchError::ProviderError(e) => e.is_transient(),
+        }
+    }
+}
@@ -1,27 +1,467 @@
-use perroute_commons::types::{recipient::EmailRecipient, Configuration};
-use perroute_template::template::{EmailTemplate, RenderedTemplateState};
-
 use crate::{
     generic_plugins::{
-        Error, ProviderPlugin, ProviderPluginTrait, Request, Response,
+        Error as PluginError, ProviderPlugin, ProviderPluginTrait, Request,
+        Response,
     },
-    types::Properties,
+    types::{Properties, Property, PropertyType},
 };
+use lettre::{
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use perroute_commons::types::{recipient::EmailRecipient, Configuration};
+use perroute_template::template::{EmailTemplate, RenderedTemplateState};
+use std::{str::FromStr, sync::Arc};
+use tap::TapFallible;
+
+pub const PROVIDER_ID: &str = "smtp";
+
+const HOST: &str = "host";
+const PORT: &str = "port";
+const TLS: &str = "tls";
+const USERNAME: &str = "username";
+const PASSWORD: &str = "password";
+const FROM: &str = "from";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Missing smtp property: {0}")]
+    MissingProperty(&'static str),
+
+    #[error("Invalid smtp property {0}: {1}")]
+    InvalidProperty(&'static str, String),
+
+    #[error("Invalid email address: {0}")]
+    InvalidAddress(#[from] lettre::address::AddressError),
+
+    #[error("Failed to build email message: {0}")]
+    MessageError(#[from] lettre::error::Error),
+
+    #[error("Smtp transport error: {0}")]
+    TransportError(#[from] lettre::transport::smtp::Error),
+}
+
+impl Error {
+    /// Connection failures, timeouts and 4xx replies are transient, 5xx
+    /// replies and invalid settings or addresses are permanent.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::TransportError(e) => !(e.is_permanent() || e.is_client()),
+            _ => false,
+        }
+    }
+}
 
 pub struct SmtpProvider;
 
+impl SmtpProvider {
+    fn plugin() -> ProviderPlugin {
+        ProviderPlugin::new(PROVIDER_ID).with_email(
+            properties(),
+            Box::new(move |config, req| Box::pin(send_email(config, req))),
+        )
+    }
+}
+
 impl From<SmtpProvider> for Box<dyn ProviderPluginTrait> {
     fn from(_: SmtpProvider) -> Self {
-        Box::new(ProviderPlugin::new("smtp").with_email(
-            Properties::default(),
-            Box::new(move |config, req| Box::pin(send_email(config, req))),
-        ))
+        Box::new(SmtpProvider::plugin())
+    }
+}
+
+impl From<SmtpProvider> for Arc<dyn crate::ProviderPlugin> {
+    fn from(_: SmtpProvider) -> Self {
+        Arc::new(SmtpProvider::plugin())
+    }
+}
+
+fn properties() -> Properties {
+    Properties::new(vec![
+        Property::builder()
+            .name(HOST)
+            .property_type(PropertyType::String)
+            .required(true)
+            .description("Smtp server host")
+            .build(),
+        Property::builder()
+            .name(PORT)
+            .property_type(PropertyType::Int)
+            .range(1..=i64::from(u16::MAX))
+            .description("Smtp server port, defaults to the tls mode port")
+            .build(),
+        Property::builder()
+            .name(TLS)
+            .property_type(PropertyType::enumeration(&[
+                "none", "starttls", "tls",
+            ]))
+            .default("starttls")
+            .description("Connection security: none, starttls or tls")
+            .build(),
+        Property::builder()
+            .name(USERNAME)
+            .property_type(PropertyType::String)
+            .description("Smtp authentication username")
+            .build(),
+        Property::builder()
+            .name(PASSWORD)
+            .property_type(PropertyType::Secret)
+            .description("Smtp authentication password")
+            .build(),
+        Property::builder()
+            .name(FROM)
+            .property_type(PropertyType::String)
+            .required(true)
+            .description("Sender mailbox, e.g. Perroute <noreply@perroute.io>")
+            .build(),
+    ])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TlsMode {
+    None,
+    #[default]
+    StartTls,
+    Tls,
+}
+
+impl FromStr for TlsMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(TlsMode::None),
+            "starttls" => Ok(TlsMode::StartTls),
+            "tls" => Ok(TlsMode::Tls),
+            other => Err(Error::InvalidProperty(TLS, other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SmtpSettings {
+    host: String,
+    port: Option<u16>,
+    tls: TlsMode,
+    credentials: Option<Credentials>,
+    from: Mailbox,
+}
+
+impl TryFrom<&Configuration> for SmtpSettings {
+    type Error = Error;
+
+    fn try_from(cfg: &Configuration) -> Result<Self, Self::Error> {
+        let host = cfg.get(HOST).ok_or(Error::MissingProperty(HOST))?;
+
+        let port = cfg
+            .get(PORT)
+            .map(|port| {
+                port.parse::<u16>()
+                    .map_err(|e| Error::InvalidProperty(PORT, e.to_string()))
+            })
+            .transpose()?;
+
+        let tls = cfg
+            .get(TLS)
+            .map(|tls| tls.parse())
+            .transpose()?
+            .unwrap_or_default();
+
+        let credentials = match (cfg.get(USERNAME), cfg.get(PASSWORD)) {
+            (Some(username), Some(password)) => {
+                Some(Credentials::new(username.clone(), password.clone()))
+            }
+            (None, None) => None,
+            (Some(_), None) => return Err(Error::MissingProperty(PASSWORD)),
+            (None, Some(_)) => return Err(Error::MissingProperty(USERNAME)),
+        };
+
+        let from =
+            cfg.get(FROM).ok_or(Error::MissingProperty(FROM))?.parse()?;
+
+        Ok(Self {
+            host: host.clone(),
+            port,
+            tls,
+            credentials,
+            from,
+        })
+    }
+}
+
+impl SmtpSettings {
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, Error> {
+        let mut builder = match self.tls {
+            TlsMode::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                    &self.host,
+                )
+            }
+            TlsMode::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(
+                    &self.host,
+                )?
+            }
+            TlsMode::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?
+            }
+        };
+
+        if let Some(port) = self.port {
+            builder = builder.port(port);
+        }
+
+        if let Some(credentials) = &self.credentials {
+            builder = builder.credentials(credentials.clone());
+        }
+
+        Ok(builder.build())
+    }
+}
+
+fn build_message(
+    from: Mailbox,
+    recipient: &EmailRecipient,
+    template: &EmailTemplate<RenderedTemplateState>,
+) -> Result<Message, Error> {
+    let mut builder = Message::builder().from(from).to(Mailbox::new(
+        recipient.display_name().clone(),
+        recipient.address().as_ref().parse()?,
+    ));
+    for cc in recipient.cc() {
+        builder = builder.cc(cc.as_ref().parse()?);
+    }
+    for bcc in recipient.bcc() {
+        builder = builder.bcc(bcc.as_ref().parse()?);
     }
+
+    Ok(builder.subject(template.subject()).multipart(
+        MultiPart::alternative_plain_html(
+            template.text().to_string(),
+            template.html().to_string(),
+        ),
+    )?)
 }
 
 async fn send_email(
     cfg: &Configuration,
     request: &Request<'_, EmailRecipient, EmailTemplate<RenderedTemplateState>>,
-) -> Result<Response, Error> {
+) -> Result<Response, PluginError> {
+    let settings = SmtpSettings::try_from(cfg)
+        .tap_err(|e| log::error!("Invalid smtp configuration: {e}"))?;
+
+    let message = build_message(
+        settings.from.clone(),
+        request.recipient(),
+        request.template(),
+    )
+    .tap_err(|e| log::error!("Failed to build email message: {e}"))?;
+
+    settings
+        .transport()?
+        .send(message)
+        .await
+        .tap_err(|e| {
+            log::error!(
+                "Failed to send message {} through smtp: {e}",
+                request.message_id()
+            )
+        })
+        .map_err(Error::from)?;
+
     Ok(Response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perroute_commons::types::{id::Id, recipient::EmailAddress};
+    use perroute_template::{
+        render::{Escape, RenderError, Renderer},
+        template::Template,
+    };
+    use std::{
+        collections::HashMap,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    struct PlainRenderer;
+
+    impl Renderer for PlainRenderer {
+        fn render(
+            &self,
+            template: &str,
+            _escape: Escape,
+        ) -> Result<String, RenderError> {
+            Ok(template.to_string())
+        }
+    }
+
+    /// Minimal SMTP sink that accepts a single message and hands the DATA
+    /// section back through the returned channel.
+    fn smtp_sink() -> (u16, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut data = String::new();
+            let mut in_data = false;
+
+            writer.write_all(b"220 localhost ESMTP sink\r\n").unwrap();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        writer.write_all(b"250 OK queued\r\n").unwrap();
+                    } else {
+                        data.push_str(&line);
+                    }
+                    continue;
+                }
+
+                let command = line.to_ascii_uppercase();
+                if command.starts_with("DATA") {
+                    in_data = true;
+                    writer.write_all(b"354 Start mail input\r\n").unwrap();
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 Bye\r\n").unwrap();
+                    break;
+                } else {
+                    writer.write_all(b"250 OK\r\n").unwrap();
+                }
+            }
+
+            tx.send(data).unwrap();
+        });
+
+        (port, rx)
+    }
+
+    fn configuration(port: u16, extra: &[(&str, &str)]) -> Configuration {
+        let mut cfg = HashMap::from([
+            (HOST.to_string(), "127.0.0.1".to_string()),
+            (PORT.to_string(), port.to_string()),
+            (TLS.to_string(), "none".to_string()),
+            (
+                FROM.to_string(),
+                "Perroute <noreply@perroute.io>".to_string(),
+            ),
+        ]);
+        cfg.extend(extra.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        Configuration::new(&cfg)
+    }
+
+    fn address(value: &str) -> EmailAddress {
+        EmailAddress::try_from(value).unwrap()
+    }
+
+    fn rendered_template() -> EmailTemplate<RenderedTemplateState> {
+        match Template::email("Welcome", "<p>Hello there</p>", "Hello there")
+            .render(&PlainRenderer)
+            .unwrap()
+        {
+            Template::Email(template) => template,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn settings_require_host_and_from() {
+        let cfg = Configuration::new(&HashMap::new());
+        assert!(matches!(
+            SmtpSettings::try_from(&cfg),
+            Err(Error::MissingProperty(HOST))
+        ));
+
+        let cfg = Configuration::new(&HashMap::from([(
+            HOST.to_string(),
+            "localhost".to_string(),
+        )]));
+        assert!(matches!(
+            SmtpSettings::try_from(&cfg),
+            Err(Error::MissingProperty(FROM))
+        ));
+    }
+
+    #[test]
+    fn settings_reject_partial_credentials() {
+        let cfg = configuration(25, &[(USERNAME, "user")]);
+        assert!(matches!(
+            SmtpSettings::try_from(&cfg),
+            Err(Error::MissingProperty(PASSWORD))
+        ));
+    }
+
+    #[test]
+    fn properties_validate_smtp_configuration() {
+        assert!(properties().validate(&configuration(25, &[])).is_ok());
+
+        let errors = properties()
+            .validate(&configuration(25, &[(TLS, "ssl"), (PORT, "abc")]))
+            .unwrap_err();
+        let errors = errors.iter().map(|e| e.property()).collect::<Vec<_>>();
+        assert_eq!(errors, vec![PORT, TLS]);
+
+        for port in ["0", "65536"] {
+            let errors = properties()
+                .validate(&configuration(25, &[(PORT, port)]))
+                .unwrap_err();
+            assert_eq!(errors[0].property(), PORT);
+            assert_eq!(errors[0].kind().code(), "out_of_range");
+        }
+    }
+
+    #[tokio::test]
+    async fn send_email_to_unreachable_server_is_transient() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let cfg = configuration(port, &[]);
+        let id = Id::new();
+        let recipient = EmailRecipient::new(address("john@doe.com"));
+        let template = rendered_template();
+
+        let error = send_email(&cfg, &Request::new(&id, &recipient, &template))
+            .await
+            .unwrap_err();
+        assert!(error.is_transient());
+    }
+
+    #[tokio::test]
+    async fn send_email_delivers_multipart_message_to_sink() {
+        let (port, rx) = smtp_sink();
+        let cfg = configuration(port, &[]);
+        let id = Id::new();
+        let recipient = EmailRecipient::new(address("john@doe.com"))
+            .with_display_name("John Doe")
+            .unwrap()
+            .with_cc(vec![address("jane@doe.com")]);
+        let template = rendered_template();
+
+        send_email(&cfg, &Request::new(&id, &recipient, &template))
+            .await
+            .unwrap();
+
+        let data = rx.recv().unwrap();
+        assert!(data.contains("To: \"John Doe\" <john@doe.com>"));
+        assert!(data.contains("Cc: jane@doe.com"));
+        assert!(data.contains("Subject: Welcome"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("Hello there"));
+        assert!(data.contains("<p>Hello there</p>"));
+    }
+}
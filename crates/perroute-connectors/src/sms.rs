@@ -0,0 +1,305 @@
+use crate::types::{Property, PropertyType};
+use perroute_commons::types::Configuration;
+use perroute_template::{
+    limits::{gsm7_septets, sms_segments},
+    template::{RenderedTemplateState, SmsTemplate},
+};
+use std::fmt::Display;
+
+/// Most segments a single SMS may be split in. Unlimited when not set.
+pub const MAX_SEGMENTS_PROPERTY: &str = "sms_max_segments";
+
+/// Concatenated SMS count their parts in a single octet.
+const SEGMENTS_LIMIT: i64 = 255;
+
+/// What to do with characters outside the GSM alphabet, see
+/// [`NonGsmCharacters`].
+pub const NON_GSM_CHARACTERS_PROPERTY: &str = "sms_non_gsm_characters";
+
+/// Sent in place of characters that can't be transliterated.
+const REPLACEMENT: char = '?';
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(
+        "The SMS needs {segments} segments, more than the limit of {limit}"
+    )]
+    TooManySegments { segments: usize, limit: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsEncoding {
+    /// GSM 03.38 7-bit alphabet, 160 characters per single message.
+    Gsm7,
+    /// Used as soon as one character is outside the GSM alphabet, 70
+    /// characters per single message.
+    Ucs2,
+}
+
+impl Display for SmsEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmsEncoding::Gsm7 => write!(f, "GSM-7"),
+            SmsEncoding::Ucs2 => write!(f, "UCS-2"),
+        }
+    }
+}
+
+/// How an SMS body goes over the air. Providers bill per segment, so this is
+/// what costs are reported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmsMetrics {
+    encoding: SmsEncoding,
+    characters: usize,
+    segments: usize,
+}
+
+impl SmsMetrics {
+    pub fn of(body: &str) -> Self {
+        let encoding = match body.chars().all(|c| gsm7_septets(c).is_some()) {
+            true => SmsEncoding::Gsm7,
+            false => SmsEncoding::Ucs2,
+        };
+
+        SmsMetrics {
+            encoding,
+            characters: body.chars().count(),
+            segments: sms_segments(body),
+        }
+    }
+
+    pub fn encoding(&self) -> SmsEncoding {
+        self.encoding
+    }
+
+    pub fn characters(&self) -> usize {
+        self.characters
+    }
+
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+}
+
+/// Characters outside the GSM alphabet force the whole message to UCS-2,
+/// more than doubling its segments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonGsmCharacters {
+    /// Sends them as they are, in UCS-2.
+    #[default]
+    Keep,
+    /// Swaps them for their closest GSM equivalent, e.g. `ã` for `a` or `”`
+    /// for `"`, and replaces the ones without one.
+    Transliterate,
+    /// Replaces every one of them with `?`.
+    Replace,
+}
+
+impl NonGsmCharacters {
+    const VALUES: [&'static str; 3] = ["keep", "transliterate", "replace"];
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "transliterate" => NonGsmCharacters::Transliterate,
+            "replace" => NonGsmCharacters::Replace,
+            _ => NonGsmCharacters::Keep,
+        }
+    }
+}
+
+/// Adapts rendered SMS to the GSM alphabet and refuses the ones above the
+/// segment limit, as configured on the SMS capability of a provider.
+#[derive(Debug, Clone, Default, bon::Builder)]
+pub struct SmsEncoder {
+    #[builder(default)]
+    non_gsm_characters: NonGsmCharacters,
+    max_segments: Option<usize>,
+}
+
+impl SmsEncoder {
+    /// Properties every SMS capability accepts on top of its own.
+    pub fn properties() -> Vec<Property> {
+        vec![
+            Property::builder()
+                .name(MAX_SEGMENTS_PROPERTY)
+                .property_type(PropertyType::Int)
+                .range(1..=SEGMENTS_LIMIT)
+                .description("Most segments a single SMS may be split in")
+                .build(),
+            Property::builder()
+                .name(NON_GSM_CHARACTERS_PROPERTY)
+                .property_type(PropertyType::enumeration(
+                    &NonGsmCharacters::VALUES,
+                ))
+                .default("keep")
+                .description(
+                    "What to do with characters outside the GSM alphabet",
+                )
+                .build(),
+        ]
+    }
+
+    /// Reads a configuration already validated against
+    /// [`SmsEncoder::properties`].
+    pub fn from_configuration(cfg: &Configuration) -> Self {
+        SmsEncoder {
+            non_gsm_characters: cfg
+                .get(NON_GSM_CHARACTERS_PROPERTY)
+                .map(|value| NonGsmCharacters::parse(value))
+                .unwrap_or_default(),
+            max_segments: cfg
+                .get(MAX_SEGMENTS_PROPERTY)
+                .and_then(|value| value.parse().ok()),
+        }
+    }
+
+    pub fn encode(
+        &self,
+        template: &SmsTemplate<RenderedTemplateState>,
+    ) -> Result<(SmsTemplate<RenderedTemplateState>, SmsMetrics), Error> {
+        let body = self.adapt(template.body());
+        let metrics = SmsMetrics::of(&body);
+
+        if let Some(limit) = self.max_segments {
+            if metrics.segments > limit {
+                return Err(Error::TooManySegments {
+                    segments: metrics.segments,
+                    limit,
+                });
+            }
+        }
+
+        Ok((template.clone().with_body(body), metrics))
+    }
+
+    fn adapt(&self, body: &str) -> String {
+        let mut adapted = String::with_capacity(body.len());
+        for c in body.chars() {
+            match (self.non_gsm_characters, gsm7_septets(c)) {
+                (NonGsmCharacters::Keep, _) | (_, Some(_)) => adapted.push(c),
+                (NonGsmCharacters::Transliterate, None) => {
+                    match transliterate(c) {
+                        Some(s) => adapted.push_str(s),
+                        None => adapted.push(REPLACEMENT),
+                    }
+                }
+                (NonGsmCharacters::Replace, None) => adapted.push(REPLACEMENT),
+            }
+        }
+        adapted
+    }
+}
+
+/// Closest GSM equivalent of common characters outside the alphabet.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        '‘' | '’' | '‚' | '′' | '`' | '´' => "'",
+        '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+        '–' | '—' | '−' | '•' => "-",
+        '…' => "...",
+        '\u{a0}' | '\t' => " ",
+        'á' | 'â' | 'ã' | 'ā' => "a",
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ā' => "A",
+        'ê' | 'ë' | 'ē' => "e",
+        'È' | 'Ê' | 'Ë' | 'Ē' => "E",
+        'í' | 'î' | 'ï' => "i",
+        'Í' | 'Ì' | 'Î' | 'Ï' => "I",
+        'ó' | 'ô' | 'õ' => "o",
+        'Ó' | 'Ò' | 'Ô' | 'Õ' => "O",
+        'ú' | 'û' => "u",
+        'Ú' | 'Ù' | 'Û' => "U",
+        'ç' => "Ç",
+        'ý' | 'ÿ' => "y",
+        'Ý' => "Y",
+        'œ' => "oe",
+        'Œ' => "OE",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Properties;
+    use perroute_template::{
+        render::{Escape, RenderError, Renderer},
+        template::Template,
+    };
+    use std::collections::HashMap;
+
+    struct Verbatim;
+
+    impl Renderer for Verbatim {
+        fn render(
+            &self,
+            template: &str,
+            _escape: Escape,
+        ) -> Result<String, RenderError> {
+            Ok(template.to_string())
+        }
+    }
+
+    fn sms(body: &str) -> SmsTemplate<RenderedTemplateState> {
+        match Template::sms(body).render(&Verbatim).unwrap() {
+            Template::Sms(sms) => sms,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn adapts_non_gsm_characters_and_enforces_the_segment_limit() {
+        let template = sms("Olá João, seu código é “1234” 🎉");
+
+        let (kept, metrics) = SmsEncoder::default().encode(&template).unwrap();
+        assert_eq!(kept.body(), template.body());
+        assert_eq!(metrics.encoding(), SmsEncoding::Ucs2);
+        assert_eq!(metrics.characters(), 31);
+
+        let (transliterated, metrics) = SmsEncoder::builder()
+            .non_gsm_characters(NonGsmCharacters::Transliterate)
+            .build()
+            .encode(&template)
+            .unwrap();
+        assert_eq!(transliterated.body(), "Ola Joao, seu codigo é \"1234\" ?");
+        assert_eq!(metrics.encoding(), SmsEncoding::Gsm7);
+        assert_eq!(metrics.segments(), 1);
+
+        let (replaced, _) = SmsEncoder::builder()
+            .non_gsm_characters(NonGsmCharacters::Replace)
+            .build()
+            .encode(&template)
+            .unwrap();
+        assert_eq!(replaced.body(), "Ol? Jo?o, seu c?digo é ?1234? ?");
+
+        let limited = SmsEncoder::builder().max_segments(1).build();
+        let error = limited.encode(&sms(&"ã".repeat(71))).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::TooManySegments {
+                segments: 2,
+                limit: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_segment_limits_out_of_range() {
+        let properties = Properties::new(SmsEncoder::properties());
+        let cfg = |value: &str| {
+            Configuration::new(&HashMap::from([(
+                MAX_SEGMENTS_PROPERTY.to_string(),
+                value.to_string(),
+            )]))
+        };
+
+        for value in ["0", "-1", "256"] {
+            let errors = properties.validate(&cfg(value)).unwrap_err();
+            assert_eq!(errors[0].kind().code(), "out_of_range");
+        }
+        assert!(properties.validate(&cfg("1")).is_ok());
+        assert_eq!(
+            SmsEncoder::from_configuration(&cfg("3")).max_segments,
+            Some(3)
+        );
+    }
+}
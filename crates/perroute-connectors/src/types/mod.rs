@@ -1,17 +1,362 @@
-use perroute_commons::types::Configuration;
+use perroute_commons::types::{dispatch_type::DispatchType, Configuration};
+use std::{fmt::Display, ops::RangeInclusive};
+use url::Url;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    Int,
+    Bool,
+    Url,
+    Enum(Vec<String>),
+    Secret,
+}
+
+impl PropertyType {
+    pub fn enumeration(values: &[&str]) -> Self {
+        PropertyType::Enum(values.iter().map(ToString::to_string).collect())
+    }
+
+    fn check(&self, value: &str) -> Result<(), PropertyErrorKind> {
+        let valid = match self {
+            PropertyType::String | PropertyType::Secret => !value.is_empty(),
+            PropertyType::Int => value.parse::<i64>().is_ok(),
+            PropertyType::Bool => value.parse::<bool>().is_ok(),
+            PropertyType::Url => Url::parse(value).is_ok(),
+            PropertyType::Enum(values) => {
+                return match values.iter().any(|v| v == value) {
+                    true => Ok(()),
+                    false => Err(PropertyErrorKind::NotAllowed(values.clone())),
+                }
+            }
+        };
+
+        match valid {
+            true => Ok(()),
+            false => Err(PropertyErrorKind::InvalidType(self.clone())),
+        }
+    }
+}
+
+impl Display for PropertyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyType::String => write!(f, "string"),
+            PropertyType::Int => write!(f, "int"),
+            PropertyType::Bool => write!(f, "bool"),
+            PropertyType::Url => write!(f, "url"),
+            PropertyType::Enum(_) => write!(f, "enum"),
+            PropertyType::Secret => write!(f, "secret"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, bon::Builder)]
 pub struct Property {
+    #[builder(into)]
     name: String,
+    property_type: PropertyType,
+    #[builder(default)]
+    required: bool,
+    #[builder(into)]
+    default: Option<String>,
+    /// Values accepted by an [`PropertyType::Int`] property.
+    range: Option<RangeInclusive<i64>>,
+    #[builder(into)]
+    description: String,
+}
+
+impl Property {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn property_type(&self) -> &PropertyType {
+        &self.property_type
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn range(&self) -> Option<&RangeInclusive<i64>> {
+        self.range.as_ref()
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn validate(&self, cfg: &Configuration) -> Result<(), PropertyError> {
+        match cfg.get(&self.name) {
+            Some(value) => self
+                .property_type
+                .check(value)
+                .and_then(|_| self.check_range(value)),
+            None if self.required && self.default.is_none() => {
+                Err(PropertyErrorKind::Required)
+            }
+            None => Ok(()),
+        }
+        .map_err(|kind| PropertyError::new(&self.name, kind))
+    }
+
+    fn check_range(&self, value: &str) -> Result<(), PropertyErrorKind> {
+        let (Some(range), Ok(value)) = (&self.range, value.parse::<i64>())
+        else {
+            return Ok(());
+        };
+
+        match range.contains(&value) {
+            true => Ok(()),
+            false => Err(PropertyErrorKind::OutOfRange(range.clone())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PropertyErrorKind {
+    #[error("property is required")]
+    Required,
+
+    #[error("property is not supported by the provider")]
+    Unknown,
+
+    #[error("expected a value of type {0}")]
+    InvalidType(PropertyType),
+
+    #[error("expected one of {0:?}")]
+    NotAllowed(Vec<String>),
+
+    #[error("expected a value from {} to {}", .0.start(), .0.end())]
+    OutOfRange(RangeInclusive<i64>),
+}
+
+impl PropertyErrorKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PropertyErrorKind::Required => "required",
+            PropertyErrorKind::Unknown => "unknown",
+            PropertyErrorKind::InvalidType(_) => "invalid_type",
+            PropertyErrorKind::NotAllowed(_) => "not_allowed",
+            PropertyErrorKind::OutOfRange(_) => "out_of_range",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{property}: {kind}")]
+pub struct PropertyError {
+    property: String,
+    kind: PropertyErrorKind,
+}
+
+impl PropertyError {
+    pub fn new(property: &str, kind: PropertyErrorKind) -> Self {
+        Self {
+            property: property.to_string(),
+            kind,
+        }
+    }
+
+    pub fn property(&self) -> &str {
+        &self.property
+    }
+
+    pub fn kind(&self) -> &PropertyErrorKind {
+        &self.kind
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigurationError {
+    #[error("Dispatch type {0} is not supported by the provider")]
+    UnsupportedDispatchType(DispatchType),
+
+    #[error("Invalid configuration: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    InvalidProperties(Vec<PropertyError>),
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Properties {
     props: Vec<Property>,
 }
 
 impl Properties {
-    pub fn validate(&self, cfg: &Configuration) -> Result<(), Vec<String>> {
-        todo!()
+    pub fn new(props: Vec<Property>) -> Self {
+        Self { props }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Property> {
+        self.props.iter()
+    }
+
+    pub fn with_properties(
+        mut self,
+        props: impl IntoIterator<Item = Property>,
+    ) -> Self {
+        self.props.extend(props);
+        self
+    }
+
+    pub fn validate(
+        &self,
+        cfg: &Configuration,
+    ) -> Result<(), Vec<PropertyError>> {
+        let unknown = cfg
+            .keys()
+            .filter(|key| !self.props.iter().any(|p| p.name() == *key))
+            .map(|key| PropertyError::new(key, PropertyErrorKind::Unknown));
+
+        let mut errors = self
+            .props
+            .iter()
+            .filter_map(|p| p.validate(cfg).err())
+            .chain(unknown)
+            .collect::<Vec<_>>();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => {
+                errors.sort_by(|a, b| a.property.cmp(&b.property));
+                Err(errors)
+            }
+        }
+    }
+
+    /// Returns the configuration filled with the declared defaults for every
+    /// property that was not explicitly set.
+    pub fn with_defaults(&self, cfg: &Configuration) -> Configuration {
+        let defaults = self
+            .props
+            .iter()
+            .filter_map(|p| {
+                p.default().map(|d| (p.name().to_string(), d.to_string()))
+            })
+            .collect();
+
+        Configuration::new(&defaults).merge(cfg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn properties() -> Properties {
+        Properties::new(vec![
+            Property::builder()
+                .name("host")
+                .property_type(PropertyType::String)
+                .required(true)
+                .description("host")
+                .build(),
+            Property::builder()
+                .name("port")
+                .property_type(PropertyType::Int)
+                .default("25")
+                .range(1..=65535)
+                .description("port")
+                .build(),
+            Property::builder()
+                .name("tls")
+                .property_type(PropertyType::enumeration(&["none", "tls"]))
+                .description("tls")
+                .build(),
+            Property::builder()
+                .name("endpoint")
+                .property_type(PropertyType::Url)
+                .description("endpoint")
+                .build(),
+        ])
+    }
+
+    fn cfg(values: &[(&str, &str)]) -> Configuration {
+        Configuration::new(
+            &values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn validate_accepts_matching_configuration() {
+        let cfg = cfg(&[
+            ("host", "localhost"),
+            ("port", "2525"),
+            ("tls", "none"),
+            ("endpoint", "https://perroute.io/hook"),
+        ]);
+
+        assert!(properties().validate(&cfg).is_ok());
+    }
+
+    #[test]
+    fn validate_reports_every_invalid_field() {
+        let cfg = cfg(&[
+            ("port", "abc"),
+            ("tls", "ssl"),
+            ("endpoint", "not a url"),
+            ("other", "x"),
+        ]);
+
+        let errors = properties().validate(&cfg).unwrap_err();
+        let errors = errors
+            .iter()
+            .map(|e| (e.property(), e.kind().code()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                ("endpoint", "invalid_type"),
+                ("host", "required"),
+                ("other", "unknown"),
+                ("port", "invalid_type"),
+                ("tls", "not_allowed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_values_out_of_range() {
+        let properties = properties();
+
+        for port in ["0", "65536", "-25"] {
+            let errors = properties
+                .validate(&cfg(&[("host", "localhost"), ("port", port)]))
+                .unwrap_err();
+            assert_eq!(
+                errors[0].kind(),
+                &PropertyErrorKind::OutOfRange(1..=65535)
+            );
+            assert_eq!(
+                errors[0].to_string(),
+                "port: expected a value from 1 to 65535"
+            );
+        }
+
+        for port in ["1", "65535"] {
+            assert!(properties
+                .validate(&cfg(&[("host", "localhost"), ("port", port)]))
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn with_defaults_does_not_override_explicit_values() {
+        let properties = properties();
+
+        let merged = properties.with_defaults(&cfg(&[("host", "localhost")]));
+        assert_eq!(merged.get("port").map(String::as_str), Some("25"));
+
+        let merged = properties.with_defaults(&cfg(&[("port", "587")]));
+        assert_eq!(merged.get("port").map(String::as_str), Some("587"));
     }
 }
@@ -0,0 +1,24 @@
+use perroute_commons::configuration::settings::DeadLetterSettings;
+
+/// How many deliveries a failing message gets before the poller moves it to
+/// the dead letter queue.
+#[derive(Debug, Clone)]
+pub struct DeadLetterQueue {
+    max_receive_count: i32,
+}
+
+impl DeadLetterQueue {
+    pub fn new(max_receive_count: i32) -> Self {
+        Self { max_receive_count }
+    }
+
+    pub fn exhausted(&self, receive_count: i32) -> bool {
+        receive_count >= self.max_receive_count
+    }
+}
+
+impl From<&DeadLetterSettings> for DeadLetterQueue {
+    fn from(settings: &DeadLetterSettings) -> Self {
+        Self::new(settings.max_receive_count)
+    }
+}
@@ -54,6 +54,24 @@ pub enum DispatchError {
     #[error("{0}")]
     UnexpectedError(#[from] Box<dyn Error + Send + Sync>),
 
-    #[error("{0}")]
+    #[error("Template not found: {0}")]
     TemplateNotFound(String),
 }
+
+impl DispatchError {
+    /// The code of errors dispatching the message again would not fix, so
+    /// it fails instead of being retried.
+    pub fn permanent_code(&self) -> Option<&'static str> {
+        match self {
+            DispatchError::NoRouteEligible => Some("no_route_eligible"),
+            DispatchError::NoTemplateAssignmentEligible => {
+                Some("no_template_assignment_eligible")
+            }
+            DispatchError::TemplateNotFound(_) => Some("template_not_found"),
+            DispatchError::RenderError(e) if e.is_over_limit() => {
+                Some("render_limit_exceeded")
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,12 +1,15 @@
 use error::DispatchError;
 use perroute_commons::{
     events::{ApplicationEventData, MessageCreatedEvent},
-    types::{MessageStatus, Timestamp},
+    types::{id::Id, MessageStatus, Timestamp},
 };
 use perroute_connectors::{DispatchRequest, ProviderPluginRepository};
 use perroute_storage::{
     active_record::message::MessageQuery,
-    models::{dispatcher_log::DispatcherLog, message::Message},
+    models::{
+        dispatcher_log::{DispatcherError, DispatcherLog},
+        message::Message,
+    },
     repository::{
         business_unit::BusinessUnitRepository,
         dispatcher_log::DispatcherLogRepository, message::MessageRepository,
@@ -15,19 +18,32 @@ use perroute_storage::{
         RepositoryError, TransactionalRepository,
     },
 };
-use perroute_template::{render::TemplateRenderPlugin, repository::TemplateLookup};
+use perroute_template::{
+    limits::RenderLimits, render::TemplateRenderPlugin,
+    repository::TemplateLookup,
+};
+use retry::RetryPolicy;
 use stack::Stacks;
+use std::time::Duration;
 use template::TemplateGenerator;
 
 pub mod error;
+pub mod retry;
 pub mod stack;
 pub mod template;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchOutcome {
+    Completed,
+    Retry(Duration),
+}
+
 #[derive(Clone)]
 pub struct Dispatcher<REPO, TRP, TR> {
     template_generator: TemplateGenerator<REPO, TRP, TR>,
     repository: REPO,
     stacks: Stacks<REPO>,
+    retry_policy: RetryPolicy,
 }
 
 impl<REPO, TRP, TR> Dispatcher<REPO, TRP, TR>
@@ -40,14 +56,20 @@ where
         template_generator: TemplateGenerator<REPO, TRP, TR>,
         repository: REPO,
         stacks: Stacks<REPO>,
+        retry_policy: RetryPolicy,
     ) -> Self {
         Self {
             template_generator,
             repository,
             stacks,
+            retry_policy,
         }
     }
 
+    pub fn repository(&self) -> &REPO {
+        &self.repository
+    }
+
     async fn retrieve_message(
         &self,
         event: &ApplicationEventData<MessageCreatedEvent>,
@@ -72,52 +94,113 @@ where
     async fn process(
         &self,
         message: &Message,
+        attempt: i32,
     ) -> Result<Vec<DispatcherLog>, DispatchError> {
-        let stack = self.stacks.create(&message).await?;
-        let template = self.template_generator.generate(&message).await?;
+        let stack = self.stacks.create(message).await?;
+        let template = self.template_generator.generate(message).await?;
         let request = DispatchRequest::create(
             message.id(),
             message.recipient(),
             &template,
         )?;
 
-        Ok(stack.dispatch(request).await)
+        Ok(stack.dispatch(request, attempt).await)
+    }
+
+    /// A message is retried only when every route failed, at least one of
+    /// them transiently, and the policy still allows another attempt.
+    fn outcome(
+        &self,
+        logs: &[DispatcherLog],
+        attempt: i32,
+    ) -> (MessageStatus, DispatchOutcome) {
+        if logs.iter().any(|l| *l.success()) {
+            return (MessageStatus::Dispatched, DispatchOutcome::Completed);
+        }
+
+        let attempt = attempt.max(0) as u32;
+        match logs.iter().any(DispatcherLog::is_transient_error)
+            && self.retry_policy.can_retry(attempt)
+        {
+            true => (
+                MessageStatus::Received,
+                DispatchOutcome::Retry(self.retry_policy.backoff(attempt)),
+            ),
+            false => (MessageStatus::Failed, DispatchOutcome::Completed),
+        }
     }
 
     pub async fn dispatch(
         self,
         event: ApplicationEventData<MessageCreatedEvent>,
-    ) -> Result<(), DispatchError> {
+    ) -> Result<DispatchOutcome, DispatchError> {
         let mut message = match self.retrieve_message(&event).await? {
             Some(message) => message,
-            None => return Ok(()),
+            None => return Ok(DispatchOutcome::Completed),
+        };
+
+        let attempt = message.attempts() + 1;
+        let logs = match self.process(&message, attempt).await {
+            Ok(logs) => logs,
+            Err(e) => match failure_log(message.id(), attempt, &e) {
+                Some(log) => {
+                    log::error!("Message {} failed: {e}", message.id());
+                    vec![log]
+                }
+                None => return Err(e),
+            },
         };
 
         let tx = self.repository.begin_transaction().await?;
-        let logs = self.process(&message).await?;
         let logs =
             DispatcherLogRepository::save_all_dispatch_logs(&tx, logs).await?;
 
-        message = message.set_status(if logs.iter().any(|l| *l.success()) {
-            MessageStatus::Dispatched
-        } else {
-            MessageStatus::Failed
-        });
+        let (status, outcome) = self.outcome(&logs, attempt);
+        if let DispatchOutcome::Retry(delay) = outcome {
+            log::warn!(
+                "Message {} failed on attempt {attempt}, retrying in {delay:?}",
+                message.id()
+            );
+        }
 
-        message = message.set_updated_at(Timestamp::now());
+        message = message
+            .set_status(status)
+            .set_attempts(attempt)
+            .set_updated_at(Timestamp::now());
 
         MessageRepository::update(&tx, message).await?;
 
         tx.commit().await?;
 
-        Ok(())
+        Ok(outcome)
     }
 }
+
+/// The log of a message failing for good before reaching any provider, so
+/// it is marked as failed instead of being retried.
+fn failure_log(
+    message_id: &Id,
+    attempt: i32,
+    error: &DispatchError,
+) -> Option<DispatcherLog> {
+    let code = error.permanent_code()?;
+    Some(DispatcherLog::build_failure(
+        message_id.clone(),
+        attempt,
+        DispatcherError::builder()
+            .code(code.to_string())
+            .description(error.to_string())
+            .build(),
+    ))
+}
+
 pub fn create_dispatcher<REPO, TRP, TL>(
     repository: REPO,
     template_render_plugin: TRP,
     template_lookup: TL,
     plugin_repository: ProviderPluginRepository,
+    retry_policy: RetryPolicy,
+    render_limits: RenderLimits,
 ) -> Dispatcher<REPO, TRP, TL>
 where
     REPO: Repository
@@ -134,8 +217,54 @@ where
             template_render_plugin,
             template_lookup,
             repository.clone(),
+            render_limits,
         ),
         repository: repository.clone(),
         stacks: Stacks::new(repository, plugin_repository),
+        retry_policy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perroute_template::render::RenderError;
+
+    #[test]
+    fn logs_permanent_failures_without_retrying() {
+        let message_id = Id::from("msg-1");
+        let over_limit = RenderError::TooLong {
+            length: 120,
+            limit: 100,
+        }
+        .in_part("title");
+
+        for (error, code) in [
+            (DispatchError::NoRouteEligible, "no_route_eligible"),
+            (
+                DispatchError::NoTemplateAssignmentEligible,
+                "no_template_assignment_eligible",
+            ),
+            (
+                DispatchError::TemplateNotFound("tpl-1".to_string()),
+                "template_not_found",
+            ),
+            (over_limit.into(), "render_limit_exceeded"),
+        ] {
+            let log = failure_log(&message_id, 3, &error).unwrap();
+            assert_eq!(log.message_id(), &message_id);
+            assert_eq!(*log.attempt(), 3);
+            assert_eq!(log.provider_id(), &None);
+            assert!(!log.success());
+            assert!(!log.is_transient_error());
+            assert_eq!(log.error().as_ref().unwrap().code(), code);
+        }
+    }
+
+    #[test]
+    fn leaves_other_failures_to_be_retried() {
+        let error = DispatchError::UnexpectedError("connection lost".into());
+
+        assert!(failure_log(&Id::from("msg-1"), 1, &error).is_none());
     }
 }
@@ -0,0 +1,106 @@
+use perroute_commons::configuration::settings::RetrySettings;
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff applied to messages whose dispatch failed with
+/// transient errors only.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(3600),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl From<&RetrySettings> for RetryPolicy {
+    fn from(settings: &RetrySettings) -> Self {
+        Self {
+            max_attempts: settings.max_attempts,
+            initial_delay: Duration::from_secs(settings.initial_delay),
+            max_delay: Duration::from_secs(settings.max_delay),
+            multiplier: settings.multiplier.max(1.0),
+            jitter: settings.jitter.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn can_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Delay before the attempt following `attempt` (1-based), randomly spread
+    /// by `jitter` so retried messages do not hit the provider all at once.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor =
+            rand::thread_rng().gen_range(1.0 - self.jitter..=1.0 + self.jitter);
+        self.base_delay(attempt).mul_f64(factor).min(self.max_delay)
+    }
+
+    fn base_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay =
+            self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+
+        Duration::try_from_secs_f64(delay)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: f64) -> RetryPolicy {
+        RetryPolicy::from(&RetrySettings {
+            max_attempts: 4,
+            initial_delay: 10,
+            max_delay: 60,
+            multiplier: 2.0,
+            jitter,
+        })
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max_delay() {
+        let policy = policy(0.0);
+
+        let delays = (1..=5)
+            .map(|a| policy.backoff(a).as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, vec![10, 20, 40, 60, 60]);
+    }
+
+    #[test]
+    fn backoff_jitter_stays_within_bounds() {
+        let policy = policy(0.5);
+
+        for _ in 0..100 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_secs(10));
+            assert!(delay <= Duration::from_secs(30));
+        }
+    }
+
+    #[test]
+    fn can_retry_until_max_attempts() {
+        let policy = policy(0.0);
+
+        assert!(policy.can_retry(3));
+        assert!(!policy.can_retry(4));
+    }
+}
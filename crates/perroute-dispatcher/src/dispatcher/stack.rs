@@ -1,13 +1,16 @@
 use std::sync::Arc;
 use perroute_connectors::{
-    DispatchRequest, ProviderPlugin, ProviderPluginRepository,
+    DispatchRequest, PluginDispatchError, ProviderPlugin,
+    ProviderPluginRepository,
 };
 use perroute_storage::{
     models::{
-        channel::Channel, dispatcher_log::DispatcherLog, message::Message,
+        channel::Channel,
+        dispatcher_log::{DispatcherError, DispatcherLog},
+        message::Message,
         route::Route,
     },
-    repository::route::RouteRepository,
+    repository::{channel::ChannelRepository, route::RouteRepository},
 };
 use super::DispatchError;
 
@@ -17,7 +20,7 @@ pub struct Stacks<R> {
     plugin_repository: ProviderPluginRepository,
 }
 
-impl<R: RouteRepository> Stacks<R> {
+impl<R: RouteRepository + ChannelRepository> Stacks<R> {
     pub fn new(
         repository: R,
         plugin_repository: ProviderPluginRepository,
@@ -32,51 +35,298 @@ impl<R: RouteRepository> Stacks<R> {
         &self,
         message: &Message,
     ) -> Result<DispatchStack, DispatchError> {
-        match RouteRepository::routes_to_dispatch(
+        let routes = RouteRepository::routes_to_dispatch(
             &self.repository,
             message.business_unit_id(),
             message.message_type_id(),
             message.dispatch_type(),
         )
-        .await
-        {
-            Ok(routes) if routes.is_empty() => {
-                Err(DispatchError::NoRouteEligible)
+        .await?;
+
+        let mut stack = Vec::with_capacity(routes.len());
+        for route in routes.into_iter().filter(|r| *r.enabled()) {
+            if let Some(entry) = self.entry(route).await? {
+                stack.push(entry);
             }
-            Ok(routes) => Ok(DispatchStack::default()),
+        }
+
+        let stack = DispatchStack::new(stack);
+        match stack.is_empty() {
+            true => Err(DispatchError::NoRouteEligible),
+            false => Ok(stack),
+        }
+    }
+
+    async fn entry(
+        &self,
+        route: Route,
+    ) -> Result<Option<StackEntry>, DispatchError> {
+        let channel = match ChannelRepository::find_channel(
+            &self.repository,
+            route.channel_id(),
+        )
+        .await?
+        {
+            Some(channel) if *channel.enabled() => channel,
+            _ => return Ok(None),
+        };
 
-            Err(e) => Err(DispatchError::from(e)),
+        match self.plugin_repository.get(channel.provider_id()) {
+            Some(plugin) => Ok(Some((route, channel, plugin.clone()))),
+            None => {
+                log::warn!(
+                    "Provider plugin {} not found for channel {}",
+                    channel.provider_id(),
+                    channel.id()
+                );
+                Ok(None)
+            }
         }
     }
 }
 
+type StackEntry = (Route, Channel, Arc<dyn ProviderPlugin>);
+
+/// Routes eligible for a message, ordered from the highest to the lowest
+/// priority.
 #[derive(Default)]
 pub struct DispatchStack {
-    stack: Vec<(Route, Channel, Arc<dyn ProviderPlugin>)>,
+    stack: Vec<StackEntry>,
 }
 
 impl DispatchStack {
+    pub fn new(mut stack: Vec<StackEntry>) -> Self {
+        stack.sort_by(|(a, _, _), (b, _, _)| b.priority().cmp(a.priority()));
+        Self { stack }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.stack.is_empty()
     }
 
+    /// Tries every route of the stack until one of them succeeds, returning
+    /// one log per route tried.
     pub async fn dispatch(
         self,
         request: DispatchRequest<'_>,
+        attempt: i32,
     ) -> Vec<DispatcherLog> {
-        // let mut logs = vec![];
+        let mut logs = vec![];
+
+        for (route, channel, plugin) in &self.stack {
+            let cfg = channel.configuration().merge(route);
+            let log = match plugin.dispatch(&cfg, &request).await {
+                Ok(_) => DispatcherLog::build_success(
+                    request.id().clone(),
+                    plugin.id(),
+                    attempt,
+                ),
+                Err(e) => {
+                    log::warn!(
+                        "Failed to dispatch message {} through route {}: {e}",
+                        request.id(),
+                        route.id()
+                    );
+                    DispatcherLog::build_error(
+                        request.id().clone(),
+                        plugin.id(),
+                        attempt,
+                        dispatcher_error(&e),
+                    )
+                }
+            };
+
+            let success = *log.success();
+            logs.push(log);
+            if success {
+                break;
+            }
+        }
+
+        logs
+    }
+}
+
+fn dispatcher_error(error: &PluginDispatchError) -> DispatcherError {
+    let code = match error {
+        PluginDispatchError::ProviderError(_) => "provider_error",
+    };
+
+    DispatcherError::builder()
+        .code(code.to_string())
+        .description(error.to_string())
+        .transient(error.is_transient())
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perroute_commons::types::{
+        dispatch_type::DispatchType,
+        id::Id,
+        name::Name,
+        priority::Priority,
+        recipient::{EmailAddress, EmailRecipient},
+        Configuration, ProviderId, Timestamp,
+    };
+    use perroute_connectors::{
+        generic_plugins, types::ConfigurationError, DispatchResponse, Request,
+    };
+    use perroute_template::{
+        render::{Escape, RenderError, Renderer},
+        template::{EmailTemplate, RenderedTemplateState, Template},
+    };
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct FakePlugin {
+        id: &'static str,
+        succeed: bool,
+        calls: Mutex<Vec<Configuration>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ProviderPlugin for FakePlugin {
+        fn id(&self) -> ProviderId {
+            ProviderId::from(self.id)
+        }
+
+        fn validate_configuration(
+            &self,
+            _: &DispatchType,
+            _: &Configuration,
+        ) -> Result<(), ConfigurationError> {
+            Ok(())
+        }
+
+        async fn dispatch(
+            &self,
+            configuration: &Configuration,
+            _: &DispatchRequest,
+        ) -> Result<DispatchResponse, PluginDispatchError> {
+            self.calls.lock().unwrap().push(configuration.clone());
+            match self.succeed {
+                true => Ok(DispatchResponse::default()),
+                false => Err(generic_plugins::Error::UnsupportedDispatchType(
+                    DispatchType::Email,
+                )
+                .into()),
+            }
+        }
+    }
+
+    struct PlainRenderer;
+
+    impl Renderer for PlainRenderer {
+        fn render(
+            &self,
+            template: &str,
+            _escape: Escape,
+        ) -> Result<String, RenderError> {
+            Ok(template.to_string())
+        }
+    }
+
+    fn plugin(id: &'static str, succeed: bool) -> Arc<FakePlugin> {
+        Arc::new(FakePlugin {
+            id,
+            succeed,
+            calls: Mutex::new(vec![]),
+        })
+    }
+
+    fn cfg(values: &[(&str, &str)]) -> Configuration {
+        Configuration::new(
+            &values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    fn entry(
+        priority: i64,
+        route_cfg: Configuration,
+        plugin: Arc<FakePlugin>,
+    ) -> StackEntry {
+        let channel = Channel::builder()
+            .id(Id::new())
+            .business_unit_id(Id::new())
+            .name(Name::try_from("channel").unwrap())
+            .provider_id(plugin.id())
+            .dispatch_type(DispatchType::Email)
+            .configuration(sqlx::types::Json(cfg(&[
+                ("host", "channel"),
+                ("from", "channel"),
+            ])))
+            .enabled(true)
+            .created_at(Timestamp::now())
+            .updated_at(Timestamp::now())
+            .build();
+
+        let route = Route::builder()
+            .id(Id::new())
+            .channel_id(channel.id().clone())
+            .message_type_id(Id::new())
+            .configuration(route_cfg)
+            .priority(Priority::new(priority))
+            .enabled(true)
+            .created_at(Timestamp::now())
+            .updated_at(Timestamp::now())
+            .build();
+
+        (route, channel, plugin)
+    }
+
+    fn template() -> EmailTemplate<RenderedTemplateState> {
+        match Template::email("subject", "html", "text")
+            .render(&PlainRenderer)
+            .unwrap()
+        {
+            Template::Email(template) => template,
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_falls_back_by_priority_until_first_success() {
+        let failing = plugin("failing", false);
+        let working = plugin("working", true);
+        let unused = plugin("unused", true);
 
-        // for (route, channel, plugin) in &self.stack {
-        //     let cfg = channel.configuration().merge(route);
-        //     logs.push(plugin.dispatch(&cfg, &request).await);
+        let stack = DispatchStack::new(vec![
+            entry(1, cfg(&[]), unused.clone()),
+            entry(10, cfg(&[("host", "route")]), failing.clone()),
+            entry(5, cfg(&[]), working.clone()),
+        ]);
 
-        //     if logs.last().is_some_and(|log| log.is_ok()) {
-        //         break;
-        //     }
-        // }
+        let id = Id::new();
+        let recipient = EmailRecipient::new(
+            EmailAddress::try_from("john@doe.com").unwrap(),
+        );
+        let template = template();
+        let logs = stack
+            .dispatch(Request::email(&id, &recipient, &template).into(), 2)
+            .await;
 
-        // logs
+        let logs = logs
+            .iter()
+            .map(|l| {
+                let provider_id = l.provider_id().as_ref().unwrap();
+                (provider_id.to_string(), *l.success(), *l.attempt())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            logs,
+            vec![
+                ("failing".to_string(), false, 2),
+                ("working".to_string(), true, 2)
+            ]
+        );
+        assert!(unused.calls.lock().unwrap().is_empty());
 
-        todo!()
+        let calls = failing.calls.lock().unwrap();
+        assert_eq!(calls[0], cfg(&[("host", "route"), ("from", "channel")]));
     }
 }
@@ -1,7 +1,8 @@
-use perroute_commons::types::vars::Vars;
+use perroute_commons::types::{vars::Vars, Timestamp};
 use perroute_storage::{
     active_record::{
         business_unit::BusinessUnitQuery, message_type::MessageTypeQuery,
+        template_assignment::QueryForDispatch,
     },
     models::{message::Message, template_assignment::TemplateAssignment},
     repository::{
@@ -11,6 +12,7 @@ use perroute_storage::{
     },
 };
 use perroute_template::{
+    limits::RenderLimits,
     render::{TemplateRenderContext, TemplateRenderPlugin},
     repository::{TemplateId, TemplateLookup},
     template::{RenderedTemplateState, Template},
@@ -22,6 +24,7 @@ pub struct TemplateGenerator<REPO, TRP, TR> {
     template_render_plugin: TRP,
     template_repository: TR,
     repository: REPO,
+    limits: RenderLimits,
 }
 
 impl<REPO, TRP, TR> TemplateGenerator<REPO, TRP, TR>
@@ -36,20 +39,32 @@ where
         template_render_plugin: TRP,
         template_repository: TR,
         repository: REPO,
+        limits: RenderLimits,
     ) -> Self {
         Self {
             template_render_plugin,
             template_repository,
             repository,
+            limits,
         }
     }
 
+    /// Assignment active at dispatch time, so seasonal templates take over
+    /// as soon as their window opens. Assignments without a template for the
+    /// message locale or a default one are skipped.
     async fn fetch_template_assignment(
         &self,
-        _: &Message,
+        message: &Message,
     ) -> Result<Option<TemplateAssignment>, RepositoryError> {
         TemplateAssignmentRepository::find_template_assingment_for_dispatch(
             &self.repository,
+            QueryForDispatch::builder()
+                .business_unit_id(message.business_unit_id())
+                .message_type_id(message.message_type_id())
+                .dispatch_type(message.dispatch_type())
+                .maybe_locale(message.locale().as_ref())
+                .reference_date(&Timestamp::now())
+                .build(),
         )
         .await
     }
@@ -67,14 +82,15 @@ where
 
         let message_type = MessageTypeRepository::get_message_type(
             &self.repository,
-            MessageTypeQuery::ById(message.business_unit_id()),
+            MessageTypeQuery::ById(message.message_type_id()),
         )
         .await?;
 
-        Ok(bu
-            .vars()
-            .merge(message_type.vars())
-            .merge(template_assignment.vars()))
+        Ok(TemplateAssignment::template_vars(
+            Some(template_assignment),
+            &bu,
+            &message_type,
+        ))
     }
 
     pub async fn generate(
@@ -88,15 +104,25 @@ where
             Some(template_assignment) => template_assignment,
             None => return Err(DispatchError::NoTemplateAssignmentEligible),
         };
-        let template_id =
-            match template_assignment.template_id(message.dispatch_type()) {
-                Some(id) => id,
-                None => {
-                    return Err(DispatchError::UnexpectedError(
-                        "the respective id was not found".to_owned().into(),
-                    ))
+        let template_id = match template_assignment.localized_template_id(
+            message.dispatch_type(),
+            message.locale().as_ref(),
+        ) {
+            Some((locale, id)) => {
+                if let (Some(requested), None) = (message.locale(), locale) {
+                    log::debug!(
+                        "No {requested} template in assignment {}, using the default one",
+                        template_assignment.id()
+                    );
                 }
-            };
+                id
+            }
+            None => {
+                return Err(DispatchError::UnexpectedError(
+                    "the respective id was not found".to_owned().into(),
+                ))
+            }
+        };
 
         let template_path = TemplateId::new(
             template_assignment.id(),
@@ -104,18 +130,86 @@ where
             &template_id,
         );
 
-        let template =
-            match self.template_repository.get(&template_path).await? {
-                Some(template) => template,
-                None => {
-                    return Err(DispatchError::TemplateNotFound("".to_owned()))
-                }
-            };
+        let found = match self.template_repository.get(&template_path).await? {
+            Some(found) => found,
+            None => {
+                return Err(DispatchError::TemplateNotFound(
+                    template_id.to_string(),
+                ))
+            }
+        };
+        let template = found.template();
 
         let vars = self.build_vars(&template_assignment, &message).await?;
-        let ctx = TemplateRenderContext::new(message.payload(), &vars);
+        let partials = self
+            .template_repository
+            .partials(message.business_unit_id())
+            .await?;
+        let ctx = TemplateRenderContext::new(message.payload(), &vars)
+            .with_partials(&partials)
+            .with_engine(template.engine())
+            .with_template(template_id, found.version());
         let renderer = self.template_render_plugin.renderer(ctx);
 
-        Ok(template.render(renderer.as_ref())?)
+        let rendered = template.render(renderer.as_ref())?;
+        self.limits.check(&rendered)?;
+
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perroute_storage::{
+        active_record::{
+            datasource::DataSource, message::MessageQuery,
+            template_assignment::TemplateAssignmentQuery, ActiveRecord,
+        },
+        repository::pgrepository::PgRepository,
+    };
+    use perroute_template::{
+        render::handlebars::HandlebarsPlugin,
+        repository::postgres::PgTemplateRepository,
+    };
+    use sqlx::PgPool;
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../perroute-storage/fixtures",
+            scripts("messages", "template_assignments")
+        )
+    )]
+    async fn builds_vars_from_the_message_type_of_the_message(pool: PgPool) {
+        sqlx::query(
+            r#"update message_types set vars = '{"sender": "Perroute"}' where id = 'mt-1'"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let datasource = DataSource::new(pool);
+        let message = Message::fetch_one(
+            &datasource,
+            MessageQuery::ById(&"msg-1".into()),
+        )
+        .await
+        .unwrap();
+        let assignment = TemplateAssignment::fetch_one(
+            &datasource,
+            TemplateAssignmentQuery::ById(&"ta-low".into()),
+        )
+        .await
+        .unwrap();
+        let generator = TemplateGenerator::new(
+            HandlebarsPlugin::new(),
+            PgTemplateRepository::new(datasource.clone()),
+            PgRepository::new(datasource),
+            RenderLimits::default(),
+        );
+
+        let vars = generator.build_vars(&assignment, &message).await.unwrap();
+        assert!(vars.contains_key("sender"));
     }
 }
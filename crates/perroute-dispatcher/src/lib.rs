@@ -1,2 +1,5 @@
+pub mod dead_letter;
 pub mod dispatcher;
 pub mod pooling;
+pub mod queue;
+pub mod scheduler;
@@ -1,37 +1,143 @@
-use perroute_commons::configuration::settings::Settings;
+use perroute_commons::configuration::settings::{
+    print_config_requested, DispatchQueueBackend, Settings,
+};
 use perroute_connectors::plugin_repository;
-use perroute_dispatcher::{dispatcher::create_dispatcher, pooling::SqsPooling};
-use perroute_storage::repository::pgrepository::PgRepository;
+use perroute_dispatcher::{
+    dead_letter::DeadLetterQueue,
+    dispatcher::{create_dispatcher, retry::RetryPolicy},
+    pooling::Pooling,
+    queue::{postgres::PgQueue, sqs::SqsQueue, DispatchQueue},
+    scheduler::Scheduler,
+};
+use perroute_storage::{create_datasource, repository::pgrepository::PgRepository};
 use perroute_template::{
-    render::handlebars::HandlebarsPlugin,
-    repository::aws_s3::AwsS3TemplateRepository,
+    limits::RenderLimits,
+    render::{
+        engines::RenderEngines, handlebars::HandlebarsPlugin,
+        minijinja::MiniJinjaPlugin, TemplateEngine,
+    },
+    repository,
 };
 use std::time::Duration;
 
+/// Used by the Postgres queue when no visibility timeout is configured.
+const DEFAULT_VISIBILITY_TIMEOUT: u64 = 300;
+
+/// Used by the scheduler when no scheduler settings are configured.
+const DEFAULT_SCHEDULER_INTERVAL: u64 = 5;
+const DEFAULT_SCHEDULER_BATCH_SIZE: i64 = 100;
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenvy::dotenv()?;
     let settings = Settings::load()?;
-    let aws_settings = settings.aws.as_ref().unwrap();
+    if print_config_requested() {
+        println!("{}", settings.dump()?);
+        return Ok(());
+    }
     let sdk_config = aws_config::load_from_env().await;
-    let sqs_client = aws_sdk_sqs::Client::new(&sdk_config);
+    let retry_policy = settings
+        .dispatcher
+        .as_ref()
+        .map(|dispatcher| RetryPolicy::from(&dispatcher.retry))
+        .unwrap_or_default();
+    let dead_letter = settings
+        .dispatcher
+        .as_ref()
+        .and_then(|dispatcher| dispatcher.dead_letter.as_ref());
+
+    let datasource = create_datasource(
+        settings
+            .database
+            .as_ref()
+            .ok_or("Missing database settings")?,
+    )
+    .await?;
+
+    let scheduler = match settings
+        .dispatcher
+        .as_ref()
+        .and_then(|dispatcher| dispatcher.scheduler.as_ref())
+    {
+        Some(scheduler) => {
+            Scheduler::from_settings(datasource.clone(), scheduler)
+        }
+        None => Scheduler::new(
+            datasource.clone(),
+            Duration::from_secs(DEFAULT_SCHEDULER_INTERVAL),
+            DEFAULT_SCHEDULER_BATCH_SIZE,
+        ),
+    };
+    tokio::spawn(async move { scheduler.run().await });
+
+    let template_repository =
+        repository::configured(&settings, &datasource).await?;
+
+    let mut handlebars = HandlebarsPlugin::new();
+    if let Some(helpers) = settings.template_helpers.as_ref() {
+        handlebars = handlebars.with_script_helpers(&helpers.scripts_path)?;
+    }
+    let render_engines = RenderEngines::default()
+        .with_engine(TemplateEngine::Handlebars, handlebars)
+        .with_engine(TemplateEngine::MiniJinja, MiniJinjaPlugin::new());
 
     let dispatcher = create_dispatcher(
-        PgRepository,
-        HandlebarsPlugin::new(),
-        AwsS3TemplateRepository::new(&sdk_config, "bucket"),
+        PgRepository::new(datasource.clone()),
+        render_engines,
+        template_repository,
         plugin_repository(),
+        retry_policy,
+        settings
+            .template_limits
+            .as_ref()
+            .map(RenderLimits::from)
+            .unwrap_or_default(),
     );
 
-    SqsPooling::new(
-        dispatcher,
-        sqs_client,
-        &aws_settings.digest_queue_url,
-        Duration::from_secs(1),
-        10,
-    )
-    .run()
-    .await?;
+    let queue_settings = settings.dispatch_queue.clone();
+    let backend = queue_settings
+        .as_ref()
+        .map(|queue| queue.backend.clone())
+        .unwrap_or_default();
+
+    let queue: Box<dyn DispatchQueue> = match backend {
+        DispatchQueueBackend::Sqs => {
+            let aws_settings =
+                settings.aws.as_ref().ok_or("Missing aws settings")?;
+            let mut queue = SqsQueue::new(
+                aws_sdk_sqs::Client::new(&sdk_config),
+                &aws_settings.digest_queue_url,
+            );
+            if let Some(dead_letter) = dead_letter {
+                queue = queue.with_dead_letter_queue(
+                    dead_letter
+                        .queue_url
+                        .as_ref()
+                        .ok_or("Missing dead letter queue url")?,
+                );
+            }
+            Box::new(queue)
+        }
+        DispatchQueueBackend::Postgres => {
+            let visibility_timeout = queue_settings
+                .and_then(|queue| queue.visibility_timeout)
+                .unwrap_or(DEFAULT_VISIBILITY_TIMEOUT);
+            Box::new(PgQueue::new(
+                datasource,
+                Duration::from_secs(visibility_timeout),
+            ))
+        }
+    };
+
+    let mut pooling =
+        Pooling::new(dispatcher, queue, Duration::from_secs(1), 10);
+
+    if let Some(dead_letter) = dead_letter {
+        pooling =
+            pooling.with_dead_letter_queue(DeadLetterQueue::from(dead_letter));
+    }
+
+    pooling.run().await?;
 
     Ok(())
 }
@@ -1,117 +1,169 @@
-use crate::dispatcher::Dispatcher;
-use aws_sdk_sqs::{
-    config::http::HttpResponse,
-    error::SdkError,
-    operation::receive_message::{ReceiveMessageError, ReceiveMessageOutput},
-    types::Message,
+use crate::{
+    dead_letter::DeadLetterQueue,
+    dispatcher::{DispatchOutcome, Dispatcher},
+    queue::{DispatchQueue, QueueError, QueueMessage},
+};
+use perroute_commons::{
+    events::{ApplicationEventData, MessageCreatedEvent},
+    types::{id::Id, Timestamp},
+};
+use perroute_storage::{
+    active_record::dead_letter::CreateDeadLetter,
+    repository::{dead_letter::DeadLetterRepository, Repository},
 };
-use perroute_commons::events::{ApplicationEventData, MessageCreatedEvent};
-use perroute_storage::repository::Repository;
 use perroute_template::{render::TemplateRenderPlugin, repository::TemplateLookup};
-use std::{sync::Arc, time::Duration};
+use std::time::Duration;
 use tap::TapFallible;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PooligError {
     #[error("Error receiving message: {0}")]
-    ReceiveMessageError(#[from] SdkError<ReceiveMessageError, HttpResponse>),
+    ReceiveMessageError(#[from] QueueError),
 
     #[error("Error running task: {0}")]
     TaskError(#[from] tokio::task::JoinError),
 }
 
-pub struct SqsPooling<REPO, TRP, TR> {
+pub struct Pooling<Q, REPO, TRP, TR> {
     interval: Duration,
     pool_size: i32,
-    sqs_client: Arc<aws_sdk_sqs::Client>,
-    queue_url: String,
+    queue: Q,
     dispatcher: Dispatcher<REPO, TRP, TR>,
+    dead_letter_queue: Option<DeadLetterQueue>,
 }
 
-impl<REPO, TRP, TR> SqsPooling<REPO, TRP, TR>
+impl<Q, REPO, TRP, TR> Pooling<Q, REPO, TRP, TR>
 where
+    Q: DispatchQueue,
     TRP: TemplateRenderPlugin + Clone + Send + Sync + 'static,
     TR: TemplateLookup + Clone + Send + Sync + 'static,
     REPO: Repository + Clone + Send + Sync + 'static,
 {
     pub fn new(
         dispatcher: Dispatcher<REPO, TRP, TR>,
-        sqs_client: aws_sdk_sqs::Client,
-        queue_url: &str,
+        queue: Q,
         interval: Duration,
         pool_size: i32,
     ) -> Self {
-        SqsPooling {
+        Pooling {
             dispatcher,
-            sqs_client: Arc::new(sqs_client),
+            queue,
             interval,
             pool_size,
-            queue_url: queue_url.to_string(),
+            dead_letter_queue: None,
         }
     }
 
-    async fn delete_message(&self, message: &Message) {
-        if let Some(receipt_handle) = message.receipt_handle() {
-            match self
-                .sqs_client
-                .delete_message()
-                .queue_url(&self.queue_url)
-                .receipt_handle(receipt_handle)
-                .send()
-                .await
-            {
-                Ok(_) => {
-                    log::info!("Message deleted successfully");
-                }
-                Err(e) => {
-                    log::error!("Failed to delete message: {e}");
-                }
+    pub fn with_dead_letter_queue(mut self, queue: DeadLetterQueue) -> Self {
+        self.dead_letter_queue = Some(queue);
+        self
+    }
+
+    async fn delete_message(&self, message: &QueueMessage) {
+        match self.queue.delete(message).await {
+            Ok(_) => {
+                log::info!("Message deleted successfully");
+            }
+            Err(e) => {
+                log::error!("Failed to delete message: {e}");
             }
-        } else {
-            log::warn!("Failed to delete message: receipt handle not found");
         }
     }
 
-    async fn receive_message(
+    /// Hides the message from consumers until the retry delay has elapsed,
+    /// so the queue hands it back once the backoff is over.
+    async fn retry_message(&self, message: &QueueMessage, delay: Duration) {
+        if let Err(e) = self.queue.retry(message, delay).await {
+            log::error!("Failed to change message visibility: {e}");
+        }
+    }
+
+    /// Moves the message out of the queue with the failure reason attached
+    /// and records it for later redrive. Queues that cannot dead-letter
+    /// leave the message to be redelivered.
+    async fn dead_letter(
         &self,
-    ) -> Result<ReceiveMessageOutput, PooligError> {
-        self.sqs_client
-            .receive_message()
-            .queue_url(&self.queue_url)
-            .max_number_of_messages(self.pool_size)
-            .send()
-            .await
-            .tap_err(|e| log::error!("Failed to receive messages from sqs:{e}"))
-            .map_err(Into::into)
+        message: &QueueMessage,
+        message_id: Option<Id>,
+        reason: String,
+    ) {
+        if !self.queue.can_dead_letter() {
+            log::error!("Message could not be processed: {reason}");
+            return;
+        }
+
+        if let Err(e) = self.queue.dead_letter(message, &reason).await {
+            log::error!("Failed to send message to dead letter queue: {e}");
+            return;
+        }
+
+        let create = CreateDeadLetter::builder()
+            .maybe_message_id(message_id)
+            .queue(self.queue.name())
+            .body(message.body().unwrap_or_default())
+            .reason(&reason)
+            .receive_count(message.receive_count())
+            .timestamp(Timestamp::now())
+            .build();
+
+        if let Err(e) = DeadLetterRepository::save_dead_letter(
+            self.dispatcher.repository(),
+            create,
+        )
+        .await
+        {
+            log::error!("Failed to record dead letter: {e}");
+        }
+
+        log::warn!("Message moved to dead letter queue: {reason}");
+    }
+
+    fn exhausted(&self, message: &QueueMessage) -> bool {
+        self.dead_letter_queue
+            .as_ref()
+            .is_some_and(|q| q.exhausted(message.receive_count()))
     }
 
     async fn inner_run(&self) -> Result<(), PooligError> {
-        let tasks = self
-            .receive_message()
-            .await?
-            .messages
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|message| {
-                to_event(&message).map(|event| (message, event))
-            })
-            .map(|(message, event)| {
-                let dispatcher = self.dispatcher.clone();
-                (message, tokio::spawn(dispatcher.dispatch(event)))
-            })
-            .collect::<Vec<_>>();
-
-        for (message, task) in tasks {
+        let mut tasks = vec![];
+
+        for message in self
+            .queue
+            .receive(self.pool_size)
+            .await
+            .tap_err(|e| log::error!("Failed to receive messages: {e}"))?
+        {
+            match to_event(&message) {
+                Ok(event) => {
+                    let message_id = event.entity_id().clone();
+                    let dispatcher = self.dispatcher.clone();
+                    let task = tokio::spawn(dispatcher.dispatch(event));
+                    tasks.push((message, message_id, task));
+                }
+                Err(reason) => self.dead_letter(&message, None, reason).await,
+            }
+        }
+
+        for (message, message_id, task) in tasks {
             match task.await {
-                Ok(Ok(_)) => {
+                Ok(Ok(DispatchOutcome::Completed)) => {
                     self.delete_message(&message).await;
                 }
+                Ok(Ok(DispatchOutcome::Retry(delay))) => {
+                    self.retry_message(&message, delay).await;
+                }
+                Ok(Err(e)) if self.exhausted(&message) => {
+                    self.dead_letter(&message, Some(message_id), e.to_string())
+                        .await;
+                }
                 Ok(Err(e)) => {
-                    //todo: send to a dlq?
                     log::error!("Task failed: {e}");
                 }
+                Err(e) if self.exhausted(&message) => {
+                    self.dead_letter(&message, Some(message_id), e.to_string())
+                        .await;
+                }
                 Err(e) => {
-                    //todo: send to a dlq?
                     log::error!("Task completation failed: {e}");
                 }
             }
@@ -135,11 +187,11 @@ where
 }
 
 fn to_event(
-    message: &Message,
-) -> Option<ApplicationEventData<MessageCreatedEvent>> {
-    message.body().and_then(|body| {
-        serde_json::from_str(body)
-            .tap_err(|e| log::error!("Failed to parse message body: {e}"))
-            .ok()
-    })
+    message: &QueueMessage,
+) -> Result<ApplicationEventData<MessageCreatedEvent>, String> {
+    let body = message.body().ok_or("Message without body")?;
+
+    serde_json::from_str(body)
+        .tap_err(|e| log::error!("Failed to parse message body: {e}"))
+        .map_err(|e| format!("Failed to parse message body: {e}"))
 }
@@ -0,0 +1,124 @@
+use perroute_storage::active_record::ActiveRecordError;
+use std::time::Duration;
+
+pub mod postgres;
+pub mod sqs;
+
+#[derive(Debug, thiserror::Error)]
+pub enum QueueError {
+    #[error("SQS error: {0}")]
+    SqsError(#[from] aws_sdk_sqs::Error),
+
+    #[error("Database error: {0}")]
+    ActiveRecordError(#[from] ActiveRecordError),
+
+    #[error("Dead letter queue not configured")]
+    DeadLetterQueueNotConfigured,
+}
+
+/// A message received from a [`DispatchQueue`], identified by a handle only
+/// meaningful to the queue that delivered it.
+#[derive(Debug, Clone)]
+pub struct QueueMessage {
+    handle: String,
+    body: Option<String>,
+    receive_count: i32,
+}
+
+impl QueueMessage {
+    pub fn new(
+        handle: impl Into<String>,
+        body: Option<String>,
+        receive_count: i32,
+    ) -> Self {
+        Self {
+            handle: handle.into(),
+            body,
+            receive_count,
+        }
+    }
+
+    pub fn handle(&self) -> &str {
+        &self.handle
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn receive_count(&self) -> i32 {
+        self.receive_count
+    }
+}
+
+/// Source of the messages the dispatcher consumes. Received messages stay
+/// hidden from other consumers until they are deleted, retried or
+/// dead-lettered, or until the queue's visibility timeout expires.
+#[async_trait::async_trait]
+pub trait DispatchQueue: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Whether [`DispatchQueue::dead_letter`] can take messages out of the
+    /// queue as it is configured.
+    fn can_dead_letter(&self) -> bool;
+
+    async fn receive(
+        &self,
+        max_messages: i32,
+    ) -> Result<Vec<QueueMessage>, QueueError>;
+
+    async fn delete(&self, message: &QueueMessage) -> Result<(), QueueError>;
+
+    /// Hands the message back to consumers once `delay` has elapsed.
+    async fn retry(
+        &self,
+        message: &QueueMessage,
+        delay: Duration,
+    ) -> Result<(), QueueError>;
+
+    /// Takes the message out of the queue for good, keeping `reason` along
+    /// with it where the backend allows.
+    async fn dead_letter(
+        &self,
+        message: &QueueMessage,
+        reason: &str,
+    ) -> Result<(), QueueError>;
+}
+
+#[async_trait::async_trait]
+impl<Q: DispatchQueue + ?Sized> DispatchQueue for Box<Q> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn can_dead_letter(&self) -> bool {
+        (**self).can_dead_letter()
+    }
+
+    async fn receive(
+        &self,
+        max_messages: i32,
+    ) -> Result<Vec<QueueMessage>, QueueError> {
+        (**self).receive(max_messages).await
+    }
+
+    async fn delete(&self, message: &QueueMessage) -> Result<(), QueueError> {
+        (**self).delete(message).await
+    }
+
+    async fn retry(
+        &self,
+        message: &QueueMessage,
+        delay: Duration,
+    ) -> Result<(), QueueError> {
+        (**self).retry(message, delay).await
+    }
+
+    async fn dead_letter(
+        &self,
+        message: &QueueMessage,
+        reason: &str,
+    ) -> Result<(), QueueError> {
+        (**self).dead_letter(message, reason).await
+    }
+}
@@ -0,0 +1,205 @@
+use super::{DispatchQueue, QueueError, QueueMessage};
+use perroute_commons::types::{id::Id, Timestamp};
+use perroute_storage::{
+    active_record::{
+        datasource::{DataSource, NonTransactionalDataSource},
+        dispatch_job::DispatchJobActiveRecord,
+    },
+    models::dispatch_job::DispatchJob,
+};
+use std::time::Duration;
+
+const QUEUE_NAME: &str = "dispatch_jobs";
+
+/// Dispatch queue backed by the `dispatch_jobs` table. Jobs are claimed with
+/// `FOR UPDATE SKIP LOCKED`, so several dispatchers can share the table.
+#[derive(Debug, Clone)]
+pub struct PgQueue {
+    datasource: DataSource<NonTransactionalDataSource>,
+    visibility_timeout: Duration,
+}
+
+impl PgQueue {
+    pub fn new(
+        datasource: DataSource<NonTransactionalDataSource>,
+        visibility_timeout: Duration,
+    ) -> Self {
+        Self {
+            datasource,
+            visibility_timeout,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchQueue for PgQueue {
+    fn name(&self) -> &str {
+        QUEUE_NAME
+    }
+
+    /// Dead jobs stay in the table, so burying needs no extra setup.
+    fn can_dead_letter(&self) -> bool {
+        true
+    }
+
+    async fn receive(
+        &self,
+        max_messages: i32,
+    ) -> Result<Vec<QueueMessage>, QueueError> {
+        let jobs = DispatchJob::claim(
+            &self.datasource,
+            max_messages.into(),
+            self.visibility_timeout.as_secs_f64(),
+            &Timestamp::now(),
+        )
+        .await?;
+
+        Ok(jobs
+            .into_iter()
+            .map(|job| {
+                QueueMessage::new(
+                    job.id().to_string(),
+                    Some(job.body().clone()),
+                    *job.attempts(),
+                )
+            })
+            .collect())
+    }
+
+    async fn delete(&self, message: &QueueMessage) -> Result<(), QueueError> {
+        DispatchJob::complete(&self.datasource, &Id::from(message.handle()))
+            .await?;
+        Ok(())
+    }
+
+    async fn retry(
+        &self,
+        message: &QueueMessage,
+        delay: Duration,
+    ) -> Result<(), QueueError> {
+        DispatchJob::reschedule(
+            &self.datasource,
+            &Id::from(message.handle()),
+            delay.as_secs_f64(),
+            &Timestamp::now(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn dead_letter(
+        &self,
+        message: &QueueMessage,
+        reason: &str,
+    ) -> Result<(), QueueError> {
+        DispatchJob::bury(
+            &self.datasource,
+            &Id::from(message.handle()),
+            reason,
+            &Timestamp::now(),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    fn queue(pool: PgPool) -> PgQueue {
+        PgQueue::new(DataSource::new(pool), Duration::from_secs(300))
+    }
+
+    async fn job(
+        pool: &PgPool,
+        id: &str,
+    ) -> Option<(i32, bool, Option<String>)> {
+        sqlx::query_as(
+            "select attempts, dead_at is not null, last_error from dispatch_jobs where id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../perroute-storage/fixtures",
+            scripts("dispatch_jobs")
+        )
+    )]
+    async fn receive_claims_visible_jobs_and_hides_them(pool: PgPool) {
+        let queue = queue(pool.clone());
+
+        let messages = queue.receive(10).await.unwrap();
+        let again = queue.receive(10).await.unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].handle(), "job-visible");
+        assert_eq!(messages[0].receive_count(), 1);
+        assert!(again.is_empty());
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../perroute-storage/fixtures",
+            scripts("dispatch_jobs")
+        )
+    )]
+    async fn delete_removes_the_job(pool: PgPool) {
+        let queue = queue(pool.clone());
+        let message = queue.receive(1).await.unwrap().remove(0);
+
+        queue.delete(&message).await.unwrap();
+
+        assert_eq!(job(&pool, "job-visible").await, None);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../perroute-storage/fixtures",
+            scripts("dispatch_jobs")
+        )
+    )]
+    async fn retry_hands_the_job_back_after_the_delay(pool: PgPool) {
+        let queue = queue(pool.clone());
+        let message = queue.receive(1).await.unwrap().remove(0);
+
+        queue.retry(&message, Duration::ZERO).await.unwrap();
+        let redelivered = queue.receive(1).await.unwrap();
+
+        assert_eq!(redelivered.len(), 1);
+        assert_eq!(redelivered[0].receive_count(), 2);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../../perroute-storage/fixtures",
+            scripts("dispatch_jobs")
+        )
+    )]
+    async fn dead_letter_buries_the_job(pool: PgPool) {
+        let queue = queue(pool.clone());
+        let message = queue.receive(1).await.unwrap().remove(0);
+
+        queue.dead_letter(&message, "poison").await.unwrap();
+
+        assert!(queue.can_dead_letter());
+        assert!(queue.receive(10).await.unwrap().is_empty());
+        assert_eq!(
+            job(&pool, "job-visible").await,
+            Some((1, true, Some("poison".to_string())))
+        );
+    }
+}
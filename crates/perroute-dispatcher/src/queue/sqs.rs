@@ -0,0 +1,144 @@
+use super::{DispatchQueue, QueueError, QueueMessage};
+use aws_sdk_sqs::types::{MessageAttributeValue, MessageSystemAttributeName};
+use std::{sync::Arc, time::Duration};
+
+/// Longest visibility timeout accepted by SQS (12 hours).
+const MAX_VISIBILITY_TIMEOUT: u64 = 43_200;
+
+const DEAD_LETTER_REASON_ATTRIBUTE: &str = "dead_letter_reason";
+
+#[derive(Debug, Clone)]
+pub struct SqsQueue {
+    sqs_client: Arc<aws_sdk_sqs::Client>,
+    queue_url: String,
+    dead_letter_queue_url: Option<String>,
+}
+
+impl SqsQueue {
+    pub fn new(sqs_client: aws_sdk_sqs::Client, queue_url: &str) -> Self {
+        Self {
+            sqs_client: Arc::new(sqs_client),
+            queue_url: queue_url.to_string(),
+            dead_letter_queue_url: None,
+        }
+    }
+
+    pub fn with_dead_letter_queue(mut self, queue_url: &str) -> Self {
+        self.dead_letter_queue_url = Some(queue_url.to_string());
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchQueue for SqsQueue {
+    fn name(&self) -> &str {
+        &self.queue_url
+    }
+
+    fn can_dead_letter(&self) -> bool {
+        self.dead_letter_queue_url.is_some()
+    }
+
+    async fn receive(
+        &self,
+        max_messages: i32,
+    ) -> Result<Vec<QueueMessage>, QueueError> {
+        let output = self
+            .sqs_client
+            .receive_message()
+            .queue_url(&self.queue_url)
+            .max_number_of_messages(max_messages)
+            .message_system_attribute_names(
+                MessageSystemAttributeName::ApproximateReceiveCount,
+            )
+            .send()
+            .await
+            .map_err(aws_sdk_sqs::Error::from)?;
+
+        Ok(output
+            .messages
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|message| {
+                let receive_count = message
+                    .attributes()
+                    .and_then(|a| {
+                        a.get(
+                            &MessageSystemAttributeName::ApproximateReceiveCount,
+                        )
+                    })
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(1);
+
+                match message.receipt_handle {
+                    Some(handle) => Some(QueueMessage::new(
+                        handle,
+                        message.body,
+                        receive_count,
+                    )),
+                    None => {
+                        log::warn!("Ignoring message without receipt handle");
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    async fn delete(&self, message: &QueueMessage) -> Result<(), QueueError> {
+        self.sqs_client
+            .delete_message()
+            .queue_url(&self.queue_url)
+            .receipt_handle(message.handle())
+            .send()
+            .await
+            .map_err(aws_sdk_sqs::Error::from)?;
+        Ok(())
+    }
+
+    async fn retry(
+        &self,
+        message: &QueueMessage,
+        delay: Duration,
+    ) -> Result<(), QueueError> {
+        let timeout = delay.as_secs().min(MAX_VISIBILITY_TIMEOUT) as i32;
+        self.sqs_client
+            .change_message_visibility()
+            .queue_url(&self.queue_url)
+            .receipt_handle(message.handle())
+            .visibility_timeout(timeout)
+            .send()
+            .await
+            .map_err(aws_sdk_sqs::Error::from)?;
+        Ok(())
+    }
+
+    async fn dead_letter(
+        &self,
+        message: &QueueMessage,
+        reason: &str,
+    ) -> Result<(), QueueError> {
+        let dead_letter_queue_url = self
+            .dead_letter_queue_url
+            .as_ref()
+            .ok_or(QueueError::DeadLetterQueueNotConfigured)?;
+
+        self.sqs_client
+            .send_message()
+            .queue_url(dead_letter_queue_url)
+            .message_body(message.body().unwrap_or_default())
+            .message_attributes(
+                DEAD_LETTER_REASON_ATTRIBUTE,
+                MessageAttributeValue::builder()
+                    .data_type("String")
+                    .string_value(reason)
+                    .build()
+                    .expect("data type is set"),
+            )
+            .send()
+            .await
+            .map_err(aws_sdk_sqs::Error::from)?;
+
+        self.delete(message).await
+    }
+}
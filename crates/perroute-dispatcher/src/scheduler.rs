@@ -0,0 +1,148 @@
+use perroute_commons::{
+    configuration::settings::SchedulerSettings,
+    events::{ApplicationEvent, MessageCreatedEvent},
+    types::{actor::Actor, Timestamp},
+};
+use perroute_storage::{
+    active_record::{
+        datasource::{
+            DataSource, NonTransactionalDataSource, TransactionalDataSource,
+        },
+        message::ScheduledMessageActiveRecord,
+        ActiveRecord, ActiveRecordError,
+    },
+    models::{event::DbEvent, message::Message},
+};
+use std::time::Duration;
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchedulerError {
+    #[error("Database error: {0}")]
+    ActiveRecordError(#[from] ActiveRecordError),
+
+    #[error("Failed to serialize event: {0}")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+/// Releases scheduled messages once they are due, publishing a
+/// [`MessageCreatedEvent`] for each of them in the same transaction so they
+/// reach the dispatch queue through the event outbox.
+pub struct Scheduler {
+    datasource: DataSource<NonTransactionalDataSource>,
+    interval: Duration,
+    batch_size: i64,
+}
+
+impl Scheduler {
+    pub fn new(
+        datasource: DataSource<NonTransactionalDataSource>,
+        interval: Duration,
+        batch_size: i64,
+    ) -> Self {
+        Self {
+            datasource,
+            interval,
+            batch_size,
+        }
+    }
+
+    pub fn from_settings(
+        datasource: DataSource<NonTransactionalDataSource>,
+        settings: &SchedulerSettings,
+    ) -> Self {
+        Self::new(
+            datasource,
+            Duration::from_secs(settings.interval),
+            settings.batch_size,
+        )
+    }
+
+    async fn release(&self) -> Result<usize, SchedulerError> {
+        let tx = self.datasource.begin_transaction().await?;
+        let timestamp = Timestamp::now();
+
+        let released = match self.publish_due(&tx, &timestamp).await {
+            Ok(released) => released,
+            Err(e) => {
+                tx.rollback().await.tap_err(|e| {
+                    log::error!("Failed to rollback transaction: {e}")
+                })?;
+                return Err(e);
+            }
+        };
+
+        tx.commit()
+            .await
+            .tap_err(|e| log::error!("Failed to commit transaction: {e}"))?;
+
+        Ok(released)
+    }
+
+    async fn publish_due(
+        &self,
+        tx: &DataSource<TransactionalDataSource>,
+        timestamp: &Timestamp,
+    ) -> Result<usize, SchedulerError> {
+        let messages =
+            Message::release_due(tx, self.batch_size, timestamp).await?;
+
+        for message in &messages {
+            let event = MessageCreatedEvent::from(message)
+                .to_event(&Actor::System, timestamp);
+
+            DbEvent::create(tx, DbEvent::try_from(event)?).await?;
+        }
+
+        Ok(messages.len())
+    }
+
+    pub async fn run(&self) {
+        loop {
+            match self.release().await {
+                Ok(0) => {}
+                Ok(released) => {
+                    log::info!("{released} scheduled messages released")
+                }
+                Err(e) => {
+                    log::error!("Failed to release scheduled messages: {e}")
+                }
+            }
+
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(
+        migrations = "../perroute-storage/migrations",
+        fixtures(
+            path = "../../perroute-storage/fixtures",
+            scripts("messages", "scheduled_messages")
+        )
+    )]
+    async fn release_publishes_an_event_per_due_message(pool: PgPool) {
+        let scheduler = Scheduler::new(
+            DataSource::new(pool.clone()),
+            Duration::from_secs(1),
+            10,
+        );
+
+        assert_eq!(scheduler.release().await.unwrap(), 2);
+        assert_eq!(scheduler.release().await.unwrap(), 0);
+
+        let entities: Vec<String> = sqlx::query_scalar(
+            "select entity_id from event_messages order by entity_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(entities, vec!["msg-due", "msg-overdue"]);
+    }
+}
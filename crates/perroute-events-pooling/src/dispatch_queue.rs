@@ -0,0 +1,77 @@
+use crate::publisher::{Publisher, PublisherOutput, PublisherResult};
+use perroute_commons::{
+    events::{Event, EventType},
+    types::Timestamp,
+};
+use perroute_storage::{
+    active_record::{
+        datasource::{DataSource, NonTransactionalDataSource},
+        dispatch_job::CreateDispatchJob,
+        ActiveRecord, ActiveRecordError,
+    },
+    models::dispatch_job::DispatchJob,
+};
+use tap::TapFallible;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DispatchQueuePublisherError {
+    #[error("Failed to serialize event: {0}")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("Failed to enqueue dispatch job: {0}")]
+    ActiveRecordError(#[from] ActiveRecordError),
+
+    #[error("Event {0} can not be dispatched")]
+    NotDispatchable(EventType),
+}
+
+/// Publishes message created events straight into the `dispatch_jobs` table,
+/// for deployments where the dispatcher consumes from Postgres instead of
+/// SQS.
+pub struct DispatchQueuePublisher {
+    datasource: DataSource<NonTransactionalDataSource>,
+}
+
+impl DispatchQueuePublisher {
+    pub fn new(datasource: DataSource<NonTransactionalDataSource>) -> Self {
+        Self { datasource }
+    }
+
+    async fn enqueue(
+        &self,
+        event: &Event,
+    ) -> Result<DispatchJob, DispatchQueuePublisherError> {
+        let Event::MessageCreated(event_data) = event else {
+            return Err(DispatchQueuePublisherError::NotDispatchable(
+                event.event_type().clone(),
+            ));
+        };
+
+        Ok(DispatchJob::create(
+            &self.datasource,
+            CreateDispatchJob::builder()
+                .message_id(event_data.entity_id().clone())
+                .body(serde_json::to_string(event_data)?)
+                .timestamp(Timestamp::now())
+                .build(),
+        )
+        .await?)
+    }
+}
+
+impl Publisher for DispatchQueuePublisher {
+    async fn publish<'e>(&self, events: Vec<&'e Event>) -> PublisherResult<'e> {
+        let mut publisher_output = PublisherOutput::new();
+
+        for event in events {
+            match self.enqueue(event).await.tap_err(|e| {
+                log::error!("Failed to publish event {}: {e}", event.id())
+            }) {
+                Ok(_) => publisher_output.push_success(event),
+                Err(e) => publisher_output.push_failed(event, e.into()),
+            }
+        }
+
+        Ok(publisher_output)
+    }
+}
@@ -1,3 +1,4 @@
+pub mod dispatch_queue;
 pub mod pooling;
 pub mod publisher;
 pub mod sns;
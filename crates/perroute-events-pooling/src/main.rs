@@ -1,5 +1,17 @@
-use perroute_commons::{configuration::settings::Settings, events::EventType};
-use perroute_events_pooling::{pooling::Pooling, sns::SnsPublisher};
+use perroute_commons::{
+    configuration::settings::{
+        print_config_requested, DispatchQueueBackend, Settings,
+    },
+    events::EventType,
+};
+use perroute_events_pooling::{
+    dispatch_queue::DispatchQueuePublisher, pooling::Pooling,
+    publisher::Publisher, sns::SnsPublisher,
+};
+use perroute_storage::active_record::datasource::{
+    DataSource, NonTransactionalDataSource,
+};
+use std::collections::HashSet;
 use perroute_storage::create_datasource;
 use std::error::Error;
 use tap::TapFallible;
@@ -12,36 +24,75 @@ async fn main() -> Result<(), Box<dyn Error>> {
     log::info!("Starting events pooling service...");
     let settings = Settings::load()
         .tap_err(|e| log::error!("Failed to load settings: {e}"))?;
+    if print_config_requested() {
+        println!("{}", settings.dump()?);
+        return Ok(());
+    }
     let event_pooling_settings = settings
         .pooling
         .ok_or("Event pooling settings are missing")?;
 
-    let sdk_config = aws_config::load_from_env().await;
-    let sns_client = aws_sdk_sns::Client::new(&sdk_config);
-
     let datasource = create_datasource(settings.database.as_ref().unwrap())
         .await
         .unwrap();
 
-    let publisher =
-        SnsPublisher::new(sns_client, event_pooling_settings.topic_arn);
+    let publishable_event_types = EventType::parse(
+        &event_pooling_settings
+            .publishable_events
+            .unwrap_or("".to_string()),
+    )
+    .tap_err(|e| log::error!("Failed to parse publishable events: {e}"))?;
+
+    let backend = settings
+        .dispatch_queue
+        .map(|queue| queue.backend)
+        .unwrap_or_default();
+
+    match backend {
+        DispatchQueueBackend::Sqs => {
+            let sdk_config = aws_config::load_from_env().await;
+            let sns_client = aws_sdk_sns::Client::new(&sdk_config);
+            let publisher =
+                SnsPublisher::new(sns_client, event_pooling_settings.topic_arn);
+            run(
+                datasource,
+                publisher,
+                event_pooling_settings.interval,
+                event_pooling_settings.max_events,
+                publishable_event_types,
+            )
+            .await;
+        }
+        DispatchQueueBackend::Postgres => {
+            let publisher = DispatchQueuePublisher::new(datasource.clone());
+            run(
+                datasource,
+                publisher,
+                event_pooling_settings.interval,
+                event_pooling_settings.max_events,
+                publishable_event_types,
+            )
+            .await;
+        }
+    }
 
+    Ok(())
+}
+
+async fn run<P: Publisher + Send + Sync>(
+    datasource: DataSource<NonTransactionalDataSource>,
+    publisher: P,
+    interval: u64,
+    max_events: u64,
+    publishable_event_types: HashSet<EventType>,
+) {
     let pooling = Pooling::new(
         datasource,
         publisher,
-        event_pooling_settings.interval,
-        event_pooling_settings.max_events,
-        EventType::parse(
-            &event_pooling_settings
-                .publishable_events
-                .unwrap_or("".to_string()),
-        )
-        .tap_err(|e| log::error!("Failed to parse publishable events: {e}"))?,
+        interval,
+        max_events,
+        publishable_event_types,
     );
 
-    tokio::spawn(async move { pooling.run().await })
-        .await
-        .tap_err(|e| log::error!("Failed to join task: {e}"))?;
-
-    Ok(())
+    pooling.run().await;
 }
@@ -1,4 +1,4 @@
-use crate::sns::SnsPublisherError;
+use crate::{dispatch_queue::DispatchQueuePublisherError, sns::SnsPublisherError};
 use perroute_commons::events::Event;
 use std::future::Future;
 
@@ -7,7 +7,16 @@ pub type PublisherResult<'e> = Result<PublisherOutput<'e>, PublisherError>;
 #[derive(Debug, thiserror::Error)]
 pub enum PublisherError {
     #[error("SNS publisher error: {0}")]
-    SnsPublisherError(#[from] SnsPublisherError),
+    SnsPublisherError(#[from] Box<SnsPublisherError>),
+
+    #[error("Dispatch queue publisher error: {0}")]
+    DispatchQueuePublisherError(#[from] DispatchQueuePublisherError),
+}
+
+impl From<SnsPublisherError> for PublisherError {
+    fn from(error: SnsPublisherError) -> Self {
+        Box::new(error).into()
+    }
 }
 
 pub trait Publisher {
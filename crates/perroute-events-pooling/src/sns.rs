@@ -148,6 +148,15 @@ fn to_entry(event: &Event) -> SnsPublisherResult<PublishBatchRequestEntry> {
         Event::TemplateAssignmentDeleted(event_data) => {
             from_event_data(event_data)
         }
+        Event::TemplateVersionCreated(event_data) => {
+            from_event_data(event_data)
+        }
+        Event::TemplateVersionPublished(event_data) => {
+            from_event_data(event_data)
+        }
+        Event::TemplateVersionRolledBack(event_data) => {
+            from_event_data(event_data)
+        }
     }
 }
 
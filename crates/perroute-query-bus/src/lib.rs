@@ -1,8 +1,15 @@
 pub mod queries;
 
 use perroute_commons::types::actor::Actor;
-use perroute_storage::active_record::datasource::{
-    DataSource, NonTransactionalDataSource,
+use perroute_storage::active_record::{
+    datasource::{DataSource, NonTransactionalDataSource},
+    ActiveRecordError,
+};
+use queries::{
+    dead_letter::QueryDeadLettersHandler,
+    template_assignment::QueryTemplateAssignmentsHandler,
+    template_lint::TemplateLintHandler,
+    template_preview::TemplatePreviewHandler,
 };
 
 use std::{
@@ -17,6 +24,10 @@ pub fn create_query_bus(
     repository: DataSource<NonTransactionalDataSource>,
 ) -> impl QueryBus + Clone {
     DefaultQueryBus::new(repository) //.register(QueryBusinessUnitsHandler)
+        .register(QueryDeadLettersHandler)
+        .register(QueryTemplateAssignmentsHandler)
+        .register(TemplateLintHandler)
+        .register(TemplatePreviewHandler)
 }
 
 pub type QueryBusResult<T> = Result<T, QueryBusError>;
@@ -25,6 +36,9 @@ pub type QueryBusResult<T> = Result<T, QueryBusError>;
 pub enum QueryBusError {
     #[error("Command handler not found for command: {0}")]
     QueryHandlerNotFound(String),
+
+    #[error("{0}")]
+    ActiveRecordError(#[from] ActiveRecordError),
 }
 
 pub struct QueryBusContext<'c> {
@@ -0,0 +1,26 @@
+use crate::{Query, QueryBusContext, QueryBusResult, QueryHandler};
+use perroute_storage::{
+    active_record::{dead_letter::DeadLetterQuery, ActiveRecord},
+    models::dead_letter::DeadLetter,
+};
+
+/// Dead letters that were not redriven yet, oldest first.
+#[derive(Debug)]
+pub struct PendingDeadLettersQuery;
+
+impl Query for PendingDeadLettersQuery {}
+
+pub struct QueryDeadLettersHandler;
+
+impl QueryHandler for QueryDeadLettersHandler {
+    type Query = PendingDeadLettersQuery;
+    type Output = Vec<DeadLetter>;
+
+    async fn handle(
+        &self,
+        _query: &Self::Query,
+        ctx: QueryBusContext<'_>,
+    ) -> QueryBusResult<Self::Output> {
+        Ok(DeadLetter::query(ctx.repository, DeadLetterQuery::Pending).await?)
+    }
+}
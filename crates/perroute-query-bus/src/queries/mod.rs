@@ -1 +1,5 @@
 //pub mod business_unit;
+pub mod dead_letter;
+pub mod template_assignment;
+pub mod template_lint;
+pub mod template_preview;
@@ -0,0 +1,36 @@
+use crate::{Query, QueryBusContext, QueryBusResult, QueryHandler};
+use bon::Builder;
+use perroute_commons::types::id::Id;
+use perroute_storage::{
+    active_record::{
+        template_assignment::TemplateAssignmentQuery, ActiveRecord,
+    },
+    models::template_assignment::TemplateAssignment,
+};
+
+/// Template assignments of a business unit, grouped by message type.
+#[derive(Debug, Builder)]
+pub struct BusinessUnitTemplateAssignmentsQuery {
+    business_unit_id: Id,
+}
+
+impl Query for BusinessUnitTemplateAssignmentsQuery {}
+
+pub struct QueryTemplateAssignmentsHandler;
+
+impl QueryHandler for QueryTemplateAssignmentsHandler {
+    type Query = BusinessUnitTemplateAssignmentsQuery;
+    type Output = Vec<TemplateAssignment>;
+
+    async fn handle(
+        &self,
+        query: &Self::Query,
+        ctx: QueryBusContext<'_>,
+    ) -> QueryBusResult<Self::Output> {
+        Ok(TemplateAssignment::query(
+            ctx.repository,
+            TemplateAssignmentQuery::ByBusinessUnit(&query.business_unit_id),
+        )
+        .await?)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{Query, QueryBusContext, QueryBusResult, QueryHandler};
+use bon::Builder;
+use derive_getters::Getters;
+use perroute_commons::types::{
+    dispatch_type::DispatchType, id::Id, schema::Schema, vars::Vars,
+};
+use perroute_storage::{
+    active_record::{
+        business_unit::BusinessUnitQuery, message_type::MessageTypeQuery,
+        template_assignment::TemplateAssignmentQuery, ActiveRecord,
+    },
+    models::{
+        business_unit::BusinessUnit, message_type::MessageType,
+        template_assignment::TemplateAssignment,
+    },
+};
+
+/// What a template is checked against when it is saved: the payload schema
+/// and the vars of every template assignment using it.
+#[derive(Debug, Builder)]
+pub struct TemplateLintQuery {
+    template_id: Id,
+    dispatch_type: DispatchType,
+}
+
+impl Query for TemplateLintQuery {}
+
+#[derive(Debug, Getters)]
+pub struct TemplateLintContext {
+    template_assignment_id: Id,
+    schema: Schema,
+    vars: Vars,
+}
+
+pub struct TemplateLintHandler;
+
+impl QueryHandler for TemplateLintHandler {
+    type Query = TemplateLintQuery;
+    type Output = Vec<TemplateLintContext>;
+
+    async fn handle(
+        &self,
+        query: &Self::Query,
+        ctx: QueryBusContext<'_>,
+    ) -> QueryBusResult<Self::Output> {
+        let assignments = TemplateAssignment::query(
+            ctx.repository,
+            TemplateAssignmentQuery::ByTemplate(
+                &query.template_id,
+                &query.dispatch_type,
+            ),
+        )
+        .await?;
+
+        let mut contexts = Vec::with_capacity(assignments.len());
+        for assignment in assignments {
+            let business_unit = BusinessUnit::fetch_optional(
+                ctx.repository,
+                BusinessUnitQuery::ById(assignment.business_unit_id()),
+            )
+            .await?;
+            let message_type = MessageType::fetch_optional(
+                ctx.repository,
+                MessageTypeQuery::ById(assignment.message_type_id()),
+            )
+            .await?;
+
+            if let Some((business_unit, message_type)) =
+                business_unit.zip(message_type)
+            {
+                contexts.push(TemplateLintContext {
+                    template_assignment_id: assignment.id().clone(),
+                    schema: message_type.schema().clone(),
+                    vars: TemplateAssignment::template_vars(
+                        Some(&assignment),
+                        &business_unit,
+                        &message_type,
+                    ),
+                });
+            }
+        }
+
+        Ok(contexts)
+    }
+}
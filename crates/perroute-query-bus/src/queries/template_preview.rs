@@ -0,0 +1,99 @@
+use crate::{Query, QueryBusContext, QueryBusResult, QueryHandler};
+use bon::Builder;
+use derive_getters::Getters;
+use perroute_commons::types::{id::Id, vars::Vars};
+use perroute_storage::{
+    active_record::{
+        business_unit::BusinessUnitQuery, message_type::MessageTypeQuery,
+        payload_example::PayloadExampleQuery,
+        template_assignment::TemplateAssignmentQuery, ActiveRecord,
+    },
+    models::{
+        business_unit::BusinessUnit,
+        message_type::{MessageType, PayloadExample},
+        template_assignment::TemplateAssignment,
+    },
+};
+
+/// Everything needed to render a template outside of a dispatch.
+#[derive(Debug, Builder)]
+pub struct TemplatePreviewQuery {
+    business_unit_id: Id,
+    message_type_id: Id,
+    template_assignment_id: Option<Id>,
+}
+
+impl Query for TemplatePreviewQuery {}
+
+#[derive(Debug, Getters)]
+pub struct TemplatePreviewContext {
+    template_assignment: Option<TemplateAssignment>,
+    vars: Vars,
+    payload_examples: Vec<PayloadExample>,
+}
+
+pub struct TemplatePreviewHandler;
+
+impl QueryHandler for TemplatePreviewHandler {
+    type Query = TemplatePreviewQuery;
+
+    /// `None` when the business unit, the message type or the assignment do
+    /// not exist, or the assignment belongs to someone else.
+    type Output = Option<TemplatePreviewContext>;
+
+    async fn handle(
+        &self,
+        query: &Self::Query,
+        ctx: QueryBusContext<'_>,
+    ) -> QueryBusResult<Self::Output> {
+        let Some(business_unit) = BusinessUnit::fetch_optional(
+            ctx.repository,
+            BusinessUnitQuery::ById(&query.business_unit_id),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let Some(message_type) = MessageType::fetch_optional(
+            ctx.repository,
+            MessageTypeQuery::ById(&query.message_type_id),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let template_assignment = match &query.template_assignment_id {
+            Some(id) => match TemplateAssignment::fetch_optional(
+                ctx.repository,
+                TemplateAssignmentQuery::ById(id),
+            )
+            .await?
+            .filter(|ta| {
+                ta.business_unit_id() == business_unit.id()
+                    && ta.message_type_id() == message_type.id()
+            }) {
+                Some(ta) => Some(ta),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        let payload_examples = PayloadExample::query(
+            ctx.repository,
+            PayloadExampleQuery::ByMessageType(message_type.id()),
+        )
+        .await?;
+
+        Ok(Some(TemplatePreviewContext {
+            vars: TemplateAssignment::template_vars(
+                template_assignment.as_ref(),
+                &business_unit,
+                &message_type,
+            ),
+            template_assignment,
+            payload_examples,
+        }))
+    }
+}
@@ -0,0 +1,2 @@
+insert into channels (id, name, business_unit_id, dispatch_type, provider_id, configuration, enabled, created_at, updated_at)
+values ('ch-1', 'Email channel', 'bu-1', 'Email', 'smtp', '{}', true, now(), now());
@@ -0,0 +1,5 @@
+insert into dead_letters (id, message_id, queue, body, reason, receive_count, created_at, redriven_at)
+values
+    ('dl-bound', 'msg-1', 'digest', '{}', 'dispatch failed', 5, now() - interval '2 minutes', null),
+    ('dl-unbound', null, 'digest', 'not json', 'Failed to parse message body', 1, now() - interval '1 minute', null),
+    ('dl-redriven', 'msg-1', 'digest', '{}', 'dispatch failed', 5, now() - interval '3 minutes', now());
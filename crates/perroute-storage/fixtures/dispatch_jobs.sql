@@ -0,0 +1,5 @@
+insert into dispatch_jobs (id, message_id, body, attempts, visible_at, last_error, dead_at, created_at, updated_at)
+values
+    ('job-visible', null, '{}', 0, now() - interval '1 minute', null, null, now(), now()),
+    ('job-hidden', null, '{}', 1, now() + interval '1 hour', null, null, now(), now()),
+    ('job-dead', null, '{}', 5, now() - interval '1 minute', 'boom', now(), now(), now());
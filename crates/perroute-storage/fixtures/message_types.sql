@@ -0,0 +1,4 @@
+insert into message_types (id, name, code, schema, vars, enabled, created_at, updated_at)
+values
+    ('mt-schema', 'Welcome', 'welcome', '{"type": "object", "required": ["name"], "properties": {"name": {"type": "string"}}}', '{}', true, now(), now()),
+    ('mt-disabled', 'Disabled', 'disabled', '{}', '{}', false, now(), now());
@@ -0,0 +1,8 @@
+insert into business_units (id, code, name, vars, created_at, updated_at)
+values ('bu-1', 'bu_1', 'Business unit', '{}', now(), now());
+
+insert into message_types (id, name, code, schema, vars, enabled, created_at, updated_at)
+values ('mt-1', 'Message type', 'mt_1', '{}', '{}', true, now(), now());
+
+insert into messages (id, message_type_id, business_unit_id, payload, recipient, dispatch_type, status, tags, created_at, updated_at)
+values ('msg-1', 'mt-1', 'bu-1', '{}', '{"Email": {"address": "user@example.com"}}', 'Email', 'Failed', '[]', now(), now());
@@ -0,0 +1,6 @@
+insert into messages (id, message_type_id, business_unit_id, payload, recipient, dispatch_type, status, tags, scheduled_at, created_at, updated_at)
+values
+    ('msg-due', 'mt-1', 'bu-1', '{}', '{"Email": {"address": "user@example.com"}}', 'Email', 'Scheduled', '[]', now() - interval '1 hour', now(), now()),
+    ('msg-overdue', 'mt-1', 'bu-1', '{}', '{"Email": {"address": "user@example.com"}}', 'Email', 'Scheduled', '[]', now() - interval '1 day', now(), now()),
+    ('msg-future', 'mt-1', 'bu-1', '{}', '{"Email": {"address": "user@example.com"}}', 'Email', 'Scheduled', '[]', now() + interval '1 day', now(), now()),
+    ('msg-cancelled', 'mt-1', 'bu-1', '{}', '{"Email": {"address": "user@example.com"}}', 'Email', 'Cancelled', '[]', now() - interval '1 hour', now(), now());
@@ -0,0 +1,12 @@
+insert into template_assignments (id, message_type_id, business_unit_id, vars, priority, start_at, end_at, enabled, email_template_id, sms_template_id, created_at, updated_at)
+values
+    ('ta-low', 'mt-1', 'bu-1', '{}', 1, now() - interval '10 days', null, true, 'tpl-low', null, now(), now()),
+    ('ta-high-old', 'mt-1', 'bu-1', '{}', 5, now() - interval '10 days', null, true, 'tpl-high-old', null, now(), now()),
+    ('ta-high-recent', 'mt-1', 'bu-1', '{}', 5, now() - interval '1 day', null, true, 'tpl-high-recent', null, now(), now()),
+    ('ta-seasonal', 'mt-1', 'bu-1', '{}', 10, now() + interval '1 day', now() + interval '5 days', true, 'tpl-seasonal', null, now(), now()),
+    ('ta-expired', 'mt-1', 'bu-1', '{}', 20, now() - interval '10 days', now() - interval '1 day', true, 'tpl-expired', null, now(), now()),
+    ('ta-disabled', 'mt-1', 'bu-1', '{}', 30, now() - interval '10 days', null, false, 'tpl-disabled', null, now(), now()),
+    ('ta-sms-only', 'mt-1', 'bu-1', '{}', 40, now() - interval '10 days', null, true, null, 'tpl-sms', now(), now());
+
+insert into template_assignments (id, message_type_id, business_unit_id, vars, priority, start_at, end_at, enabled, localized_templates, created_at, updated_at)
+values ('ta-push-pt', 'mt-1', 'bu-1', '{}', 50, now() - interval '10 days', null, true, '{"pt": {"push_template_id": "tpl-push-pt"}}', now(), now());
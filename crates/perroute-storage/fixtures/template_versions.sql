@@ -0,0 +1,6 @@
+insert into template_versions (template_id, dispatch_type, version, status, content, created_by, created_at, published_by, published_at)
+values
+    ('tpl-1', 'Email', 1, 'Archived', '{}', '"System"', now() - interval '3 days', '"System"', now() - interval '3 days'),
+    ('tpl-1', 'Email', 2, 'Published', '{}', '"System"', now() - interval '2 days', '"System"', now() - interval '2 days'),
+    ('tpl-1', 'Email', 3, 'Draft', '{}', '"System"', now() - interval '1 day', null, null),
+    ('tpl-1', 'Sms', 1, 'Draft', '{}', '"System"', now() - interval '1 day', null, null);
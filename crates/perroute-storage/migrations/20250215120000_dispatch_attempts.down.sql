@@ -0,0 +1,3 @@
+alter table message_dispatches drop column attempt;
+
+alter table messages drop column attempts;
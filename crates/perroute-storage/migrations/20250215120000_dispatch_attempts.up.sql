@@ -0,0 +1,3 @@
+alter table messages add column attempts integer not null default 0;
+
+alter table message_dispatches add column attempt integer not null default 1;
@@ -0,0 +1,13 @@
+create table dead_letters(
+    id              varchar(21) primary key,
+    message_id      varchar(21) null,
+    queue           text not null,
+    body            text not null,
+    reason          text not null,
+    receive_count   integer not null,
+    created_at      timestamp not null,
+    redriven_at     timestamp null,
+    constraint fk_dead_letters_messages foreign key (message_id) references messages(id)
+);
+
+create index dead_letters_pending_idx on dead_letters (created_at) where redriven_at is null;
@@ -0,0 +1 @@
+drop table dispatch_jobs;
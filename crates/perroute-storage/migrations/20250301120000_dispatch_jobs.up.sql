@@ -0,0 +1,14 @@
+create table dispatch_jobs(
+    id              varchar(21) primary key,
+    message_id      varchar(21) null,
+    body            text not null,
+    attempts        integer not null default 0,
+    visible_at      timestamp not null,
+    last_error      text null,
+    dead_at         timestamp null,
+    created_at      timestamp not null,
+    updated_at      timestamp not null,
+    constraint fk_dispatch_jobs_messages foreign key (message_id) references messages(id)
+);
+
+create index dispatch_jobs_visible_idx on dispatch_jobs (visible_at) where dead_at is null;
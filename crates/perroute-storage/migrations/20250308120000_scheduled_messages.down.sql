@@ -0,0 +1,15 @@
+drop index messages_scheduled_idx;
+
+create type message_status as enum ('pending', 'dispatched', 'failed');
+
+-- Scheduled messages fall back to pending and cancelled ones to failed, the
+-- closest statuses the enum can hold.
+alter table messages alter column status type message_status using (
+    case status
+        when 'Received' then 'pending'
+        when 'Scheduled' then 'pending'
+        when 'Dispatched' then 'dispatched'
+        when 'Failed' then 'failed'
+        when 'Cancelled' then 'failed'
+    end
+)::message_status;
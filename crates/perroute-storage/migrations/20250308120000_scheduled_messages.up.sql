@@ -0,0 +1,13 @@
+-- The status is stored as the model's variant name so new statuses like
+-- Scheduled and Cancelled need no enum migration.
+alter table messages alter column status type varchar(50) using (
+    case status
+        when 'pending' then 'Received'
+        when 'dispatched' then 'Dispatched'
+        when 'failed' then 'Failed'
+    end
+);
+
+drop type message_status;
+
+create index messages_scheduled_idx on messages (scheduled_at) where scheduled_at is not null;
@@ -0,0 +1 @@
+drop index messages_business_unit_key_idx;
@@ -0,0 +1 @@
+create unique index messages_business_unit_key_idx on messages (business_unit_id, key) where key is not null;
@@ -0,0 +1,7 @@
+drop index idx_template_assignments_dispatch;
+
+alter table template_assignments drop column push_template_id;
+alter table template_assignments drop column email_template_id;
+alter table template_assignments drop column sms_template_id;
+alter table template_assignments alter column priority type int;
+alter table template_assignments add column dispatch_type varchar(50) null;
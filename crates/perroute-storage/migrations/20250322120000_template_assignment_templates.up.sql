@@ -0,0 +1,9 @@
+alter table template_assignments drop column dispatch_type;
+alter table template_assignments alter column priority type bigint;
+alter table template_assignments add column sms_template_id varchar(21) null;
+alter table template_assignments add column email_template_id varchar(21) null;
+alter table template_assignments add column push_template_id varchar(21) null;
+
+create index idx_template_assignments_dispatch
+    on template_assignments (business_unit_id, message_type_id, priority desc)
+    where enabled;
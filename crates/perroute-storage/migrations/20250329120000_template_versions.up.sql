@@ -0,0 +1,16 @@
+create table template_versions (
+    template_id         varchar(21) not null,
+    dispatch_type       varchar(50) not null,
+    version             int not null,
+    status              varchar(50) not null,
+    content             jsonb not null,
+    created_by          jsonb not null,
+    created_at          timestamp not null,
+    published_by        jsonb null,
+    published_at        timestamp null,
+    primary key (template_id, dispatch_type, version)
+);
+
+create unique index idx_template_versions_published
+    on template_versions (template_id, dispatch_type)
+    where status = 'Published';
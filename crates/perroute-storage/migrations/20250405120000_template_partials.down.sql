@@ -0,0 +1 @@
+drop table template_partials;
@@ -0,0 +1,9 @@
+create table template_partials (
+    business_unit_id    varchar(21) not null,
+    name                varchar(100) not null,
+    content             text not null,
+    updated_by          jsonb not null,
+    updated_at          timestamp not null,
+    primary key (business_unit_id, name),
+    constraint template_partial_bu_fk foreign key (business_unit_id) references business_units(id)
+);
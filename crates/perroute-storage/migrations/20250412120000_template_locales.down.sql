@@ -0,0 +1,2 @@
+alter table template_assignments drop column localized_templates;
+alter table messages drop column locale;
@@ -0,0 +1,2 @@
+alter table messages add column locale varchar(35) null;
+alter table template_assignments add column localized_templates jsonb not null default '{}';
@@ -19,7 +19,43 @@ impl ModelQuery<Channel> for ChannelQuery<'_> {
         &self,
         projection: super::Projection,
     ) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
-        todo!()
+        let mut qb = projection.query_builder(Some("ch"));
+        qb.push(" FROM channels ch where 1=1 ");
+        match self {
+            ChannelQuery::ByBusinessUnitId(business_unit_id) => {
+                qb.push(" AND ch.business_unit_id = ");
+                qb.push_bind(business_unit_id);
+                qb
+            }
+            ChannelQuery::ById(id) => {
+                qb.push(" AND ch.id = ");
+                qb.push_bind(id);
+                qb
+            }
+            ChannelQuery::EnabledByBusinessUnitAndDispatchType(
+                business_unit_id,
+                dispatch_type,
+            ) => {
+                qb.push(" AND ch.enabled = true AND ch.business_unit_id = ");
+                qb.push_bind(business_unit_id);
+                qb.push(" AND ch.dispatch_type = ");
+                qb.push_bind(dispatch_type);
+                qb
+            }
+            ChannelQuery::ActiveByIds(ids) => {
+                if ids.is_empty() {
+                    qb.push(" AND 1=0 ");
+                    return qb;
+                }
+                qb.push(" AND ch.enabled = true AND ch.id in (");
+                let mut separated = qb.separated(", ");
+                for id in ids {
+                    separated.push_bind(*id);
+                }
+                qb.push(")");
+                qb
+            }
+        }
     }
 }
 
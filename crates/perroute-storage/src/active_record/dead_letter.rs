@@ -0,0 +1,128 @@
+use bon::Builder;
+use perroute_commons::types::{id::Id, Timestamp};
+use sqlx::{postgres::PgArguments, query, query_as, Postgres, QueryBuilder};
+use crate::models::dead_letter::DeadLetter;
+use super::{Model, ModelQuery, Projection};
+
+#[derive(Debug)]
+pub enum DeadLetterQuery<'q> {
+    ById(&'q Id),
+    Pending,
+}
+
+impl ModelQuery<DeadLetter> for DeadLetterQuery<'_> {
+    fn build(&self, projection: Projection) -> QueryBuilder<'_, Postgres> {
+        let ordered = matches!(projection, Projection::Row);
+        let mut qb = projection.query_builder(Some("dl"));
+        qb.push(" FROM dead_letters dl where 1=1 ");
+        match self {
+            DeadLetterQuery::ById(id) => {
+                qb.push(" AND dl.id = ");
+                qb.push_bind(id);
+                qb
+            }
+            DeadLetterQuery::Pending => {
+                qb.push(" AND dl.redriven_at IS NULL");
+                if ordered {
+                    qb.push(" ORDER BY dl.created_at");
+                }
+                qb
+            }
+        }
+    }
+}
+
+#[derive(Debug, Builder)]
+pub struct CreateDeadLetter {
+    message_id: Option<Id>,
+    #[builder(into)]
+    queue: String,
+    #[builder(into)]
+    body: String,
+    #[builder(into)]
+    reason: String,
+    receive_count: i32,
+    #[builder(into)]
+    timestamp: Timestamp,
+}
+
+impl Model for DeadLetter {
+    type Create = CreateDeadLetter;
+
+    fn destroy_query(&self) -> sqlx::query::Query<'_, Postgres, PgArguments> {
+        query(
+            r#"
+        delete from dead_letters
+        where id = $1"#,
+        )
+        .bind(self.id())
+    }
+
+    fn update_query(
+        &self,
+    ) -> sqlx::query::QueryAs<'_, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        update dead_letters
+            set redriven_at = $1
+        where
+            id = $2
+        returning *"#,
+        )
+        .bind(self.redriven_at())
+        .bind(self.id())
+    }
+
+    fn create_query<'q>(
+        create: Self::Create,
+    ) -> sqlx::query::QueryAs<'q, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        insert into dead_letters (
+            id,
+            message_id,
+            queue,
+            body,
+            reason,
+            receive_count,
+            created_at)
+        values ($1, $2, $3, $4, $5, $6, $7)
+        returning *"#,
+        )
+        .bind(Id::new())
+        .bind(create.message_id)
+        .bind(create.queue)
+        .bind(create.body)
+        .bind(create.reason)
+        .bind(create.receive_count)
+        .bind(create.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_record::{datasource::DataSource, ActiveRecord};
+    use sqlx::PgPool;
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../fixtures",
+        scripts("messages", "dead_letters")
+    ))]
+    async fn pending_lists_dead_letters_not_redriven_oldest_first(
+        pool: PgPool,
+    ) {
+        let datasource = DataSource::new(pool);
+
+        let pending = DeadLetter::query(&datasource, DeadLetterQuery::Pending)
+            .await
+            .unwrap();
+
+        let ids = pending
+            .iter()
+            .map(|dl| dl.id().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["dl-bound", "dl-unbound"]);
+    }
+}
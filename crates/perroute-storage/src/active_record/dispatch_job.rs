@@ -0,0 +1,216 @@
+use std::future::Future;
+use bon::Builder;
+use perroute_commons::types::{id::Id, Timestamp};
+use sqlx::{postgres::PgArguments, query, query_as, Postgres};
+use crate::models::dispatch_job::DispatchJob;
+use super::{datasource::Connection, ActiveRecordResult, Model};
+
+#[derive(Debug, Builder)]
+pub struct CreateDispatchJob {
+    message_id: Option<Id>,
+    #[builder(into)]
+    body: String,
+    #[builder(into)]
+    timestamp: Timestamp,
+}
+
+impl Model for DispatchJob {
+    type Create = CreateDispatchJob;
+
+    fn destroy_query(&self) -> sqlx::query::Query<'_, Postgres, PgArguments> {
+        query(
+            r#"
+        delete from dispatch_jobs
+        where id = $1"#,
+        )
+        .bind(self.id())
+    }
+
+    fn update_query(
+        &self,
+    ) -> sqlx::query::QueryAs<'_, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        update dispatch_jobs
+            set visible_at = $1,
+            last_error = $2,
+            dead_at = $3,
+            updated_at = $4
+        where
+            id = $5
+        returning *"#,
+        )
+        .bind(self.visible_at())
+        .bind(self.last_error())
+        .bind(self.dead_at())
+        .bind(Timestamp::now())
+        .bind(self.id())
+    }
+
+    fn create_query<'q>(
+        create: Self::Create,
+    ) -> sqlx::query::QueryAs<'q, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        insert into dispatch_jobs (
+            id,
+            message_id,
+            body,
+            attempts,
+            visible_at,
+            created_at,
+            updated_at)
+        values ($1, $2, $3, 0, $4, $4, $4)
+        returning *"#,
+        )
+        .bind(Id::new())
+        .bind(create.message_id)
+        .bind(create.body)
+        .bind(create.timestamp)
+    }
+}
+
+pub trait DispatchJobActiveRecord<C>
+where
+    C: AsRef<Connection>,
+{
+    /// Locks up to `size` visible jobs, skipping the ones other consumers
+    /// hold, hides them for `visibility_timeout` seconds and bumps their
+    /// attempt counter.
+    fn claim(
+        conn: C,
+        size: i64,
+        visibility_timeout: f64,
+        timestamp: &Timestamp,
+    ) -> impl Future<Output = ActiveRecordResult<Vec<DispatchJob>>>;
+
+    /// Removes a job that no longer needs to be dispatched.
+    fn complete(
+        conn: C,
+        id: &Id,
+    ) -> impl Future<Output = ActiveRecordResult<()>>;
+
+    /// Hides the job for `delay` seconds from `timestamp`.
+    fn reschedule(
+        conn: C,
+        id: &Id,
+        delay: f64,
+        timestamp: &Timestamp,
+    ) -> impl Future<Output = ActiveRecordResult<()>>;
+
+    /// Marks the job as dead so it is never claimed again.
+    fn bury(
+        conn: C,
+        id: &Id,
+        reason: &str,
+        timestamp: &Timestamp,
+    ) -> impl Future<Output = ActiveRecordResult<()>>;
+}
+
+impl<C: AsRef<Connection>> DispatchJobActiveRecord<C> for DispatchJob {
+    async fn claim(
+        conn: C,
+        size: i64,
+        visibility_timeout: f64,
+        timestamp: &Timestamp,
+    ) -> ActiveRecordResult<Vec<DispatchJob>> {
+        let query = query_as(
+            r#"
+        update dispatch_jobs
+            set visible_at = $1 + $2 * interval '1 second',
+            attempts = attempts + 1,
+            updated_at = $1
+        where id in (
+            select id from dispatch_jobs
+            where dead_at is null and visible_at <= $1
+            order by visible_at
+            limit $3
+            for update skip locked)
+        returning *"#,
+        )
+        .bind(timestamp)
+        .bind(visibility_timeout)
+        .bind(size);
+
+        Ok(match conn.as_ref() {
+            Connection::Pool(pool) => query.fetch_all(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.fetch_all(x.as_mut()).await
+            }
+        }?)
+    }
+
+    async fn complete(conn: C, id: &Id) -> ActiveRecordResult<()> {
+        let query = query(
+            r#"
+        delete from dispatch_jobs
+        where id = $1"#,
+        )
+        .bind(id);
+
+        match conn.as_ref() {
+            Connection::Pool(pool) => query.execute(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.execute(x.as_mut()).await
+            }
+        }?;
+        Ok(())
+    }
+
+    async fn reschedule(
+        conn: C,
+        id: &Id,
+        delay: f64,
+        timestamp: &Timestamp,
+    ) -> ActiveRecordResult<()> {
+        let query = query(
+            r#"
+        update dispatch_jobs
+            set visible_at = $1 + $2 * interval '1 second',
+            updated_at = $1
+        where id = $3"#,
+        )
+        .bind(timestamp)
+        .bind(delay)
+        .bind(id);
+
+        match conn.as_ref() {
+            Connection::Pool(pool) => query.execute(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.execute(x.as_mut()).await
+            }
+        }?;
+        Ok(())
+    }
+
+    async fn bury(
+        conn: C,
+        id: &Id,
+        reason: &str,
+        timestamp: &Timestamp,
+    ) -> ActiveRecordResult<()> {
+        let query = query(
+            r#"
+        update dispatch_jobs
+            set dead_at = $1,
+            last_error = $2,
+            updated_at = $1
+        where id = $3"#,
+        )
+        .bind(timestamp)
+        .bind(reason)
+        .bind(id);
+
+        match conn.as_ref() {
+            Connection::Pool(pool) => query.execute(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.execute(x.as_mut()).await
+            }
+        }?;
+        Ok(())
+    }
+}
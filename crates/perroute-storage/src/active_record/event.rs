@@ -7,7 +7,7 @@ use super::{
 };
 
 impl Model for DbEvent {
-    type Create = CreateEvent;
+    type Create = DbEvent;
 
     fn update_query(
         &self,
@@ -28,7 +28,26 @@ impl Model for DbEvent {
         Self,
         sqlx::postgres::PgArguments,
     > {
-        todo!()
+        sqlx::query_as(
+            r#"
+        insert into event_messages (
+            id,
+            event_type,
+            entity_id,
+            payload,
+            actor_type,
+            actor_id,
+            created_at)
+        values ($1, $2, $3, $4, $5, $6, $7)
+        returning *"#,
+        )
+        .bind(create.id().clone())
+        .bind(create.event_type().clone())
+        .bind(create.entity_id().clone())
+        .bind(create.payload().clone())
+        .bind(create.actor_type().clone())
+        .bind(create.actor_id().clone())
+        .bind(create.created_at().clone())
     }
 
     fn destroy_query(
@@ -39,8 +58,6 @@ impl Model for DbEvent {
     }
 }
 
-pub struct CreateEvent {}
-
 pub trait EventActiveRecord<C>
 where
     C: AsRef<Connection>,
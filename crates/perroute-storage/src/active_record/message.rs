@@ -1,22 +1,43 @@
 use std::future::Future;
 
-use perroute_commons::types::id::Id;
+use bon::Builder;
+use perroute_commons::types::{
+    dispatch_type::DispatchType, id::Id, idempotency_key::IdempotencyKey,
+    locale::Locale, recipient::Recipient, MessageStatus, Payload, Tags,
+    Timestamp,
+};
+use sqlx::{query, query_as, types::Json, Postgres, QueryBuilder};
 
 use crate::models::{
     business_unit::BusinessUnit, message::Message, message_type::MessageType,
 };
-use super::{datasource::Connection, ActiveRecordResult, Model, ModelQuery};
+use super::{
+    datasource::Connection, ActiveRecordResult, Model, ModelQuery, Projection,
+};
 
 pub enum MessageQuery<'q> {
     ById(&'q Id),
+    ByKey(&'q Id, &'q IdempotencyKey),
 }
 
 impl ModelQuery<Message> for MessageQuery<'_> {
-    fn build(
-        &self,
-        projection: super::Projection,
-    ) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
-        todo!()
+    fn build(&self, projection: Projection) -> QueryBuilder<'_, Postgres> {
+        let mut qb = projection.query_builder(Some("m"));
+        qb.push(" FROM messages m where 1=1 ");
+        match self {
+            MessageQuery::ById(id) => {
+                qb.push(" AND m.id = ");
+                qb.push_bind(id);
+                qb
+            }
+            MessageQuery::ByKey(business_unit_id, key) => {
+                qb.push(" AND m.business_unit_id = ");
+                qb.push_bind(business_unit_id);
+                qb.push(" AND m.key = ");
+                qb.push_bind(key);
+                qb
+            }
+        }
     }
 }
 
@@ -31,7 +52,24 @@ impl Model for Message {
         Self,
         sqlx::postgres::PgArguments,
     > {
-        todo!()
+        query_as(
+            r#"
+        update messages
+            set status = $1,
+            attempts = $2,
+            scheduled_at = $3,
+            key = $4,
+            updated_at = $5
+        where
+            id = $6
+        returning *"#,
+        )
+        .bind(self.status())
+        .bind(self.attempts())
+        .bind(self.scheduled_at())
+        .bind(self.key())
+        .bind(self.updated_at())
+        .bind(self.id())
     }
 
     fn create_query<'q>(
@@ -42,7 +80,38 @@ impl Model for Message {
         Self,
         sqlx::postgres::PgArguments,
     > {
-        todo!()
+        query_as(
+            r#"
+        insert into messages (
+            id,
+            key,
+            message_type_id,
+            business_unit_id,
+            payload,
+            recipient,
+            locale,
+            dispatch_type,
+            status,
+            attempts,
+            tags,
+            scheduled_at,
+            created_at,
+            updated_at)
+        values ($1, $2, $3, $4, $5, $6, $7, $8, $9, 0, $10, $11, $12, $12)
+        returning *"#,
+        )
+        .bind(create.id)
+        .bind(create.key)
+        .bind(create.message_type_id)
+        .bind(create.business_unit_id)
+        .bind(create.payload)
+        .bind(Json(create.recipient))
+        .bind(create.locale)
+        .bind(create.dispatch_type)
+        .bind(create.status)
+        .bind(Json(create.tags))
+        .bind(create.scheduled_at)
+        .bind(create.timestamp)
     }
 
     fn destroy_query(
@@ -81,4 +150,167 @@ impl MessageActiveRecord for Message {
     }
 }
 
-pub struct CreateMessage {}
+#[derive(Debug, Builder)]
+pub struct CreateMessage {
+    #[builder(into)]
+    id: Id,
+    key: Option<IdempotencyKey>,
+    #[builder(into)]
+    message_type_id: Id,
+    #[builder(into)]
+    business_unit_id: Id,
+    payload: Payload,
+    recipient: Recipient,
+    locale: Option<Locale>,
+    dispatch_type: DispatchType,
+    status: MessageStatus,
+    tags: Tags,
+    scheduled_at: Option<Timestamp>,
+    #[builder(into)]
+    timestamp: Timestamp,
+}
+
+pub trait ScheduledMessageActiveRecord<C>
+where
+    C: AsRef<Connection>,
+{
+    /// Moves up to `size` scheduled messages due at `timestamp` to the
+    /// received status. Rows locked by another scheduler are skipped, so
+    /// concurrent schedulers never release the same message twice.
+    fn release_due(
+        conn: C,
+        size: i64,
+        timestamp: &Timestamp,
+    ) -> impl Future<Output = ActiveRecordResult<Vec<Message>>>;
+}
+
+impl<C: AsRef<Connection>> ScheduledMessageActiveRecord<C> for Message {
+    async fn release_due(
+        conn: C,
+        size: i64,
+        timestamp: &Timestamp,
+    ) -> ActiveRecordResult<Vec<Message>> {
+        let query = query_as(
+            r#"
+        update messages
+            set status = $1,
+            updated_at = $2
+        where id in (
+            select id from messages
+            where status = $3 and scheduled_at <= $2
+            order by scheduled_at
+            limit $4
+            for update skip locked)
+        returning *"#,
+        )
+        .bind(MessageStatus::Received)
+        .bind(timestamp)
+        .bind(MessageStatus::Scheduled)
+        .bind(size);
+
+        Ok(match conn.as_ref() {
+            Connection::Pool(pool) => query.fetch_all(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.fetch_all(x.as_mut()).await
+            }
+        }?)
+    }
+}
+
+pub trait IdempotentMessageActiveRecord<C>
+where
+    C: AsRef<Connection>,
+{
+    /// Serializes the creation of messages sharing an idempotency key until
+    /// the current transaction ends, so a concurrent request with the same
+    /// key finds the message instead of violating the unique index.
+    fn lock_key(
+        conn: C,
+        business_unit_id: &Id,
+        key: &IdempotencyKey,
+    ) -> impl Future<Output = ActiveRecordResult<()>>;
+}
+
+impl<C: AsRef<Connection>> IdempotentMessageActiveRecord<C> for Message {
+    async fn lock_key(
+        conn: C,
+        business_unit_id: &Id,
+        key: &IdempotencyKey,
+    ) -> ActiveRecordResult<()> {
+        let query = query(
+            "select pg_advisory_xact_lock(hashtextextended($1 || '/' || $2, 0))",
+        )
+        .bind(business_unit_id)
+        .bind(key);
+
+        match conn.as_ref() {
+            Connection::Pool(pool) => query.execute(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.execute(x.as_mut()).await
+            }
+        }?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_record::datasource::DataSource;
+    use sqlx::PgPool;
+
+    async fn status(pool: &PgPool, id: &str) -> String {
+        sqlx::query_scalar("select status from messages where id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../fixtures",
+        scripts("messages", "scheduled_messages")
+    ))]
+    async fn release_due_receives_only_due_scheduled_messages(pool: PgPool) {
+        let datasource = DataSource::new(pool.clone());
+
+        let mut released =
+            Message::release_due(&datasource, 10, &Timestamp::now())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|m| m.id().to_string())
+                .collect::<Vec<_>>();
+        released.sort();
+
+        assert_eq!(released, vec!["msg-due", "msg-overdue"]);
+        assert_eq!(status(&pool, "msg-due").await, "Received");
+        assert_eq!(status(&pool, "msg-future").await, "Scheduled");
+        assert_eq!(status(&pool, "msg-cancelled").await, "Cancelled");
+        assert_eq!(status(&pool, "msg-1").await, "Failed");
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../fixtures",
+        scripts("messages", "scheduled_messages")
+    ))]
+    async fn release_due_takes_the_oldest_first_up_to_the_batch_size(
+        pool: PgPool,
+    ) {
+        let datasource = DataSource::new(pool.clone());
+
+        let released = Message::release_due(&datasource, 1, &Timestamp::now())
+            .await
+            .unwrap();
+
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].id().to_string(), "msg-overdue");
+        assert_eq!(released[0].status(), &MessageStatus::Received);
+        assert_eq!(status(&pool, "msg-due").await, "Scheduled");
+    }
+}
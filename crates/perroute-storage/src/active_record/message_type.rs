@@ -15,7 +15,21 @@ impl ModelQuery<MessageType> for MessageTypeQuery<'_> {
         &self,
         projection: super::Projection,
     ) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
-        todo!()
+        let mut qb = projection.query_builder(Some("mt"));
+        qb.push(" FROM message_types mt where 1=1 ");
+        match self {
+            MessageTypeQuery::ById(id) => {
+                qb.push(" AND mt.id = ");
+                qb.push_bind(id);
+                qb
+            }
+            MessageTypeQuery::ByCode(code) => {
+                qb.push(" AND mt.code = ");
+                qb.push_bind(code);
+                qb
+            }
+            MessageTypeQuery::All => qb,
+        }
     }
 }
 
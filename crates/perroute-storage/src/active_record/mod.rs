@@ -9,12 +9,17 @@ use datasource::Connection;
 pub mod business_unit;
 pub mod channel;
 pub mod datasource;
+pub mod dead_letter;
+pub mod dispatch_job;
 pub mod dispatcher_log;
 pub mod event;
 pub mod message;
 pub mod message_type;
+pub mod payload_example;
 pub mod route;
 pub mod template_assignment;
+pub mod template_partial;
+pub mod template_version;
 
 macro_rules! execute_ {
     ($source:expr,$query:expr) => {
@@ -75,6 +80,17 @@ pub enum ActiveRecordError {
     ConnectionInvalidState,
 }
 
+impl ActiveRecordError {
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            ActiveRecordError::DatabaseError(sqlx::Error::Database(e)) => {
+                e.is_unique_violation()
+            }
+            _ => false,
+        }
+    }
+}
+
 pub trait Model: Unpin + Sync + Send + for<'r> FromRow<'r, PgRow> {
     type Create;
 
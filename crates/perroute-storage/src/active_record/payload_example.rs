@@ -0,0 +1,85 @@
+use bon::Builder;
+use perroute_commons::types::{id::Id, name::Name, Payload};
+use sqlx::{postgres::PgArguments, query, query_as, Postgres, QueryBuilder};
+use crate::models::message_type::PayloadExample;
+use super::{Model, ModelQuery, Projection};
+
+#[derive(Debug)]
+pub enum PayloadExampleQuery<'q> {
+    ByMessageType(&'q Id),
+}
+
+impl ModelQuery<PayloadExample> for PayloadExampleQuery<'_> {
+    fn build(&self, projection: Projection) -> QueryBuilder<'_, Postgres> {
+        let ordered = matches!(projection, Projection::Row);
+        let mut qb = projection.query_builder(Some("pe"));
+        qb.push(" FROM payload_examples pe where 1=1 ");
+        match self {
+            PayloadExampleQuery::ByMessageType(id) => {
+                qb.push(" AND pe.message_type_id = ");
+                qb.push_bind(id);
+                if ordered {
+                    qb.push(" ORDER BY pe.name");
+                }
+                qb
+            }
+        }
+    }
+}
+
+#[derive(Debug, Builder)]
+pub struct CreatePayloadExample {
+    #[builder(into)]
+    message_type_id: Id,
+    name: Name,
+    payload: Payload,
+}
+
+impl Model for PayloadExample {
+    type Create = CreatePayloadExample;
+
+    fn destroy_query(&self) -> sqlx::query::Query<'_, Postgres, PgArguments> {
+        query(
+            r#"
+        delete from payload_examples
+        where id = $1"#,
+        )
+        .bind(self.id())
+    }
+
+    fn update_query(
+        &self,
+    ) -> sqlx::query::QueryAs<'_, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        update payload_examples
+            set name = $1,
+            payload = $2
+        where
+            id = $3
+        returning *"#,
+        )
+        .bind(self.name())
+        .bind(self.payload())
+        .bind(self.id())
+    }
+
+    fn create_query<'q>(
+        create: Self::Create,
+    ) -> sqlx::query::QueryAs<'q, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        insert into payload_examples (
+            id,
+            message_type_id,
+            name,
+            payload)
+        values ($1, $2, $3, $4)
+        returning *"#,
+        )
+        .bind(Id::new())
+        .bind(create.message_type_id)
+        .bind(create.name)
+        .bind(create.payload)
+    }
+}
@@ -1,8 +1,15 @@
-use perroute_commons::types::{dispatch_type::DispatchType, id::Id, Timestamp};
+use perroute_commons::types::{
+    dispatch_type::DispatchType, id::Id, locale::Locale, Timestamp,
+};
 use crate::models::template_assignment::TemplateAssignment;
-use super::{Model, ModelQuery};
+use super::{Model, ModelQuery, Projection};
 
 pub enum TemplateAssignmentQuery<'q> {
+    ById(&'q Id),
+    ByBusinessUnit(&'q Id),
+    /// Assignments using the template for the dispatch type, either as the
+    /// default template or as the template of a locale.
+    ByTemplate(&'q Id, &'q DispatchType),
     ForDispatch(QueryForDispatch<'q>),
 }
 
@@ -11,6 +18,9 @@ pub struct QueryForDispatch<'q> {
     business_unit_id: &'q Id,
     message_type_id: &'q Id,
     dispatch_type: &'q DispatchType,
+    /// Locale of the recipient. Assignments only holding templates for
+    /// this locale or its fallbacks are eligible too.
+    locale: Option<&'q Locale>,
     reference_date: &'q Timestamp,
 }
 
@@ -20,12 +30,89 @@ impl<'q> From<QueryForDispatch<'q>> for TemplateAssignmentQuery<'q> {
     }
 }
 
+impl QueryForDispatch<'_> {
+    fn template_column(&self) -> &'static str {
+        match self.dispatch_type {
+            DispatchType::Email => "email_template_id",
+            DispatchType::Sms => "sms_template_id",
+            DispatchType::Push => "push_template_id",
+        }
+    }
+}
+
 impl ModelQuery<TemplateAssignment> for TemplateAssignmentQuery<'_> {
+    /// Eligible assignments are enabled, carry a template for the dispatch
+    /// type, by default or for the locale, and are active at the reference
+    /// date. The highest priority wins;
+    /// ties go to the most recently started assignment, then to the lowest id.
     fn build(
         &self,
-        projection: super::Projection,
+        projection: Projection,
     ) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
-        todo!()
+        let ordered = matches!(projection, Projection::Row);
+        let mut qb = projection.query_builder(Some("ta"));
+        qb.push(" FROM template_assignments ta where 1=1 ");
+        match self {
+            TemplateAssignmentQuery::ById(id) => {
+                qb.push(" AND ta.id = ");
+                qb.push_bind(id);
+                qb
+            }
+            TemplateAssignmentQuery::ByBusinessUnit(business_unit_id) => {
+                qb.push(" AND ta.business_unit_id = ");
+                qb.push_bind(business_unit_id);
+                if ordered {
+                    qb.push(" ORDER BY ta.message_type_id, ta.priority desc");
+                }
+                qb
+            }
+            TemplateAssignmentQuery::ByTemplate(template_id, dispatch_type) => {
+                let column = match dispatch_type {
+                    DispatchType::Email => "email_template_id",
+                    DispatchType::Sms => "sms_template_id",
+                    DispatchType::Push => "push_template_id",
+                };
+                qb.push(format!(" AND (ta.{column} = "));
+                qb.push_bind(template_id);
+                qb.push(format!(
+                    " OR EXISTS (SELECT 1 FROM jsonb_each(ta.localized_templates) l WHERE l.value ->> '{column}' = "
+                ));
+                qb.push_bind(template_id);
+                qb.push("))");
+                if ordered {
+                    qb.push(" ORDER BY ta.id");
+                }
+                qb
+            }
+            TemplateAssignmentQuery::ForDispatch(query) => {
+                qb.push(" AND ta.business_unit_id = ");
+                qb.push_bind(query.business_unit_id);
+                qb.push(" AND ta.message_type_id = ");
+                qb.push_bind(query.message_type_id);
+                qb.push(" AND ta.enabled = true");
+                let column = query.template_column();
+                qb.push(format!(" AND (ta.{column} is not null"));
+                for locale in
+                    query.locale.into_iter().flat_map(Locale::fallbacks)
+                {
+                    qb.push(" OR ta.localized_templates -> ");
+                    qb.push_bind(locale.to_string());
+                    qb.push(format!(" ->> '{column}' is not null"));
+                }
+                qb.push(")");
+                qb.push(" AND ta.start_at <= ");
+                qb.push_bind(query.reference_date);
+                qb.push(" AND (ta.end_at is null or ta.end_at > ");
+                qb.push_bind(query.reference_date);
+                qb.push(")");
+                if ordered {
+                    qb.push(
+                        " ORDER BY ta.priority desc, ta.start_at desc, ta.id asc",
+                    );
+                }
+                qb
+            }
+        }
     }
 }
 
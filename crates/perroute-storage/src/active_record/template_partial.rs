@@ -0,0 +1,113 @@
+use bon::Builder;
+use perroute_commons::types::{actor::Actor, id::Id, Timestamp};
+use sqlx::{
+    postgres::PgArguments, query, query_as, types::Json, Postgres, QueryBuilder,
+};
+use crate::models::template_partial::TemplatePartial;
+use super::{Model, ModelQuery, Projection};
+
+#[derive(Debug)]
+pub enum TemplatePartialQuery<'q> {
+    ByBusinessUnit(&'q Id),
+    ByName(&'q Id, &'q str),
+}
+
+impl ModelQuery<TemplatePartial> for TemplatePartialQuery<'_> {
+    fn build(&self, projection: Projection) -> QueryBuilder<'_, Postgres> {
+        let ordered = matches!(projection, Projection::Row);
+        let mut qb = projection.query_builder(Some("tp"));
+        qb.push(" FROM template_partials tp where 1=1 ");
+
+        match self {
+            TemplatePartialQuery::ByBusinessUnit(business_unit_id) => {
+                qb.push(" AND tp.business_unit_id = ");
+                qb.push_bind(*business_unit_id);
+                if ordered {
+                    qb.push(" ORDER BY tp.name");
+                }
+                qb
+            }
+            TemplatePartialQuery::ByName(business_unit_id, name) => {
+                qb.push(" AND tp.business_unit_id = ");
+                qb.push_bind(*business_unit_id);
+                qb.push(" AND tp.name = ");
+                qb.push_bind(*name);
+                qb
+            }
+        }
+    }
+}
+
+/// Saves a partial, replacing the content of an existing one with the same
+/// name.
+#[derive(Debug, Builder)]
+pub struct SaveTemplatePartial {
+    #[builder(into)]
+    business_unit_id: Id,
+    #[builder(into)]
+    name: String,
+    #[builder(into)]
+    content: String,
+    updated_by: Actor,
+    #[builder(into)]
+    timestamp: Timestamp,
+}
+
+impl Model for TemplatePartial {
+    type Create = SaveTemplatePartial;
+
+    fn destroy_query(&self) -> sqlx::query::Query<'_, Postgres, PgArguments> {
+        query(
+            r#"
+        delete from template_partials
+        where business_unit_id = $1 and name = $2"#,
+        )
+        .bind(self.business_unit_id())
+        .bind(self.name())
+    }
+
+    fn update_query(
+        &self,
+    ) -> sqlx::query::QueryAs<'_, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        update template_partials
+            set content = $1,
+            updated_by = $2,
+            updated_at = $3
+        where
+            business_unit_id = $4 and name = $5
+        returning *"#,
+        )
+        .bind(self.content())
+        .bind(self.updated_by())
+        .bind(self.updated_at())
+        .bind(self.business_unit_id())
+        .bind(self.name())
+    }
+
+    fn create_query<'q>(
+        create: Self::Create,
+    ) -> sqlx::query::QueryAs<'q, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        insert into template_partials (
+            business_unit_id,
+            name,
+            content,
+            updated_by,
+            updated_at)
+        values ($1, $2, $3, $4, $5)
+        on conflict (business_unit_id, name) do update
+            set content = excluded.content,
+            updated_by = excluded.updated_by,
+            updated_at = excluded.updated_at
+        returning *"#,
+        )
+        .bind(create.business_unit_id)
+        .bind(create.name)
+        .bind(create.content)
+        .bind(Json(create.updated_by))
+        .bind(create.timestamp)
+    }
+}
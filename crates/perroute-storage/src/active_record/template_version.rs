@@ -0,0 +1,178 @@
+use std::future::Future;
+
+use bon::Builder;
+use perroute_commons::types::{
+    actor::Actor, dispatch_type::DispatchType, id::Id, TemplateVersionStatus,
+    Timestamp,
+};
+use serde_json::Value;
+use sqlx::{
+    postgres::PgArguments, query, query_as, types::Json, Postgres, QueryBuilder,
+};
+use crate::models::template_version::TemplateVersion;
+use super::{
+    datasource::Connection, ActiveRecordResult, Model, ModelQuery, Projection,
+};
+
+#[derive(Debug)]
+pub enum TemplateVersionQuery<'q> {
+    ByTemplate(&'q Id, &'q DispatchType),
+    ByVersion(&'q Id, &'q DispatchType, i32),
+    Published(&'q Id, &'q DispatchType),
+}
+
+impl ModelQuery<TemplateVersion> for TemplateVersionQuery<'_> {
+    fn build(&self, projection: Projection) -> QueryBuilder<'_, Postgres> {
+        let ordered = matches!(projection, Projection::Row);
+        let mut qb = projection.query_builder(Some("tv"));
+        qb.push(" FROM template_versions tv where 1=1 ");
+        let (template_id, dispatch_type) = match self {
+            TemplateVersionQuery::ByTemplate(id, dispatch_type)
+            | TemplateVersionQuery::ByVersion(id, dispatch_type, _)
+            | TemplateVersionQuery::Published(id, dispatch_type) => {
+                (id, dispatch_type)
+            }
+        };
+        qb.push(" AND tv.template_id = ");
+        qb.push_bind(*template_id);
+        qb.push(" AND tv.dispatch_type = ");
+        qb.push_bind(*dispatch_type);
+
+        match self {
+            TemplateVersionQuery::ByTemplate(_, _) => {
+                if ordered {
+                    qb.push(" ORDER BY tv.version desc");
+                }
+                qb
+            }
+            TemplateVersionQuery::ByVersion(_, _, version) => {
+                qb.push(" AND tv.version = ");
+                qb.push_bind(*version);
+                qb
+            }
+            TemplateVersionQuery::Published(_, _) => {
+                qb.push(" AND tv.status = ");
+                qb.push_bind(TemplateVersionStatus::Published);
+                qb
+            }
+        }
+    }
+}
+
+/// A new draft numbered after the latest version of the template. Take the
+/// [`TemplateVersionActiveRecord::lock`] first so concurrent drafts do not
+/// compute the same number.
+#[derive(Debug, Builder)]
+pub struct CreateTemplateVersion {
+    #[builder(into)]
+    template_id: Id,
+    dispatch_type: DispatchType,
+    content: Value,
+    created_by: Actor,
+    #[builder(into)]
+    timestamp: Timestamp,
+}
+
+impl Model for TemplateVersion {
+    type Create = CreateTemplateVersion;
+
+    fn destroy_query(&self) -> sqlx::query::Query<'_, Postgres, PgArguments> {
+        query(
+            r#"
+        delete from template_versions
+        where template_id = $1 and dispatch_type = $2 and version = $3"#,
+        )
+        .bind(self.template_id())
+        .bind(self.dispatch_type())
+        .bind(self.version())
+    }
+
+    fn update_query(
+        &self,
+    ) -> sqlx::query::QueryAs<'_, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        update template_versions
+            set status = $1,
+            published_by = $2,
+            published_at = $3
+        where
+            template_id = $4 and dispatch_type = $5 and version = $6
+        returning *"#,
+        )
+        .bind(self.status())
+        .bind(self.published_by())
+        .bind(self.published_at())
+        .bind(self.template_id())
+        .bind(self.dispatch_type())
+        .bind(self.version())
+    }
+
+    fn create_query<'q>(
+        create: Self::Create,
+    ) -> sqlx::query::QueryAs<'q, Postgres, Self, PgArguments> {
+        query_as(
+            r#"
+        insert into template_versions (
+            template_id,
+            dispatch_type,
+            version,
+            status,
+            content,
+            created_by,
+            created_at)
+        values (
+            $1,
+            $2,
+            (select coalesce(max(version), 0) + 1
+                from template_versions
+                where template_id = $1 and dispatch_type = $2),
+            $3, $4, $5, $6)
+        returning *"#,
+        )
+        .bind(create.template_id)
+        .bind(create.dispatch_type)
+        .bind(TemplateVersionStatus::Draft)
+        .bind(Json(create.content))
+        .bind(Json(create.created_by))
+        .bind(create.timestamp)
+    }
+}
+
+pub trait TemplateVersionActiveRecord<C>
+where
+    C: AsRef<Connection>,
+{
+    /// Serializes changes to the versions of a template until the current
+    /// transaction ends, so numbering a draft or swapping the published
+    /// version never races with another writer.
+    fn lock(
+        conn: C,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> impl Future<Output = ActiveRecordResult<()>>;
+}
+
+impl<C: AsRef<Connection>> TemplateVersionActiveRecord<C> for TemplateVersion {
+    async fn lock(
+        conn: C,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> ActiveRecordResult<()> {
+        let query = query(
+            "select pg_advisory_xact_lock(hashtextextended($1 || '/' || $2, 0))",
+        )
+        .bind(template_id)
+        .bind(dispatch_type);
+
+        match conn.as_ref() {
+            Connection::Pool(pool) => query.execute(pool).await,
+            Connection::Tx(tx) => {
+                let mut x = tx.lock().await;
+                query.execute(x.as_mut()).await
+            }
+        }?;
+
+        Ok(())
+    }
+}
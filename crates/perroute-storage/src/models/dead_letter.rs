@@ -0,0 +1,33 @@
+use derive_getters::Getters;
+use derive_setters::Setters;
+use perroute_commons::types::{id::Id, Timestamp};
+use sqlx::prelude::FromRow;
+
+/// A queue message the dispatcher gave up on, kept with the reason it was
+/// dead-lettered so it can be inspected and redriven.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Getters, Setters)]
+#[setters(prefix = "set_")]
+#[setters(into)]
+pub struct DeadLetter {
+    #[setters(skip)]
+    id: Id,
+    #[setters(skip)]
+    message_id: Option<Id>,
+    #[setters(skip)]
+    queue: String,
+    #[setters(skip)]
+    body: String,
+    #[setters(skip)]
+    reason: String,
+    #[setters(skip)]
+    receive_count: i32,
+    #[setters(skip)]
+    created_at: Timestamp,
+    redriven_at: Option<Timestamp>,
+}
+
+impl perroute_commons::types::entity::Entity for DeadLetter {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+}
@@ -0,0 +1,25 @@
+use derive_getters::Getters;
+use perroute_commons::types::{id::Id, Timestamp};
+use sqlx::prelude::FromRow;
+
+/// A message waiting to be dispatched when Postgres is used as the dispatch
+/// queue. A job is hidden from consumers until `visible_at`, and is kept with
+/// `dead_at` set once the dispatcher gives up on it.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Getters)]
+pub struct DispatchJob {
+    id: Id,
+    message_id: Option<Id>,
+    body: String,
+    attempts: i32,
+    visible_at: Timestamp,
+    last_error: Option<String>,
+    dead_at: Option<Timestamp>,
+    created_at: Timestamp,
+    updated_at: Timestamp,
+}
+
+impl perroute_commons::types::entity::Entity for DispatchJob {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+}
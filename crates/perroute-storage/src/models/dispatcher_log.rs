@@ -10,25 +10,35 @@ use sqlx::{prelude::FromRow, types::Json};
 pub struct DispatcherError {
     pub code: String,
     pub description: String,
+    #[serde(default)]
+    #[builder(default)]
+    pub transient: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, FromRow, Getters)]
 pub struct DispatcherLog {
     id: Id,
     message_id: Id,
-    provider_id: ProviderId,
+    /// None when the message failed before any provider was tried.
+    provider_id: Option<ProviderId>,
     success: bool,
+    attempt: i32,
     error: Option<Json<DispatcherError>>,
     created_at: Timestamp,
 }
 
 impl DispatcherLog {
-    pub fn build_success(message_id: Id, provider_id: ProviderId) -> Self {
+    pub fn build_success(
+        message_id: Id,
+        provider_id: ProviderId,
+        attempt: i32,
+    ) -> Self {
         DispatcherLog {
             id: Id::new(),
             message_id,
-            provider_id,
+            provider_id: Some(provider_id),
             success: true,
+            attempt,
             error: None,
             created_at: Default::default(),
         }
@@ -37,15 +47,39 @@ impl DispatcherLog {
     pub fn build_error(
         message_id: Id,
         provider_id: ProviderId,
+        attempt: i32,
         error: DispatcherError,
     ) -> Self {
         DispatcherLog {
             id: Id::new(),
             message_id,
-            provider_id,
+            provider_id: Some(provider_id),
             success: false,
+            attempt,
             error: Some(Json(error)),
             created_at: Default::default(),
         }
     }
+
+    /// A failure that kept the message from reaching any provider, e.g. no
+    /// route being eligible.
+    pub fn build_failure(
+        message_id: Id,
+        attempt: i32,
+        error: DispatcherError,
+    ) -> Self {
+        DispatcherLog {
+            id: Id::new(),
+            message_id,
+            provider_id: None,
+            success: false,
+            attempt,
+            error: Some(Json(error)),
+            created_at: Default::default(),
+        }
+    }
+
+    pub fn is_transient_error(&self) -> bool {
+        self.error.as_ref().is_some_and(|e| e.transient)
+    }
 }
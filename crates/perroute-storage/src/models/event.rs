@@ -138,6 +138,19 @@ impl TryFrom<&DbEvent> for Event {
                     ApplicationEventData::try_from(value)?,
                 )
             }
+            EventType::TemplateVersionCreated => Event::TemplateVersionCreated(
+                ApplicationEventData::try_from(value)?,
+            ),
+            EventType::TemplateVersionPublished => {
+                Event::TemplateVersionPublished(ApplicationEventData::try_from(
+                    value,
+                )?)
+            }
+            EventType::TemplateVersionRolledBack => {
+                Event::TemplateVersionRolledBack(
+                    ApplicationEventData::try_from(value)?,
+                )
+            }
         })
     }
 }
@@ -163,6 +176,9 @@ impl TryFrom<Event> for DbEvent {
             Event::TemplateAssignmentCreated(d) => DbEvent::try_from(&d),
             Event::TemplateAssignmentUpdated(d) => DbEvent::try_from(&d),
             Event::TemplateAssignmentDeleted(d) => DbEvent::try_from(&d),
+            Event::TemplateVersionCreated(d) => DbEvent::try_from(&d),
+            Event::TemplateVersionPublished(d) => DbEvent::try_from(&d),
+            Event::TemplateVersionRolledBack(d) => DbEvent::try_from(&d),
         }
         .tap_err(|e| log::error!("Failed to convert event to DbEvent: {e}"))
     }
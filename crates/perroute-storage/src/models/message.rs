@@ -1,8 +1,12 @@
 use derive_getters::Getters;
 use derive_setters::Setters;
-use perroute_commons::types::{
-    dispatch_type::DispatchType, id::Id, recipient::Recipient, MessageStatus,
-    Payload, Tags, Timestamp,
+use perroute_commons::{
+    events::MessageCreatedEvent,
+    types::{
+        dispatch_type::DispatchType, id::Id, idempotency_key::IdempotencyKey,
+        locale::Locale, recipient::Recipient, MessageStatus, Payload, Tags,
+        Timestamp,
+    },
 };
 use sqlx::{prelude::FromRow, types::Json};
 
@@ -12,6 +16,9 @@ use sqlx::{prelude::FromRow, types::Json};
 pub struct Message {
     #[setters(skip)]
     id: Id,
+
+    key: Option<IdempotencyKey>,
+
     #[setters(skip)]
     message_type_id: Id,
     #[setters(skip)]
@@ -22,10 +29,13 @@ pub struct Message {
     dispatch_type: DispatchType,
     #[setters(skip)]
     recipient: Json<Recipient>,
+    #[setters(skip)]
+    locale: Option<Locale>,
 
     status: MessageStatus,
 
-    #[setters(skip)]
+    attempts: i32,
+
     scheduled_at: Option<Timestamp>,
 
     #[setters(skip)]
@@ -42,3 +52,18 @@ impl perroute_commons::types::entity::Entity for Message {
         &self.id
     }
 }
+
+impl From<&Message> for MessageCreatedEvent {
+    fn from(message: &Message) -> Self {
+        MessageCreatedEvent::builder()
+            .message_id(message.id())
+            .message_type_id(message.message_type_id())
+            .business_unit_id(message.business_unit_id())
+            .dispatch_type(*message.dispatch_type())
+            .recipient(message.recipient().0.clone())
+            .payload(message.payload().clone())
+            .tags(message.tags().0.clone())
+            .maybe_scheduled_at(message.scheduled_at().clone())
+            .build()
+    }
+}
@@ -1,10 +1,14 @@
 pub mod application;
 pub mod business_unit;
 pub mod channel;
+pub mod dead_letter;
+pub mod dispatch_job;
 pub mod dispatcher_log;
 pub mod event;
 pub mod message;
 pub mod message_type;
 pub mod route;
 pub mod template_assignment;
+pub mod template_partial;
+pub mod template_version;
 pub mod user;
@@ -1,9 +1,32 @@
+use super::{business_unit::BusinessUnit, message_type::MessageType};
 use derive_getters::Getters;
 use perroute_commons::types::{
-    dispatch_type::DispatchType, entity::Entity, id::Id, priority::Priority,
-    vars::Vars, Timestamp,
+    dispatch_type::DispatchType, entity::Entity, id::Id, locale::Locale,
+    priority::Priority, vars::Vars, Timestamp,
 };
+use serde::{Deserialize, Serialize};
 use sqlx::{prelude::FromRow, types::Json};
+use std::collections::BTreeMap;
+
+/// Templates used instead of the default ones for recipients of a locale.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Getters,
+)]
+pub struct LocalizedTemplates {
+    sms_template_id: Option<Id>,
+    email_template_id: Option<Id>,
+    push_template_id: Option<Id>,
+}
+
+impl LocalizedTemplates {
+    pub fn template_id(&self, dispatch_type: &DispatchType) -> &Option<Id> {
+        match dispatch_type {
+            DispatchType::Email => self.email_template_id(),
+            DispatchType::Sms => self.sms_template_id(),
+            DispatchType::Push => self.push_template_id(),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, FromRow, Getters)]
 pub struct TemplateAssignment {
@@ -13,6 +36,7 @@ pub struct TemplateAssignment {
     sms_template_id: Option<Id>,
     email_template_id: Option<Id>,
     push_template_id: Option<Id>,
+    localized_templates: Json<BTreeMap<Locale, LocalizedTemplates>>,
     vars: Json<Vars>,
     priority: Priority,
     start_at: Timestamp,
@@ -30,6 +54,46 @@ impl TemplateAssignment {
             DispatchType::Push => self.push_template_id(),
         }
     }
+
+    /// Best template for the locale, walking its fallbacks (`pt-BR`, then
+    /// `pt`) before settling on the default template. Returns the locale the
+    /// template was found for, `None` meaning the default one.
+    pub fn localized_template_id(
+        &self,
+        dispatch_type: &DispatchType,
+        locale: Option<&Locale>,
+    ) -> Option<(Option<Locale>, &Id)> {
+        locale
+            .into_iter()
+            .flat_map(Locale::fallbacks)
+            .find_map(|locale| {
+                let id = self
+                    .localized_templates
+                    .get(&locale)?
+                    .template_id(dispatch_type)
+                    .as_ref()?;
+                Some((Some(locale), id))
+            })
+            .or_else(|| {
+                self.template_id(dispatch_type)
+                    .as_ref()
+                    .map(|id| (None, id))
+            })
+    }
+
+    /// Vars handed to the templates: the business unit vars, overridden by
+    /// the message type vars, overridden by the assignment vars.
+    pub fn template_vars(
+        assignment: Option<&TemplateAssignment>,
+        business_unit: &BusinessUnit,
+        message_type: &MessageType,
+    ) -> Vars {
+        let vars = business_unit.vars().merge(message_type.vars());
+        match assignment {
+            Some(assignment) => vars.merge(assignment.vars()),
+            None => vars,
+        }
+    }
 }
 
 impl Entity for TemplateAssignment {
@@ -37,3 +101,102 @@ impl Entity for TemplateAssignment {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perroute_commons::types::priority::Priority;
+
+    fn assignment(
+        email_template_id: Option<&str>,
+        localized: &[(&str, &str)],
+    ) -> TemplateAssignment {
+        let localized_templates = localized
+            .iter()
+            .map(|(locale, template_id)| {
+                (
+                    Locale::try_from(*locale).unwrap(),
+                    LocalizedTemplates {
+                        email_template_id: Some(Id::from(*template_id)),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        TemplateAssignment {
+            id: Id::from("ta-1"),
+            business_unit_id: Id::from("bu-1"),
+            message_type_id: Id::from("mt-1"),
+            sms_template_id: None,
+            email_template_id: email_template_id.map(Id::from),
+            push_template_id: None,
+            localized_templates: Json(localized_templates),
+            vars: Json(Vars::default()),
+            priority: Priority::new(0),
+            start_at: Timestamp::now(),
+            end_at: None,
+            enabled: true,
+            created_at: Timestamp::now(),
+            updated_at: Timestamp::now(),
+        }
+    }
+
+    fn template_for(
+        assignment: &TemplateAssignment,
+        locale: Option<&str>,
+    ) -> Option<(Option<String>, String)> {
+        let locale = locale.map(|locale| Locale::try_from(locale).unwrap());
+        assignment
+            .localized_template_id(&DispatchType::Email, locale.as_ref())
+            .map(|(locale, id)| (locale.map(|l| l.to_string()), id.to_string()))
+    }
+
+    #[test]
+    fn localized_template_prefers_the_exact_locale() {
+        let assignment = assignment(
+            Some("tpl-default"),
+            &[("pt-BR", "tpl-pt-br"), ("pt", "tpl-pt")],
+        );
+
+        assert_eq!(
+            template_for(&assignment, Some("pt-BR")),
+            Some((Some("pt-BR".to_string()), "tpl-pt-br".to_string()))
+        );
+    }
+
+    #[test]
+    fn localized_template_falls_back_to_the_language() {
+        let assignment = assignment(Some("tpl-default"), &[("pt", "tpl-pt")]);
+
+        assert_eq!(
+            template_for(&assignment, Some("pt-BR")),
+            Some((Some("pt".to_string()), "tpl-pt".to_string()))
+        );
+    }
+
+    #[test]
+    fn localized_template_falls_back_to_the_default() {
+        let assignment = assignment(Some("tpl-default"), &[("pt", "tpl-pt")]);
+
+        assert_eq!(
+            template_for(&assignment, Some("en-US")),
+            Some((None, "tpl-default".to_string()))
+        );
+        assert_eq!(
+            template_for(&assignment, None),
+            Some((None, "tpl-default".to_string()))
+        );
+    }
+
+    #[test]
+    fn localized_template_is_missing_without_a_match_or_default() {
+        let assignment = assignment(None, &[("pt", "tpl-pt")]);
+
+        assert_eq!(template_for(&assignment, Some("en")), None);
+        assert_eq!(
+            template_for(&assignment, Some("pt-PT")),
+            Some((Some("pt".to_string()), "tpl-pt".to_string()))
+        );
+    }
+}
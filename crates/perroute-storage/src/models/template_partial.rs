@@ -0,0 +1,13 @@
+use derive_getters::Getters;
+use perroute_commons::types::{actor::Actor, id::Id, Timestamp};
+use sqlx::{prelude::FromRow, types::Json};
+
+/// A partial or layout shared by the templates of a business unit.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Getters)]
+pub struct TemplatePartial {
+    business_unit_id: Id,
+    name: String,
+    content: String,
+    updated_by: Json<Actor>,
+    updated_at: Timestamp,
+}
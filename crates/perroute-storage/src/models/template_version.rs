@@ -0,0 +1,40 @@
+use derive_getters::Getters;
+use derive_setters::Setters;
+use perroute_commons::types::{
+    actor::Actor, dispatch_type::DispatchType, id::Id, TemplateVersionStatus,
+    Timestamp,
+};
+use serde_json::Value;
+use sqlx::{prelude::FromRow, types::Json};
+
+/// An immutable revision of a template. Only its status and publication
+/// details change once it is created.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Getters, Setters)]
+#[setters(prefix = "set_")]
+#[setters(into)]
+pub struct TemplateVersion {
+    #[setters(skip)]
+    template_id: Id,
+    #[setters(skip)]
+    dispatch_type: DispatchType,
+    #[setters(skip)]
+    version: i32,
+    status: TemplateVersionStatus,
+    #[setters(skip)]
+    content: Json<Value>,
+    #[setters(skip)]
+    created_by: Json<Actor>,
+    #[setters(skip)]
+    created_at: Timestamp,
+    published_by: Option<Json<Actor>>,
+    published_at: Option<Timestamp>,
+}
+
+impl TemplateVersion {
+    /// The version marked as published by the actor at the timestamp.
+    pub fn into_published(self, actor: &Actor, timestamp: &Timestamp) -> Self {
+        self.set_status(TemplateVersionStatus::Published)
+            .set_published_by(Some(Json(actor.clone())))
+            .set_published_at(Some(timestamp.clone()))
+    }
+}
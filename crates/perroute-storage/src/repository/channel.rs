@@ -0,0 +1,8 @@
+use perroute_commons::types::id::Id;
+use crate::models::channel::Channel;
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait ChannelRepository {
+    async fn find_channel(&self, id: &Id) -> RepositoryResult<Option<Channel>>;
+}
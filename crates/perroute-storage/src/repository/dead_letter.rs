@@ -0,0 +1,13 @@
+use crate::{
+    active_record::dead_letter::CreateDeadLetter,
+    models::dead_letter::DeadLetter,
+};
+use super::RepositoryResult;
+
+#[async_trait::async_trait]
+pub trait DeadLetterRepository {
+    async fn save_dead_letter(
+        &self,
+        create: CreateDeadLetter,
+    ) -> RepositoryResult<DeadLetter>;
+}
@@ -1,4 +1,6 @@
 use business_unit::BusinessUnitRepository;
+use channel::ChannelRepository;
+use dead_letter::DeadLetterRepository;
 use dispatcher_log::DispatcherLogRepository;
 use message::MessageRepository;
 use message_type::MessageTypeRepository;
@@ -6,6 +8,8 @@ use route::RouteRepository;
 use template_assignment::TemplateAssignmentRepository;
 
 pub mod business_unit;
+pub mod channel;
+pub mod dead_letter;
 pub mod dispatcher_log;
 pub mod message;
 pub mod message_type;
@@ -21,6 +25,9 @@ pub type RepositoryResult<T> = Result<T, RepositoryError>;
 pub enum RepositoryError {
     #[error("{0}")]
     SqlxError(#[from] sqlx::Error),
+
+    #[error("{0}")]
+    ActiveRecordError(#[from] crate::active_record::ActiveRecordError),
 }
 
 #[async_trait::async_trait]
@@ -28,8 +35,10 @@ pub trait Repository:
     MessageRepository
     + MessageTypeRepository
     + BusinessUnitRepository
+    + ChannelRepository
     + RouteRepository
     + DispatcherLogRepository
+    + DeadLetterRepository
     + TemplateAssignmentRepository
 {
     type Tx: TransactionalRepository + Send + Sync;
@@ -42,8 +51,10 @@ pub trait TransactionalRepository:
     MessageRepository
     + MessageTypeRepository
     + BusinessUnitRepository
+    + ChannelRepository
     + RouteRepository
     + DispatcherLogRepository
+    + DeadLetterRepository
     + TemplateAssignmentRepository
 {
     async fn commit(self) -> RepositoryResult<()>;
@@ -1,5 +1,8 @@
 use crate::{
-    active_record::business_unit::{BusinessUnitQuery, CreateBusinessUnit},
+    active_record::{
+        business_unit::{BusinessUnitQuery, CreateBusinessUnit},
+        ActiveRecord,
+    },
     models::business_unit::BusinessUnit,
     repository::{business_unit::BusinessUnitRepository, RepositoryResult},
 };
@@ -25,6 +28,6 @@ impl BusinessUnitRepository for PgRepository {
         &self,
         query: BusinessUnitQuery<'q>,
     ) -> RepositoryResult<BusinessUnit> {
-        todo!()
+        Ok(BusinessUnit::fetch_one(&self.datasource, query).await?)
     }
 }
@@ -0,0 +1,42 @@
+use crate::{
+    active_record::{channel::ChannelQuery, ActiveRecord},
+    models::channel::Channel,
+    repository::{channel::ChannelRepository, RepositoryResult},
+};
+use super::PgRepository;
+use perroute_commons::types::id::Id;
+
+#[async_trait::async_trait]
+impl ChannelRepository for PgRepository {
+    async fn find_channel(&self, id: &Id) -> RepositoryResult<Option<Channel>> {
+        Ok(
+            Channel::fetch_optional(&self.datasource, ChannelQuery::ById(id))
+                .await?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_record::datasource::DataSource;
+    use sqlx::PgPool;
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../../fixtures",
+        scripts("messages", "channels")
+    ))]
+    async fn finds_the_channel_by_id(pool: PgPool) {
+        let repository = PgRepository::new(DataSource::new(pool));
+
+        let channel = repository.find_channel(&Id::from("ch-1")).await.unwrap();
+        let missing = repository
+            .find_channel(&Id::from("ch-missing"))
+            .await
+            .unwrap();
+
+        assert_eq!(channel.map(|c| c.id().clone()), Some(Id::from("ch-1")));
+        assert!(missing.is_none());
+    }
+}
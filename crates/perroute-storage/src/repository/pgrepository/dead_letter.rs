@@ -0,0 +1,48 @@
+use crate::{
+    active_record::{dead_letter::CreateDeadLetter, ActiveRecord},
+    models::dead_letter::DeadLetter,
+    repository::{dead_letter::DeadLetterRepository, RepositoryResult},
+};
+use super::PgRepository;
+
+#[async_trait::async_trait]
+impl DeadLetterRepository for PgRepository {
+    async fn save_dead_letter(
+        &self,
+        create: CreateDeadLetter,
+    ) -> RepositoryResult<DeadLetter> {
+        Ok(DeadLetter::create(&self.datasource, create).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_record::datasource::DataSource;
+    use perroute_commons::types::{id::Id, Timestamp};
+    use sqlx::PgPool;
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(path = "../../../fixtures", scripts("messages")))]
+    async fn saves_the_dead_letter_as_pending(pool: PgPool) {
+        let repository = PgRepository::new(DataSource::new(pool));
+
+        let dead_letter = repository
+            .save_dead_letter(
+                CreateDeadLetter::builder()
+                    .message_id(Id::from("msg-1"))
+                    .queue("digest")
+                    .body("{}")
+                    .reason("boom")
+                    .receive_count(5)
+                    .timestamp(Timestamp::now())
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(dead_letter.message_id(), &Some(Id::from("msg-1")));
+        assert_eq!(dead_letter.reason(), "boom");
+        assert_eq!(dead_letter.redriven_at(), &None);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    active_record::message_type::{CreateMessageType, MessageTypeQuery},
+    active_record::{
+        message_type::{CreateMessageType, MessageTypeQuery},
+        ActiveRecord,
+    },
     models::message_type::MessageType,
     repository::{message_type::MessageTypeRepository, RepositoryResult},
 };
@@ -26,6 +29,6 @@ impl MessageTypeRepository for PgRepository {
         &self,
         query: MessageTypeQuery<'q>,
     ) -> RepositoryResult<MessageType> {
-        todo!()
+        Ok(MessageType::fetch_one(&self.datasource, query).await?)
     }
 }
@@ -1,16 +1,25 @@
 pub mod business_unit;
+pub mod channel;
+pub mod dead_letter;
 pub mod dispatcher_log;
 pub mod message;
 pub mod message_type;
 pub mod route;
 pub mod template_assignment;
 
-use sqlx::Database;
-
+use crate::active_record::datasource::{DataSource, NonTransactionalDataSource};
 use super::{Repository, RepositoryResult, TransactionalRepository};
 
 #[derive(Clone)]
-pub struct PgRepository;
+pub struct PgRepository {
+    datasource: DataSource<NonTransactionalDataSource>,
+}
+
+impl PgRepository {
+    pub fn new(datasource: DataSource<NonTransactionalDataSource>) -> Self {
+        Self { datasource }
+    }
+}
 
 #[async_trait::async_trait]
 impl Repository for PgRepository {
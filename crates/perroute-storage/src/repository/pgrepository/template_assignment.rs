@@ -1,4 +1,8 @@
 use crate::{
+    active_record::{
+        template_assignment::{QueryForDispatch, TemplateAssignmentQuery},
+        ActiveRecord,
+    },
     models::template_assignment::TemplateAssignment,
     repository::{
         template_assignment::TemplateAssignmentRepository, RepositoryResult,
@@ -9,9 +13,136 @@ use super::PgRepository;
 
 #[async_trait::async_trait]
 impl TemplateAssignmentRepository for PgRepository {
-    async fn find_template_assingment_for_dispatch(
+    async fn find_template_assingment_for_dispatch<'q>(
         &self,
+        query: QueryForDispatch<'q>,
     ) -> RepositoryResult<Option<TemplateAssignment>> {
-        todo!()
+        Ok(TemplateAssignment::fetch_optional(
+            &self.datasource,
+            TemplateAssignmentQuery::ForDispatch(query),
+        )
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_record::datasource::DataSource;
+    use chrono::TimeDelta;
+    use perroute_commons::types::{
+        dispatch_type::DispatchType, id::Id, locale::Locale, Timestamp,
+    };
+    use sqlx::PgPool;
+
+    async fn assignment_for(
+        pool: PgPool,
+        dispatch_type: DispatchType,
+        locale: Option<&str>,
+        reference_date: Timestamp,
+    ) -> Option<String> {
+        let locale = locale.map(|locale| Locale::try_from(locale).unwrap());
+        PgRepository::new(DataSource::new(pool))
+            .find_template_assingment_for_dispatch(
+                QueryForDispatch::builder()
+                    .business_unit_id(&Id::from("bu-1"))
+                    .message_type_id(&Id::from("mt-1"))
+                    .dispatch_type(&dispatch_type)
+                    .maybe_locale(locale.as_ref())
+                    .reference_date(&reference_date)
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .map(|assignment| assignment.id().to_string())
+    }
+
+    async fn assignment_at(
+        pool: PgPool,
+        dispatch_type: DispatchType,
+        reference_date: Timestamp,
+    ) -> Option<String> {
+        assignment_for(pool, dispatch_type, None, reference_date).await
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../../fixtures",
+        scripts("messages", "template_assignments")
+    ))]
+    async fn highest_priority_wins_then_the_most_recently_started(
+        pool: PgPool,
+    ) {
+        let assignment =
+            assignment_at(pool, DispatchType::Email, Timestamp::now()).await;
+
+        assert_eq!(assignment.as_deref(), Some("ta-high-recent"));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../../fixtures",
+        scripts("messages", "template_assignments")
+    ))]
+    async fn seasonal_assignment_wins_only_inside_its_window(pool: PgPool) {
+        let now = *Timestamp::now();
+
+        let during = assignment_at(
+            pool.clone(),
+            DispatchType::Email,
+            Timestamp::from(now + TimeDelta::days(2)),
+        )
+        .await;
+        let after = assignment_at(
+            pool,
+            DispatchType::Email,
+            Timestamp::from(now + TimeDelta::days(6)),
+        )
+        .await;
+
+        assert_eq!(during.as_deref(), Some("ta-seasonal"));
+        assert_eq!(after.as_deref(), Some("ta-high-recent"));
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../../fixtures",
+        scripts("messages", "template_assignments")
+    ))]
+    async fn assignments_without_a_template_for_the_dispatch_type_are_skipped(
+        pool: PgPool,
+    ) {
+        let sms =
+            assignment_at(pool.clone(), DispatchType::Sms, Timestamp::now())
+                .await;
+        let push =
+            assignment_at(pool, DispatchType::Push, Timestamp::now()).await;
+
+        assert_eq!(sms.as_deref(), Some("ta-sms-only"));
+        assert_eq!(push, None);
+    }
+
+    #[ignore = "requires a Postgres database (DATABASE_URL)"]
+    #[sqlx::test(fixtures(
+        path = "../../../fixtures",
+        scripts("messages", "template_assignments")
+    ))]
+    async fn localized_only_assignments_are_eligible_for_their_locales(
+        pool: PgPool,
+    ) {
+        let now = Timestamp::now();
+
+        let regional = assignment_for(
+            pool.clone(),
+            DispatchType::Push,
+            Some("pt-BR"),
+            now.clone(),
+        )
+        .await;
+        let other =
+            assignment_for(pool, DispatchType::Push, Some("en"), now).await;
+
+        assert_eq!(regional.as_deref(), Some("ta-push-pt"));
+        assert_eq!(other, None);
     }
 }
@@ -1,10 +1,14 @@
-use crate::models::template_assignment::TemplateAssignment;
+use crate::{
+    active_record::template_assignment::QueryForDispatch,
+    models::template_assignment::TemplateAssignment,
+};
 
 use super::RepositoryResult;
 
 #[async_trait::async_trait]
 pub trait TemplateAssignmentRepository {
-    async fn find_template_assingment_for_dispatch(
+    async fn find_template_assingment_for_dispatch<'q>(
         &self,
+        query: QueryForDispatch<'q>,
     ) -> RepositoryResult<Option<TemplateAssignment>>;
 }
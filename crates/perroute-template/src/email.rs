@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+/// Post-processing applied to an email once its parts are rendered. Each
+/// step can be switched on or off per template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailProcessing {
+    /// Compiles the html part from MJML into responsive HTML.
+    pub mjml: bool,
+    /// Moves the rules of `<style>` blocks into `style` attributes.
+    pub inline_css: bool,
+    /// Derives the text part from the html one when it is left empty. On by
+    /// default in builds with the `email_processing` feature only.
+    pub auto_text: bool,
+}
+
+impl Default for EmailProcessing {
+    fn default() -> Self {
+        EmailProcessing {
+            mjml: false,
+            inline_css: false,
+            auto_text: cfg!(feature = "email_processing"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmailProcessingError {
+    #[error("{0} is not supported in this build")]
+    Unsupported(&'static str),
+
+    #[cfg(feature = "email_processing")]
+    #[error("Invalid MJML: {0}")]
+    Mjml(String),
+
+    #[cfg(feature = "email_processing")]
+    #[error("Failed to inline CSS: {0}")]
+    CssInline(#[from] css_inline::InlineError),
+
+    #[cfg(feature = "email_processing")]
+    #[error("Failed to derive the text part: {0}")]
+    Text(#[from] html2text::Error),
+}
+
+/// Width the derived text part is wrapped at.
+#[cfg(feature = "email_processing")]
+const TEXT_WIDTH: usize = 78;
+
+impl EmailProcessing {
+    pub fn html(&self, html: String) -> Result<String, EmailProcessingError> {
+        let html = match self.mjml {
+            true => compile_mjml(&html)?,
+            false => html,
+        };
+
+        match self.inline_css {
+            true => inline_css(&html),
+            false => Ok(html),
+        }
+    }
+
+    /// The text part, derived from the processed html when it is empty.
+    pub fn text(
+        &self,
+        html: &str,
+        text: String,
+    ) -> Result<String, EmailProcessingError> {
+        match self.auto_text && text.trim().is_empty() && !html.is_empty() {
+            true => html_to_text(html),
+            false => Ok(text),
+        }
+    }
+}
+
+#[cfg(feature = "email_processing")]
+fn compile_mjml(source: &str) -> Result<String, EmailProcessingError> {
+    let mjml = mrml::parse(source)
+        .map_err(|e| EmailProcessingError::Mjml(e.to_string()))?;
+    mjml.element
+        .render(&mrml::prelude::render::RenderOptions::default())
+        .map_err(|e| EmailProcessingError::Mjml(e.to_string()))
+}
+
+#[cfg(feature = "email_processing")]
+fn inline_css(html: &str) -> Result<String, EmailProcessingError> {
+    // Linked stylesheets, such as the web fonts MJML adds, are left to the
+    // email client.
+    let inliner = css_inline::CSSInliner::options()
+        .load_remote_stylesheets(false)
+        .build();
+    Ok(inliner.inline(html)?)
+}
+
+#[cfg(feature = "email_processing")]
+fn html_to_text(html: &str) -> Result<String, EmailProcessingError> {
+    let text = html2text::from_read(html.as_bytes(), TEXT_WIDTH)?;
+    Ok(text.trim_end().to_string())
+}
+
+#[cfg(not(feature = "email_processing"))]
+fn compile_mjml(_source: &str) -> Result<String, EmailProcessingError> {
+    Err(EmailProcessingError::Unsupported("MJML"))
+}
+
+#[cfg(not(feature = "email_processing"))]
+fn inline_css(_html: &str) -> Result<String, EmailProcessingError> {
+    Err(EmailProcessingError::Unsupported("CSS inlining"))
+}
+
+#[cfg(not(feature = "email_processing"))]
+fn html_to_text(_html: &str) -> Result<String, EmailProcessingError> {
+    Err(EmailProcessingError::Unsupported("Text generation"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_processing_is_supported_in_this_build() {
+        let processing = EmailProcessing::default();
+
+        assert_eq!(
+            processing.html("<p>Hi</p>".to_string()).unwrap(),
+            "<p>Hi</p>"
+        );
+        assert!(processing.text("<p>Hi</p>", String::new()).is_ok());
+    }
+
+    #[cfg(feature = "email_processing")]
+    #[test]
+    fn compiles_mjml_inlines_css_and_derives_text() {
+        let processing = EmailProcessing {
+            mjml: true,
+            inline_css: true,
+            auto_text: true,
+        };
+
+        let html = processing
+            .html(
+                "<mjml><mj-body><mj-section><mj-column><mj-text>Hello John</mj-text></mj-column></mj-section></mj-body></mjml>"
+                    .to_string(),
+            )
+            .unwrap();
+        assert!(html.contains("<table"));
+        assert!(html.contains("Hello John"));
+        assert!(processing.html("<mjml><mj-body>".to_string()).is_err());
+
+        let processing = EmailProcessing {
+            mjml: false,
+            ..processing
+        };
+        let html = processing
+            .html("<style>p { color: red }</style><p>Hi</p>".to_string())
+            .unwrap();
+        assert!(html.contains(r#"<p style="color: red;">Hi</p>"#));
+
+        assert_eq!(
+            processing
+                .text("<h1>Hi</h1><p>There</p>", String::new())
+                .unwrap(),
+            "# Hi\n\nThere"
+        );
+        assert_eq!(
+            processing.text("<p>Hi</p>", "Written".to_string()).unwrap(),
+            "Written"
+        );
+    }
+}
@@ -1,4 +1,9 @@
+pub mod email;
 pub mod error;
+pub mod limits;
+pub mod lint;
+pub mod partial;
 pub mod render;
 pub mod repository;
 pub mod template;
+pub mod version;
@@ -0,0 +1,160 @@
+use crate::{
+    render::RenderError,
+    template::{RenderedTemplateState, Template},
+};
+use bon::Builder;
+use perroute_commons::configuration::settings::TemplateLimitsSettings;
+
+/// Characters of the GSM 03.38 default alphabet, one septet each.
+const GSM7_BASIC: &str = "@£$¥èéùìòÇ\nØø\rÅåΔ_ΦΓΛΩΠΨΣΘΞÆæßÉ !\"#¤%&'()*+,-./0123456789:;<=>?¡ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÑÜ§¿abcdefghijklmnopqrstuvwxyzäöñüà";
+
+/// Characters of the GSM 03.38 extension table, sent as an escape septet
+/// followed by the character.
+const GSM7_EXTENSION: &str = "\u{c}^{}\\[~]|€";
+
+/// Upper bounds checked on rendered templates, so a message that would be
+/// cut or rejected by the provider fails before being sent. Limits left
+/// unset are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Builder)]
+pub struct RenderLimits {
+    sms_segments: Option<usize>,
+    push_title_length: Option<usize>,
+    push_body_length: Option<usize>,
+    email_subject_length: Option<usize>,
+}
+
+impl From<&TemplateLimitsSettings> for RenderLimits {
+    fn from(settings: &TemplateLimitsSettings) -> Self {
+        RenderLimits {
+            sms_segments: settings.sms_segments,
+            push_title_length: settings.push_title_length,
+            push_body_length: settings.push_body_length,
+            email_subject_length: settings.email_subject_length,
+        }
+    }
+}
+
+impl RenderLimits {
+    /// Fails with the first limit the template exceeds, tagged with the
+    /// part exceeding it.
+    pub fn check(
+        &self,
+        template: &Template<RenderedTemplateState>,
+    ) -> Result<(), RenderError> {
+        match template {
+            Template::Sms(t) => {
+                let segments = sms_segments(t.body());
+                match self.sms_segments {
+                    Some(limit) if segments > limit => {
+                        Err(RenderError::TooManySmsSegments { segments, limit }
+                            .in_part("body"))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            Template::Push(t) => {
+                check_length(t.title(), self.push_title_length)
+                    .map_err(|e| e.in_part("title"))?;
+                check_length(t.body(), self.push_body_length)
+                    .map_err(|e| e.in_part("body"))
+            }
+            Template::Email(t) => {
+                check_length(t.subject(), self.email_subject_length)
+                    .map_err(|e| e.in_part("subject"))
+            }
+        }
+    }
+}
+
+fn check_length(value: &str, limit: Option<usize>) -> Result<(), RenderError> {
+    let length = value.chars().count();
+    match limit {
+        Some(limit) if length > limit => {
+            Err(RenderError::TooLong { length, limit })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Septets a character takes in the GSM 7-bit alphabet, or `None` when it
+/// is not part of it and the message has to be sent as UCS-2.
+pub fn gsm7_septets(c: char) -> Option<usize> {
+    match c {
+        c if GSM7_BASIC.contains(c) => Some(1),
+        c if GSM7_EXTENSION.contains(c) => Some(2),
+        _ => None,
+    }
+}
+
+/// Number of concatenated SMS needed for the body. Bodies within the GSM
+/// 7-bit alphabet fit 160 septets in a single message and 153 per part
+/// otherwise; any other character switches the whole body to UCS-2, with 70
+/// and 67 code units.
+pub fn sms_segments(body: &str) -> usize {
+    let septets = body.chars().map(gsm7_septets).sum::<Option<usize>>();
+
+    let (units, single, part) = match septets {
+        Some(septets) => (septets, 160, 153),
+        None => (body.encode_utf16().count(), 70, 67),
+    };
+
+    match units <= single {
+        true => 1,
+        false => units.div_ceil(part),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        render::{Escape, Renderer},
+        template::Template,
+    };
+
+    struct Verbatim;
+
+    impl Renderer for Verbatim {
+        fn render(
+            &self,
+            template: &str,
+            _escape: Escape,
+        ) -> Result<String, RenderError> {
+            Ok(template.to_string())
+        }
+    }
+
+    #[test]
+    fn counts_gsm_and_ucs2_segments() {
+        assert_eq!(sms_segments(&"a".repeat(160)), 1);
+        assert_eq!(sms_segments(&"a".repeat(161)), 2);
+        assert_eq!(sms_segments(&"€".repeat(80)), 1);
+        assert_eq!(sms_segments(&"€".repeat(81)), 2);
+        assert_eq!(sms_segments(&"ç".repeat(70)), 1);
+        assert_eq!(sms_segments(&format!("{}ç", "a".repeat(70))), 2);
+    }
+
+    #[test]
+    fn rejects_templates_above_the_limits() {
+        let limits = RenderLimits::builder()
+            .sms_segments(1)
+            .push_title_length(5)
+            .build();
+
+        let sms = Template::sms(&"a".repeat(161)).render(&Verbatim).unwrap();
+        let error = limits.check(&sms).unwrap_err();
+        assert_eq!(error.part(), Some("body"));
+        assert!(error.to_string().contains("2 segments"));
+
+        let push = Template::push("Hello!", "Hi").render(&Verbatim).unwrap();
+        assert_eq!(
+            limits.check(&push).unwrap_err().to_string(),
+            "Failed to render title: 6 characters, more than the limit of 5"
+        );
+
+        let push = Template::push("Hello", &"a".repeat(1000))
+            .render(&Verbatim)
+            .unwrap();
+        assert!(limits.check(&push).is_ok());
+    }
+}
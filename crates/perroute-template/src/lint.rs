@@ -0,0 +1,313 @@
+#[cfg(feature = "handlebars")]
+mod handlebars;
+#[cfg(feature = "minijinja")]
+mod minijinja;
+
+use crate::{
+    render::TemplateEngine,
+    template::{NotRenderedTemplateState, Template},
+};
+use bon::Builder;
+use perroute_commons::types::{schema::Schema, vars::Vars};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// A problem found in a template without rendering it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LintIssue {
+    /// The part does not compile with the template engine.
+    SyntaxError {
+        part: &'static str,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+
+    /// The part reads a path that is neither described by the payload schema
+    /// nor one of the vars, e.g. `payload.costumer.name`.
+    UnknownReference { part: &'static str, path: String },
+
+    /// A field the payload schema requires is never read by the template.
+    UnusedRequiredField { path: String },
+}
+
+/// A template that does not compile, with the line and column of the error
+/// when the engine reports it.
+#[derive(Debug)]
+struct SyntaxError {
+    message: String,
+    position: Option<(usize, usize)>,
+}
+
+/// Checks the paths referenced by a template against the payload schema of
+/// its message type and the vars available when rendering. References are
+/// only checked against what is given, so a linter without schema or vars
+/// reports syntax errors alone.
+#[derive(Debug, Default, Builder)]
+pub struct TemplateLinter<'a> {
+    schema: Option<&'a Schema>,
+    vars: Option<&'a Vars>,
+}
+
+impl TemplateLinter<'_> {
+    pub fn lint(
+        &self,
+        template: &Template<NotRenderedTemplateState>,
+    ) -> Vec<LintIssue> {
+        let mut issues = vec![];
+        let mut used = BTreeSet::new();
+
+        for (part, source) in template.parts() {
+            let references = match references(template.engine(), source) {
+                Ok(references) => references,
+                Err(e) => {
+                    issues.push(LintIssue::SyntaxError {
+                        part,
+                        message: e.message,
+                        line: e.position.map(|(line, _)| line),
+                        column: e.position.map(|(_, column)| column),
+                    });
+                    continue;
+                }
+            };
+
+            for reference in references {
+                if !self.resolves(&reference) {
+                    issues.push(LintIssue::UnknownReference {
+                        part,
+                        path: reference.join("."),
+                    });
+                }
+                used.insert(reference);
+            }
+        }
+
+        if let Some(schema) = self.schema {
+            let mut required = vec![];
+            required_paths(schema, &mut vec![], &mut required);
+            issues.extend(
+                required
+                    .into_iter()
+                    .filter(|path| !is_used(&used, path))
+                    .map(|path| LintIssue::UnusedRequiredField {
+                        path: format!("payload.{}", path.join(".")),
+                    }),
+            );
+        }
+
+        issues
+    }
+
+    fn resolves(&self, reference: &[String]) -> bool {
+        match reference.split_first() {
+            None => true,
+            Some((root, path)) if root == "payload" => self
+                .schema
+                .is_none_or(|schema| schema_resolves(schema, path)),
+            Some((root, path)) if root == "vars" => match path {
+                [] => true,
+                [key] => self.vars.is_none_or(|vars| vars.contains_key(key)),
+                _ => self.vars.is_none(),
+            },
+            Some(_) => false,
+        }
+    }
+}
+
+/// Every path a template part reads from the render context, split in
+/// segments. Elements of arrays are read through a `[]` segment.
+fn references(
+    engine: TemplateEngine,
+    source: &str,
+) -> Result<BTreeSet<Vec<String>>, SyntaxError> {
+    match engine {
+        #[cfg(feature = "handlebars")]
+        TemplateEngine::Handlebars => handlebars::references(source),
+        #[cfg(not(feature = "handlebars"))]
+        TemplateEngine::Handlebars => Ok(BTreeSet::new()),
+        #[cfg(feature = "minijinja")]
+        TemplateEngine::MiniJinja => minijinja::references(source),
+        #[cfg(not(feature = "minijinja"))]
+        TemplateEngine::MiniJinja => Ok(BTreeSet::new()),
+    }
+}
+
+/// Whether the path can be read from a value described by the schema. Only
+/// structure the schema declares is checked, anything else is accepted.
+fn schema_resolves(schema: &Value, path: &[String]) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        return true;
+    };
+
+    let mut constrained = false;
+    for keyword in ["allOf", "anyOf", "oneOf"] {
+        if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+            constrained = true;
+            if schemas.iter().any(|s| schema_resolves(s, path)) {
+                return true;
+            }
+        }
+    }
+
+    if let Some(properties) =
+        schema.get("properties").and_then(Value::as_object)
+    {
+        constrained = true;
+        let found = match segment.as_str() {
+            "[]" => properties.values().any(|s| schema_resolves(s, rest)),
+            name => properties
+                .get(name)
+                .is_some_and(|s| schema_resolves(s, rest)),
+        };
+        if found {
+            return true;
+        }
+    }
+
+    if let Some(items) = schema.get("items") {
+        constrained = true;
+        let element = segment == "[]" || segment.parse::<usize>().is_ok();
+        if (element && schema_resolves(items, rest))
+            || (segment == "length" && rest.is_empty())
+        {
+            return true;
+        }
+    }
+
+    match schema.get("additionalProperties") {
+        Some(Value::Bool(false)) => constrained = true,
+        Some(additional @ Value::Object(_)) => {
+            constrained = true;
+            if schema_resolves(additional, rest) {
+                return true;
+            }
+        }
+        _ => {}
+    }
+
+    let scalar = schema.get("type").and_then(Value::as_str).is_some_and(|t| {
+        matches!(t, "string" | "number" | "integer" | "boolean" | "null")
+    });
+
+    !constrained && !scalar
+}
+
+fn required_paths(
+    schema: &Value,
+    prefix: &mut Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+) {
+    if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+        for schema in schemas {
+            required_paths(schema, prefix, paths);
+        }
+    }
+
+    let required = schema.get("required").and_then(Value::as_array);
+    for name in required.into_iter().flatten().filter_map(Value::as_str) {
+        prefix.push(name.to_string());
+        paths.push(prefix.clone());
+        if let Some(property) = schema.pointer(&format!("/properties/{name}")) {
+            required_paths(property, prefix, paths);
+        }
+        prefix.pop();
+    }
+}
+
+/// A field is used when it, one of its fields or one of its parents is read.
+fn is_used(references: &BTreeSet<Vec<String>>, path: &[String]) -> bool {
+    references
+        .iter()
+        .any(|reference| match reference.split_first() {
+            None => true,
+            Some((root, reference)) => {
+                root == "payload"
+                    && (reference.starts_with(path)
+                        || path.starts_with(reference))
+            }
+        })
+}
+
+#[cfg(all(test, feature = "handlebars"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn reports_unknown_references_and_unused_required_fields() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "required": ["customer", "order_id"],
+            "properties": {
+                "customer": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}}
+                },
+                "order_id": {"type": "string"},
+                "items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"sku": {"type": "string"}}
+                    }
+                }
+            }
+        }));
+        let vars = Vars::from(&HashMap::from([(
+            "support_email".to_string(),
+            "help@example.com".to_string(),
+        )]));
+        let linter = TemplateLinter::builder()
+            .schema(&schema)
+            .vars(&vars)
+            .build();
+
+        let template = Template::push(
+            "Hi {{payload.customer.name}}",
+            "{{#each payload.items as |item|}}{{item.sku}} {{price}}{{/each}} \
+             {{#with payload.customer}}{{nickname}}{{/with}} \
+             {{vars.support_email}} {{vars.missing}} {{upper payload.total}}",
+        );
+
+        assert_eq!(
+            linter.lint(&template),
+            vec![
+                LintIssue::UnknownReference {
+                    part: "body",
+                    path: "payload.customer.nickname".to_string()
+                },
+                LintIssue::UnknownReference {
+                    part: "body",
+                    path: "payload.items.[].price".to_string()
+                },
+                LintIssue::UnknownReference {
+                    part: "body",
+                    path: "payload.total".to_string()
+                },
+                LintIssue::UnknownReference {
+                    part: "body",
+                    path: "vars.missing".to_string()
+                },
+                LintIssue::UnusedRequiredField {
+                    path: "payload.order_id".to_string()
+                },
+            ]
+        );
+
+        let broken = Template::sms("Hi {{#if payload.customer}}");
+        assert!(matches!(
+            linter.lint(&broken).as_slice(),
+            [
+                LintIssue::SyntaxError {
+                    part: "body",
+                    line: Some(1),
+                    ..
+                },
+                ..
+            ]
+        ));
+    }
+}
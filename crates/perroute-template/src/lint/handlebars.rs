@@ -0,0 +1,216 @@
+use super::SyntaxError;
+use handlebars::{
+    template::{
+        BlockParam, HelperTemplate, Parameter, Template, TemplateElement,
+    },
+    Path, PathSeg,
+};
+use std::collections::{BTreeSet, HashMap};
+
+pub(super) fn references(
+    source: &str,
+) -> Result<BTreeSet<Vec<String>>, SyntaxError> {
+    let template = Template::compile(source).map_err(|e| SyntaxError {
+        message: e.reason().to_string(),
+        position: e.pos(),
+    })?;
+
+    let mut collector = Collector::new();
+    collector.template(&template, &[Scope::default()]);
+    Ok(collector.references)
+}
+
+/// The context of a block: the path `this` points to and the block params it
+/// declares. Params without a path, like the index of `each`, are local
+/// values.
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    this: Vec<String>,
+    params: HashMap<String, Option<Vec<String>>>,
+}
+
+struct Collector {
+    up: Option<PathSeg>,
+    root: Option<PathSeg>,
+    references: BTreeSet<Vec<String>>,
+}
+
+/// The segment the parser produces for `../` or `@root`, which can't be
+/// named outside of handlebars.
+fn ruled(raw: &str) -> Option<PathSeg> {
+    match Path::parse(raw).ok()? {
+        Path::Relative((segs, _)) => segs.into_iter().next(),
+        Path::Local(_) => None,
+    }
+}
+
+impl Collector {
+    fn new() -> Self {
+        Collector {
+            up: ruled("../this"),
+            root: ruled("@root.this"),
+            references: BTreeSet::new(),
+        }
+    }
+
+    fn template(&mut self, template: &Template, scopes: &[Scope]) {
+        for element in &template.elements {
+            self.element(element, scopes);
+        }
+    }
+
+    fn element(&mut self, element: &TemplateElement, scopes: &[Scope]) {
+        match element {
+            TemplateElement::Expression(helper)
+            | TemplateElement::HtmlExpression(helper) => {
+                if helper.params.is_empty() && helper.hash.is_empty() {
+                    self.parameter(&helper.name, scopes);
+                } else {
+                    self.arguments(helper, scopes);
+                }
+            }
+            TemplateElement::HelperBlock(helper) => self.block(helper, scopes),
+            TemplateElement::PartialExpression(decorator)
+            | TemplateElement::PartialBlock(decorator)
+            | TemplateElement::DecoratorExpression(decorator)
+            | TemplateElement::DecoratorBlock(decorator) => {
+                for param in
+                    decorator.params.iter().chain(decorator.hash.values())
+                {
+                    self.parameter(param, scopes);
+                }
+                if let Some(template) = &decorator.template {
+                    self.template(template, scopes);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn arguments(&mut self, helper: &HelperTemplate, scopes: &[Scope]) {
+        if let Parameter::Subexpression(_) = &helper.name {
+            self.parameter(&helper.name, scopes);
+        }
+        for param in helper.params.iter().chain(helper.hash.values()) {
+            self.parameter(param, scopes);
+        }
+    }
+
+    /// `each` and `with` move the context to their argument, any other block
+    /// renders in the context of its parent.
+    fn block(&mut self, helper: &HelperTemplate, scopes: &[Scope]) {
+        self.arguments(helper, scopes);
+
+        let target =
+            helper.params.first().and_then(|p| self.resolve(p, scopes));
+        let scope = match (helper.name.as_name(), target) {
+            (Some("each"), Some(target)) => {
+                let element = [target, vec!["[]".to_string()]].concat();
+                Some(Scope {
+                    params: block_params(&helper.block_param, Some(&element)),
+                    this: element,
+                })
+            }
+            (Some("with"), Some(target)) => Some(Scope {
+                params: block_params(&helper.block_param, Some(&target)),
+                this: target,
+            }),
+            _ => None,
+        };
+
+        if let Some(template) = &helper.template {
+            match scope {
+                Some(scope) => {
+                    self.template(template, &[scopes, &[scope]].concat())
+                }
+                None => self.template(template, scopes),
+            }
+        }
+        if let Some(inverse) = &helper.inverse {
+            self.template(inverse, scopes);
+        }
+    }
+
+    fn parameter(&mut self, param: &Parameter, scopes: &[Scope]) {
+        if let Some(path) = self.resolve(param, scopes) {
+            self.references.insert(path);
+        }
+    }
+
+    /// The path a parameter reads from the root of the context, if any.
+    fn resolve(
+        &mut self,
+        param: &Parameter,
+        scopes: &[Scope],
+    ) -> Option<Vec<String>> {
+        match param {
+            Parameter::Name(name) if name == "this" => lookup(scopes, 0, &[]),
+            Parameter::Name(name) => {
+                lookup(scopes, 0, std::slice::from_ref(name))
+            }
+            Parameter::Path(Path::Relative((segs, _))) => {
+                let names = segs
+                    .iter()
+                    .filter_map(|seg| match seg {
+                        PathSeg::Named(name) => Some(name.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                if self.root.is_some() && segs.first() == self.root.as_ref() {
+                    return Some(names);
+                }
+                let ups = segs
+                    .iter()
+                    .filter(|seg| self.up.as_ref() == Some(*seg))
+                    .count();
+                lookup(scopes, ups, &names)
+            }
+            Parameter::Subexpression(subexpression) => {
+                self.element(subexpression.as_element(), scopes);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+fn lookup(
+    scopes: &[Scope],
+    ups: usize,
+    names: &[String],
+) -> Option<Vec<String>> {
+    let visible = &scopes[..scopes.len().saturating_sub(ups).max(1)];
+    if let Some((first, rest)) = names.split_first() {
+        let param = visible
+            .iter()
+            .rev()
+            .find_map(|scope| scope.params.get(first));
+        if let Some(param) = param {
+            return param.as_ref().map(|path| [path, rest].concat());
+        }
+    }
+
+    let this = visible.last().map(|scope| scope.this.as_slice())?;
+    Some([this, names].concat())
+}
+
+fn block_params(
+    block_param: &Option<BlockParam>,
+    target: Option<&Vec<String>>,
+) -> HashMap<String, Option<Vec<String>>> {
+    let name = |param: &Parameter| param.as_name().map(String::from);
+    match block_param {
+        Some(BlockParam::Single(param)) => name(param)
+            .map(|n| (n, target.cloned()))
+            .into_iter()
+            .collect(),
+        Some(BlockParam::Pair((value, key))) => [
+            name(value).map(|n| (n, target.cloned())),
+            name(key).map(|n| (n, None)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        _ => HashMap::new(),
+    }
+}
@@ -0,0 +1,29 @@
+use super::SyntaxError;
+use crate::render::minijinja::Error;
+use minijinja::Environment;
+use std::collections::{BTreeSet, HashSet};
+
+pub(super) fn references(
+    source: &str,
+) -> Result<BTreeSet<Vec<String>>, SyntaxError> {
+    let env = Environment::new();
+    let globals = env
+        .globals()
+        .map(|(name, _)| name.to_string())
+        .collect::<HashSet<_>>();
+
+    let template = env.template_from_str(source).map_err(|e| {
+        let e = Error::from(e);
+        SyntaxError {
+            message: e.to_string(),
+            position: e.position(),
+        }
+    })?;
+
+    Ok(template
+        .undeclared_variables(true)
+        .into_iter()
+        .map(|variable| variable.split('.').map(String::from).collect())
+        .filter(|path: &Vec<String>| !globals.contains(&path[0]))
+        .collect())
+}
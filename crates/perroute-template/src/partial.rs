@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Partials and layouts shared by the templates of a business unit, by name,
+/// e.g. `footer` for `{{> footer}}` or `layout` for `{{#> layout}}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Partials(HashMap<String, String>);
+
+impl Partials {
+    /// Names are used as file names and in templates, so they are kept to
+    /// letters, digits, `-` and `_`.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= 100
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Into<String>,
+    ) {
+        self.0.insert(name.into(), source.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// A hash of the names and sources, the same for equal partials
+    /// whatever the order they were inserted in.
+    pub fn fingerprint(&self) -> u64 {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_unstable();
+        let mut hasher = DefaultHasher::new();
+        entries.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, source)| (name.as_str(), source.as_str()))
+    }
+}
+
+impl<N: Into<String>, S: Into<String>> FromIterator<(N, S)> for Partials {
+    fn from_iter<T: IntoIterator<Item = (N, S)>>(iter: T) -> Self {
+        Partials(
+            iter.into_iter()
+                .map(|(name, source)| (name.into(), source.into()))
+                .collect(),
+        )
+    }
+}
@@ -1,29 +1,173 @@
+pub mod engines;
 #[cfg(feature = "handlebars")]
 pub mod handlebars;
+#[cfg(feature = "minijinja")]
+pub mod minijinja;
 
-use perroute_commons::types::{vars::Vars, Payload};
-use serde::Serialize;
+use crate::{email::EmailProcessingError, partial::Partials};
+use perroute_commons::types::{id::Id, vars::Vars, Payload};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Template language a template is written in. Stored with the template so
+/// templates of different engines can coexist.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateEngine {
+    #[default]
+    Handlebars,
+    MiniJinja,
+}
+
+impl Display for TemplateEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateEngine::Handlebars => write!(f, "handlebars"),
+            TemplateEngine::MiniJinja => write!(f, "minijinja"),
+        }
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum RenderError {
     #[cfg(feature = "handlebars")]
-    #[error("")]
+    #[error("{0}")]
     HandlebarsError(#[from] handlebars::Error),
 
+    #[cfg(feature = "minijinja")]
+    #[error("{0}")]
+    MiniJinjaError(#[from] minijinja::Error),
+
+    #[error("{0}")]
+    EmailProcessingError(#[from] EmailProcessingError),
+
+    #[error("The {0} template engine is not available")]
+    EngineNotAvailable(TemplateEngine),
+
+    #[error(
+        "The SMS needs {segments} segments, more than the limit of {limit}"
+    )]
+    TooManySmsSegments { segments: usize, limit: usize },
+
+    #[error("{length} characters, more than the limit of {limit}")]
+    TooLong { length: usize, limit: usize },
+
+    #[error("Failed to render {part}: {source}")]
+    Part {
+        part: &'static str,
+        #[source]
+        source: Box<RenderError>,
+    },
+
     #[cfg(test)]
     #[error("{0}")]
     FailedTemplateRenderPluginError(String),
 }
 
+impl RenderError {
+    /// Tags the error with the template part being rendered, e.g. `subject`.
+    pub fn in_part(self, part: &'static str) -> Self {
+        RenderError::Part {
+            part,
+            source: Box::new(self),
+        }
+    }
+
+    pub fn part(&self) -> Option<&'static str> {
+        match self {
+            RenderError::Part { part, .. } => Some(part),
+            _ => None,
+        }
+    }
+
+    /// Whether the rendered template is over one of the
+    /// [render limits](crate::limits::RenderLimits).
+    pub fn is_over_limit(&self) -> bool {
+        match self {
+            RenderError::TooManySmsSegments { .. }
+            | RenderError::TooLong { .. } => true,
+            RenderError::Part { source, .. } => source.is_over_limit(),
+            _ => false,
+        }
+    }
+
+    /// Line and column in the template source where rendering failed, when
+    /// the engine reports it.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            #[cfg(feature = "handlebars")]
+            RenderError::HandlebarsError(e) => e.position(),
+            #[cfg(feature = "minijinja")]
+            RenderError::MiniJinjaError(e) => e.position(),
+            RenderError::EmailProcessingError(_)
+            | RenderError::EngineNotAvailable(_)
+            | RenderError::TooManySmsSegments { .. }
+            | RenderError::TooLong { .. } => None,
+            RenderError::Part { source, .. } => source.position(),
+            #[cfg(test)]
+            RenderError::FailedTemplateRenderPluginError(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct TemplateRenderContext<'ctx> {
     payload: &'ctx Payload,
     vars: &'ctx Vars,
+    #[serde(skip)]
+    partials: Option<&'ctx Partials>,
+    #[serde(skip)]
+    engine: TemplateEngine,
+    #[serde(skip)]
+    template: Option<(&'ctx Id, Option<i32>)>,
 }
 
 impl<'ctx> TemplateRenderContext<'ctx> {
     pub fn new(payload: &'ctx Payload, vars: &'ctx Vars) -> Self {
-        Self { payload, vars }
+        Self {
+            payload,
+            vars,
+            partials: None,
+            engine: TemplateEngine::default(),
+            template: None,
+        }
+    }
+
+    /// Id and version of the template rendered in this context, letting
+    /// engines reuse what they parsed for it on previous renders.
+    pub fn with_template(
+        mut self,
+        template_id: &'ctx Id,
+        version: Option<i32>,
+    ) -> Self {
+        self.template = Some((template_id, version));
+        self
+    }
+
+    /// Engine the rendered template is written in, see
+    /// [`engines::RenderEngines`].
+    pub fn with_engine(mut self, engine: TemplateEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Makes the partials available to the templates rendered in this
+    /// context.
+    pub fn with_partials(mut self, partials: &'ctx Partials) -> Self {
+        self.partials = Some(partials);
+        self
     }
 
     pub fn payload(&self) -> &Payload {
@@ -33,6 +177,18 @@ impl<'ctx> TemplateRenderContext<'ctx> {
     pub fn vars(&self) -> &Vars {
         &self.vars
     }
+
+    pub fn partials(&self) -> Option<&Partials> {
+        self.partials
+    }
+
+    pub fn engine(&self) -> TemplateEngine {
+        self.engine
+    }
+
+    pub fn template(&self) -> Option<(&Id, Option<i32>)> {
+        self.template
+    }
 }
 
 pub trait TemplateRenderPlugin {
@@ -42,6 +198,29 @@ pub trait TemplateRenderPlugin {
     ) -> Box<dyn Renderer + 'c>;
 }
 
+/// How the values a template writes are escaped. Chosen by the template part
+/// being rendered, so only HTML parts get HTML entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    Html,
+    Plain,
+}
+
 pub trait Renderer {
-    fn render(&self, template: &str) -> Result<String, RenderError>;
+    fn render(
+        &self,
+        template: &str,
+        escape: Escape,
+    ) -> Result<String, RenderError>;
+
+    /// Renders a part of a template, e.g. `subject`, tagging errors with the
+    /// part. Engines caching parsed templates keep them by part.
+    fn render_part(
+        &self,
+        part: &'static str,
+        template: &str,
+        escape: Escape,
+    ) -> Result<String, RenderError> {
+        self.render(template, escape).map_err(|e| e.in_part(part))
+    }
 }
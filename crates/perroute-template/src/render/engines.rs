@@ -0,0 +1,49 @@
+use super::{
+    Escape, RenderError, Renderer, TemplateEngine, TemplateRenderContext,
+    TemplateRenderPlugin,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Hands each template to the plugin of the engine it is written in, as set
+/// with [`TemplateRenderContext::with_engine`].
+#[derive(Clone, Default)]
+pub struct RenderEngines {
+    plugins:
+        HashMap<TemplateEngine, Arc<dyn TemplateRenderPlugin + Send + Sync>>,
+}
+
+impl RenderEngines {
+    pub fn with_engine(
+        mut self,
+        engine: TemplateEngine,
+        plugin: impl TemplateRenderPlugin + Send + Sync + 'static,
+    ) -> Self {
+        self.plugins.insert(engine, Arc::new(plugin));
+        self
+    }
+}
+
+impl TemplateRenderPlugin for RenderEngines {
+    fn renderer<'c>(
+        &'c self,
+        context: TemplateRenderContext<'c>,
+    ) -> Box<dyn Renderer + 'c> {
+        let engine = context.engine();
+        match self.plugins.get(&engine) {
+            Some(plugin) => plugin.renderer(context),
+            None => Box::new(Unavailable(engine)),
+        }
+    }
+}
+
+struct Unavailable(TemplateEngine);
+
+impl Renderer for Unavailable {
+    fn render(
+        &self,
+        _template: &str,
+        _escape: Escape,
+    ) -> Result<String, RenderError> {
+        Err(RenderError::EngineNotAvailable(self.0))
+    }
+}
@@ -1,25 +1,245 @@
-use std::sync::Arc;
+pub mod helpers;
 
-use handlebars::Handlebars;
-use super::{RenderError, Renderer, TemplateRenderContext, TemplateRenderPlugin};
+use std::{
+    cell::OnceCell,
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use handlebars::{
+    no_escape, Context, Handlebars, HelperDef, RenderContext,
+    RenderErrorReason, Renderable, StringOutput, Template,
+};
+use lru::LruCache;
+use perroute_commons::types::id::Id;
+use super::{
+    Escape, RenderError, Renderer, TemplateRenderContext, TemplateRenderPlugin,
+};
+
+/// Parsed templates kept by default, see
+/// [`HandlebarsPlugin::with_cache_capacity`].
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Registries kept with the partials of a business unit registered, one
+/// per distinct set of partials.
+const PARTIAL_REGISTRIES_CAPACITY: usize = 64;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("")]
+    #[error("{0}")]
     RWenderError(#[from] handlebars::RenderError),
+
+    #[cfg(feature = "script_helpers")]
+    #[error("Failed to read helper scripts from {0}: {1}")]
+    ScriptDirectoryError(std::path::PathBuf, std::io::Error),
+
+    #[cfg(feature = "script_helpers")]
+    #[error("Failed to load helper script {0}: {1}")]
+    ScriptError(std::path::PathBuf, String),
+}
+
+impl Error {
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            Error::RWenderError(e) => match e.reason() {
+                RenderErrorReason::TemplateError(e) => e.pos(),
+                _ => e.line_no.zip(e.column_no),
+            },
+            #[cfg(feature = "script_helpers")]
+            Error::ScriptDirectoryError(_, _) | Error::ScriptError(_, _) => {
+                None
+            }
+        }
+    }
+}
+
+/// A part of a version of a template, e.g. the `subject` of an email.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    template_id: Id,
+    version: Option<i32>,
+    part: &'static str,
+}
+
+struct Parsed {
+    /// Stores without versions change templates under the same key, so a
+    /// part is parsed again when its source is not the one cached.
+    source: String,
+    template: Arc<Template>,
+}
+
+/// Parsed template parts, along with the version cached for each template
+/// and its parts, so caching another version drops the previous one.
+struct ParsedTemplates {
+    parsed: LruCache<CacheKey, Parsed>,
+    versions: HashMap<Id, (Option<i32>, Vec<&'static str>)>,
 }
 
+impl ParsedTemplates {
+    fn new(capacity: NonZeroUsize) -> Self {
+        ParsedTemplates {
+            parsed: LruCache::new(capacity),
+            versions: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey, source: &str) -> Option<Arc<Template>> {
+        self.parsed
+            .get(key)
+            .filter(|parsed| parsed.source == source)
+            .map(|parsed| parsed.template.clone())
+    }
+
+    fn put(&mut self, key: CacheKey, source: &str, template: Arc<Template>) {
+        let (version, parts) = self
+            .versions
+            .entry(key.template_id.clone())
+            .or_insert_with(|| (key.version, Vec::new()));
+        if *version != key.version {
+            for part in parts.drain(..) {
+                self.parsed.pop(&CacheKey {
+                    template_id: key.template_id.clone(),
+                    version: *version,
+                    part,
+                });
+            }
+            *version = key.version;
+        }
+        if !parts.contains(&key.part) {
+            parts.push(key.part);
+        }
+
+        let parsed = Parsed {
+            source: source.to_string(),
+            template,
+        };
+        if let Some((evicted, _)) = self.parsed.push(key.clone(), parsed) {
+            if evicted != key {
+                self.forget(&evicted);
+            }
+        }
+    }
+
+    /// Drops an evicted part from the versions cached.
+    fn forget(&mut self, key: &CacheKey) {
+        let Some((version, parts)) = self.versions.get_mut(&key.template_id)
+        else {
+            return;
+        };
+        if *version == key.version {
+            parts.retain(|part| *part != key.part);
+            if parts.is_empty() {
+                self.versions.remove(&key.template_id);
+            }
+        }
+    }
+}
+
+/// Registries with partials registered, by [`Partials::fingerprint`].
+///
+/// [`Partials::fingerprint`]: crate::partial::Partials::fingerprint
+type RegistriesByPartials<'hb> =
+    Arc<Mutex<LruCache<u64, Arc<PartialRegistries<'hb>>>>>;
+
+/// Renders with Handlebars, with the built-in [`helpers`] registered.
+///
+/// The parts of templates rendered in a context [with their id and
+/// version](TemplateRenderContext::with_template) are parsed once and kept
+/// in a bounded LRU cache, shared by the clones of the plugin. Caching a new
+/// version of a template drops the ones parsed for its other versions.
+/// Registries with the partials of a business unit registered are kept
+/// too, until its partials change.
 #[derive(Clone)]
 pub struct HandlebarsPlugin<'hb> {
+    /// Registry for HTML parts, escaping the values written.
     handlebars: Arc<Handlebars<'hb>>,
+    /// Same registry writing values as they are, for plain-text parts.
+    plain: Arc<Handlebars<'hb>>,
+    cache: Option<Arc<Mutex<ParsedTemplates>>>,
+    with_partials: RegistriesByPartials<'hb>,
+}
+
+impl Default for HandlebarsPlugin<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'hb> HandlebarsPlugin<'hb> {
     pub fn new() -> Self {
+        let mut handlebars = Handlebars::new();
+        helpers::register(&mut handlebars);
+        Self::from_registry(handlebars, None)
+            .with_cache_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+
+    fn from_registry(
+        handlebars: Handlebars<'hb>,
+        cache: Option<Arc<Mutex<ParsedTemplates>>>,
+    ) -> Self {
+        let mut plain = handlebars.clone();
+        plain.register_escape_fn(no_escape);
         HandlebarsPlugin {
-            handlebars: Arc::new(Handlebars::new()),
+            handlebars: Arc::new(handlebars),
+            plain: Arc::new(plain),
+            cache,
+            with_partials: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(PARTIAL_REGISTRIES_CAPACITY).unwrap(),
+            ))),
         }
     }
+
+    /// Keeps up to `capacity` parsed template parts instead of
+    /// [`DEFAULT_CACHE_CAPACITY`]. A zero capacity disables the cache.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache = NonZeroUsize::new(capacity).map(|capacity| {
+            Arc::new(Mutex::new(ParsedTemplates::new(capacity)))
+        });
+        self
+    }
+
+    /// Registers an extra helper, replacing a built-in one with the same
+    /// name.
+    pub fn with_helper(
+        self,
+        name: &str,
+        helper: impl HelperDef + Send + Sync + 'hb,
+    ) -> Self {
+        let mut handlebars = Arc::unwrap_or_clone(self.handlebars);
+        handlebars.register_helper(name, Box::new(helper));
+        Self::from_registry(handlebars, self.cache)
+    }
+
+    /// Registers every `.rhai` script in the directory as a helper named
+    /// after the file, e.g. `greeting.rhai` as `{{greeting ...}}`.
+    #[cfg(feature = "script_helpers")]
+    pub fn with_script_helpers(
+        self,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| Error::ScriptDirectoryError(dir.to_path_buf(), e))?;
+        let mut handlebars = Arc::unwrap_or_clone(self.handlebars);
+
+        for entry in entries {
+            let path = entry
+                .map_err(|e| Error::ScriptDirectoryError(dir.to_path_buf(), e))?
+                .path();
+            if path.extension().is_some_and(|ext| ext == "rhai") {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    handlebars
+                        .register_script_helper_file(name, &path)
+                        .map_err(|e| {
+                            Error::ScriptError(path.clone(), e.to_string())
+                        })?;
+                }
+            }
+        }
+
+        Ok(Self::from_registry(handlebars, self.cache))
+    }
 }
 
 impl<'hb> TemplateRenderPlugin for HandlebarsPlugin<'hb> {
@@ -28,22 +248,351 @@ impl<'hb> TemplateRenderPlugin for HandlebarsPlugin<'hb> {
         context: TemplateRenderContext<'c>,
     ) -> Box<dyn Renderer + 'c> {
         Box::new(HandlebarsRenderer {
-            handlebars: &self.handlebars,
+            plugin: self,
             ctx: context,
+            with_partials: OnceCell::new(),
         })
     }
 }
 
-struct HandlebarsRenderer<'hb> {
-    handlebars: &'hb Handlebars<'hb>,
-    ctx: TemplateRenderContext<'hb>,
+/// The plugin registries with the partials of a render context registered.
+struct PartialRegistries<'hb> {
+    handlebars: Handlebars<'hb>,
+    plain: Handlebars<'hb>,
+}
+
+struct HandlebarsRenderer<'c, 'hb> {
+    plugin: &'c HandlebarsPlugin<'hb>,
+    ctx: TemplateRenderContext<'c>,
+    /// Looked up on the first render, so the parts of a template share them.
+    with_partials: OnceCell<Arc<PartialRegistries<'hb>>>,
+}
+
+impl<'hb> HandlebarsRenderer<'_, 'hb> {
+    /// The registries to render with, with the context partials registered
+    /// when it has any.
+    fn registries(&self) -> Result<Option<&PartialRegistries<'hb>>, Error> {
+        let Some(partials) = self.ctx.partials().filter(|p| !p.is_empty())
+        else {
+            return Ok(None);
+        };
+        if let Some(registries) = self.with_partials.get() {
+            return Ok(Some(registries));
+        }
+
+        let fingerprint = partials.fingerprint();
+        let registries = match self
+            .plugin
+            .with_partials
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.get(&fingerprint).cloned())
+        {
+            Some(registries) => registries,
+            None => {
+                // Partials belong to a business unit, so they are registered
+                // on a copy of the shared registry rather than on the
+                // registry itself.
+                let mut handlebars = self.plugin.handlebars.as_ref().clone();
+                for (name, source) in partials.iter() {
+                    handlebars.register_partial(name, source).map_err(|e| {
+                        Error::from(handlebars::RenderError::from(e))
+                    })?;
+                }
+                let mut plain = handlebars.clone();
+                plain.register_escape_fn(no_escape);
+
+                let registries =
+                    Arc::new(PartialRegistries { handlebars, plain });
+                if let Ok(mut cache) = self.plugin.with_partials.lock() {
+                    cache.put(fingerprint, registries.clone());
+                }
+                registries
+            }
+        };
+
+        Ok(Some(self.with_partials.get_or_init(|| registries)))
+    }
+
+    /// The parsed template, from the cache when the part is known and the
+    /// context tells which template and version it belongs to.
+    fn parse(
+        &self,
+        part: Option<&'static str>,
+        source: &str,
+    ) -> Result<Arc<Template>, Error> {
+        let compile = || {
+            Template::compile(source)
+                .map(Arc::new)
+                .map_err(|e| Error::from(handlebars::RenderError::from(e)))
+        };
+        let (Some(cache), Some(part), Some((template_id, version))) =
+            (&self.plugin.cache, part, self.ctx.template())
+        else {
+            return compile();
+        };
+
+        let key = CacheKey {
+            template_id: template_id.clone(),
+            version,
+            part,
+        };
+        if let Some(parsed) = cache
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.get(&key, source))
+        {
+            return Ok(parsed);
+        }
+
+        let parsed = compile()?;
+        if let Ok(mut cache) = cache.lock() {
+            cache.put(key, source, parsed.clone());
+        }
+        Ok(parsed)
+    }
+
+    fn render_with(
+        &self,
+        part: Option<&'static str>,
+        template: &str,
+        escape: Escape,
+    ) -> Result<String, RenderError> {
+        let parsed = self.parse(part, template)?;
+        let registry = match (self.registries()?, escape) {
+            (Some(registries), Escape::Html) => &registries.handlebars,
+            (Some(registries), Escape::Plain) => &registries.plain,
+            (None, Escape::Html) => self.plugin.handlebars.as_ref(),
+            (None, Escape::Plain) => self.plugin.plain.as_ref(),
+        };
+
+        Ok(self.render_parsed(registry, &parsed).map_err(Error::from)?)
+    }
+
+    fn render_parsed(
+        &self,
+        registry: &Handlebars<'_>,
+        template: &Template,
+    ) -> Result<String, handlebars::RenderError> {
+        let ctx = Context::wraps(&self.ctx)?;
+        let mut output = StringOutput::new();
+        template.render(
+            registry,
+            &ctx,
+            &mut RenderContext::new(None),
+            &mut output,
+        )?;
+        output.into_string().map_err(handlebars::RenderError::from)
+    }
+}
+
+impl Renderer for HandlebarsRenderer<'_, '_> {
+    fn render(
+        &self,
+        template: &str,
+        escape: Escape,
+    ) -> Result<String, RenderError> {
+        self.render_with(None, template, escape)
+    }
+
+    fn render_part(
+        &self,
+        part: &'static str,
+        template: &str,
+        escape: Escape,
+    ) -> Result<String, RenderError> {
+        self.render_with(Some(part), template, escape)
+            .map_err(|e| e.in_part(part))
+    }
 }
 
-impl<'hb> Renderer for HandlebarsRenderer<'hb> {
-    fn render(&self, template: &str) -> Result<String, RenderError> {
-        Ok(self
-            .handlebars
-            .render_template(template, &self.ctx)
-            .map_err(Error::from)?)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partial::Partials;
+    use perroute_commons::types::{vars::Vars, Payload};
+    use serde_json::json;
+
+    #[test]
+    fn reports_the_position_of_syntax_errors() {
+        let plugin = HandlebarsPlugin::new();
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let renderer =
+            plugin.renderer(TemplateRenderContext::new(&payload, &vars));
+
+        assert_eq!(
+            renderer
+                .render("Hello {{payload.name}}", Escape::Plain)
+                .unwrap(),
+            "Hello John"
+        );
+
+        let error = renderer
+            .render("Hello\n  {{#if payload.name}}", Escape::Plain)
+            .unwrap_err();
+        assert_eq!(error.position().map(|(line, _)| line), Some(2));
+    }
+
+    #[test]
+    fn escapes_html_parts_only() {
+        let plugin = HandlebarsPlugin::new();
+        let payload = Payload::new(json!({"name": "Tom & \"Jerry\""}));
+        let vars = Vars::default();
+        let renderer =
+            plugin.renderer(TemplateRenderContext::new(&payload, &vars));
+
+        assert_eq!(
+            renderer.render("{{payload.name}}", Escape::Html).unwrap(),
+            "Tom &amp; &quot;Jerry&quot;"
+        );
+        assert_eq!(
+            renderer.render("{{payload.name}}", Escape::Plain).unwrap(),
+            "Tom & \"Jerry\""
+        );
+    }
+
+    #[test]
+    fn caches_parsed_templates_per_version() {
+        let plugin = HandlebarsPlugin::new().with_cache_capacity(2);
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let template_id = Id::from("welcome");
+        let cached = || {
+            let cache = plugin.cache.as_ref().unwrap().lock().unwrap();
+            (cache.parsed.len(), cache.versions.len())
+        };
+
+        let renderer = plugin.renderer(
+            TemplateRenderContext::new(&payload, &vars)
+                .with_template(&template_id, Some(1)),
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                renderer
+                    .render_part("body", "Hi {{payload.name}}", Escape::Plain)
+                    .unwrap(),
+                "Hi John"
+            );
+        }
+        renderer.render_part("title", "Bye", Escape::Plain).unwrap();
+        renderer.render("Not cached", Escape::Plain).unwrap();
+        assert_eq!(cached(), (2, 1));
+
+        let renderer = plugin.renderer(
+            TemplateRenderContext::new(&payload, &vars)
+                .with_template(&template_id, Some(2)),
+        );
+        assert_eq!(
+            renderer
+                .render_part("body", "Hello {{payload.name}}", Escape::Html)
+                .unwrap(),
+            "Hello John"
+        );
+        assert_eq!(cached(), (1, 1));
+    }
+
+    #[test]
+    fn parses_changed_parts_of_unversioned_templates_again() {
+        let plugin = HandlebarsPlugin::new();
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let template_id = Id::from("welcome");
+        let renderer = plugin.renderer(
+            TemplateRenderContext::new(&payload, &vars)
+                .with_template(&template_id, None),
+        );
+
+        assert_eq!(
+            renderer
+                .render_part("body", "Hi {{payload.name}}", Escape::Plain)
+                .unwrap(),
+            "Hi John"
+        );
+        assert_eq!(
+            renderer
+                .render_part("body", "Bye {{payload.name}}", Escape::Plain)
+                .unwrap(),
+            "Bye John"
+        );
+    }
+
+    #[test]
+    fn renders_partials_and_layouts() {
+        let plugin = HandlebarsPlugin::new();
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let partials = Partials::from_iter([
+            ("footer", "Bye {{payload.name}}"),
+            ("layout", "<main>{{> @partial-block}}</main>"),
+        ]);
+        let renderer = plugin.renderer(
+            TemplateRenderContext::new(&payload, &vars)
+                .with_partials(&partials),
+        );
+
+        assert_eq!(
+            renderer
+                .render("Hi {{payload.name}}. {{> footer}}", Escape::Plain)
+                .unwrap(),
+            "Hi John. Bye John"
+        );
+        assert_eq!(
+            renderer
+                .render(
+                    "{{#> layout}}Hi {{payload.name}}{{/layout}}",
+                    Escape::Html
+                )
+                .unwrap(),
+            "<main>Hi John</main>"
+        );
+    }
+
+    #[test]
+    fn keeps_registries_until_partials_change() {
+        let plugin = HandlebarsPlugin::new();
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let render = |partials: &Partials| {
+            plugin
+                .renderer(
+                    TemplateRenderContext::new(&payload, &vars)
+                        .with_partials(partials),
+                )
+                .render("{{> footer}}", Escape::Plain)
+                .unwrap()
+        };
+        let kept = || plugin.with_partials.lock().unwrap().len();
+
+        let partials = Partials::from_iter([("footer", "Bye")]);
+        assert_eq!(render(&partials), "Bye");
+        assert_eq!(render(&partials.clone()), "Bye");
+        assert_eq!(kept(), 1);
+
+        let changed = Partials::from_iter([("footer", "Bye {{payload.name}}")]);
+        assert_eq!(render(&changed), "Bye John");
+        assert_eq!(kept(), 2);
+    }
+
+    #[cfg(feature = "script_helpers")]
+    #[test]
+    fn registers_script_helpers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("shout.rhai"), "params[0] + \"!\"")
+            .unwrap();
+        let plugin = HandlebarsPlugin::new()
+            .with_script_helpers(dir.path())
+            .unwrap();
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let renderer =
+            plugin.renderer(TemplateRenderContext::new(&payload, &vars));
+
+        assert_eq!(
+            renderer
+                .render("{{shout payload.name}}", Escape::Plain)
+                .unwrap(),
+            "John!"
+        );
     }
 }
@@ -0,0 +1,361 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, RenderContext, RenderError,
+    RenderErrorReason, ScopedJson,
+};
+use serde_json::Value;
+use std::fmt::Write;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const DEFAULT_LOCALE: &str = "en-US";
+const DEFAULT_TRUNCATE_SUFFIX: &str = "…";
+
+type HelperFn = fn(&Helper) -> Result<Value, RenderError>;
+
+/// Helpers computing a value, so they can be used both as `{{upper name}}`
+/// and as subexpressions like `{{upper (default name "there")}}`.
+struct ValueHelper(HelperFn);
+
+impl HelperDef for ValueHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        (self.0)(h).map(ScopedJson::Derived)
+    }
+}
+
+/// Registers the built-in helpers:
+///
+/// - `format_date value [format] [tz=]`: RFC 3339 strings, naive date times
+///   (taken as UTC), dates and unix timestamps, formatted with `strftime`
+///   specifiers in the given IANA timezone.
+/// - `format_number value [decimals=] [locale=]`
+/// - `format_currency value code [decimals=] [locale=]`
+/// - `pluralize count singular [plural]`
+/// - `default value fallback`
+/// - `truncate value length [suffix=]`
+/// - `upper value`, `lower value`
+/// - `url_encode value`
+pub fn register(handlebars: &mut Handlebars) {
+    let helpers: [(&str, HelperFn); 9] = [
+        ("format_date", format_date),
+        ("format_number", format_number),
+        ("format_currency", format_currency),
+        ("pluralize", pluralize),
+        ("default", default),
+        ("truncate", truncate),
+        ("upper", upper),
+        ("lower", lower),
+        ("url_encode", url_encode),
+    ];
+
+    for (name, helper) in helpers {
+        handlebars.register_helper(name, Box::new(ValueHelper(helper)));
+    }
+}
+
+fn invalid(helper: &str, message: impl std::fmt::Display) -> RenderError {
+    RenderErrorReason::Other(format!("{helper}: {message}")).into()
+}
+
+fn param<'a>(
+    h: &'a Helper,
+    helper: &'static str,
+    index: usize,
+) -> Result<&'a Value, RenderError> {
+    h.param(index).map(|param| param.value()).ok_or_else(|| {
+        RenderErrorReason::ParamNotFoundForIndex(helper, index).into()
+    })
+}
+
+fn hash_str<'a>(h: &'a Helper, key: &str) -> Option<&'a str> {
+    h.hash_get(key).and_then(|value| value.value().as_str())
+}
+
+fn hash_u64(h: &Helper, key: &str) -> Option<u64> {
+    h.hash_get(key).and_then(|value| value.value().as_u64())
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_date(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Number(n) => DateTime::from_timestamp(n.as_i64()?, 0),
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|date| date.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                    .iter()
+                    .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+                    .map(|date| date.and_utc())
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|date| date.and_utc())
+            }),
+        _ => None,
+    }
+}
+
+fn format_date(h: &Helper) -> Result<Value, RenderError> {
+    let value = param(h, "format_date", 0)?;
+    let date = parse_date(value).ok_or_else(|| {
+        invalid("format_date", format!("invalid date {value}"))
+    })?;
+    let format = h
+        .param(1)
+        .and_then(|param| param.value().as_str())
+        .unwrap_or(DEFAULT_DATE_FORMAT);
+    let tz: Tz = hash_str(h, "tz")
+        .unwrap_or("UTC")
+        .parse()
+        .map_err(|e| invalid("format_date", e))?;
+
+    // Invalid specifiers surface as a formatting error instead of a panic.
+    let mut formatted = String::new();
+    write!(formatted, "{}", date.with_timezone(&tz).format(format)).map_err(
+        |_| invalid("format_date", format!("invalid format {format}")),
+    )?;
+
+    Ok(Value::String(formatted))
+}
+
+/// Thousands and decimal separators of a locale, by language and region.
+fn separators(locale: &str) -> (&'static str, &'static str) {
+    let mut parts = locale.split(['-', '_']);
+    let language = parts.next().unwrap_or_default().to_lowercase();
+    let region = parts.next().unwrap_or_default().to_uppercase();
+
+    match (language.as_str(), region.as_str()) {
+        ("de" | "it" | "fr", "CH") => ("'", "."),
+        ("es", "MX") => (",", "."),
+        ("pt" | "de" | "es" | "it" | "nl" | "id" | "tr" | "da", _) => {
+            (".", ",")
+        }
+        ("fr" | "pl" | "cs" | "sv" | "nb" | "fi" | "ru" | "uk", _) => {
+            ("\u{a0}", ",")
+        }
+        _ => (",", "."),
+    }
+}
+
+fn group(number: f64, decimals: usize, locale: &str) -> String {
+    let (thousands, decimal) = separators(locale);
+    let formatted = format!("{:.*}", decimals, number.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push_str(thousands);
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push_str(decimal);
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+fn format_number(h: &Helper) -> Result<Value, RenderError> {
+    let value = param(h, "format_number", 0)?;
+    let number = as_number(value).ok_or_else(|| {
+        invalid("format_number", format!("invalid number {value}"))
+    })?;
+    let decimals = hash_u64(h, "decimals").unwrap_or(0) as usize;
+    let locale = hash_str(h, "locale").unwrap_or(DEFAULT_LOCALE);
+
+    let sign = if number < 0.0 { "-" } else { "" };
+    Ok(Value::String(format!(
+        "{sign}{}",
+        group(number, decimals, locale)
+    )))
+}
+
+fn currency_symbol(code: &str) -> &str {
+    match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "BRL" => "R$",
+        "GBP" => "£",
+        "JPY" => "¥",
+        "INR" => "₹",
+        other => other,
+    }
+}
+
+fn format_currency(h: &Helper) -> Result<Value, RenderError> {
+    let value = param(h, "format_currency", 0)?;
+    let number = as_number(value).ok_or_else(|| {
+        invalid("format_currency", format!("invalid amount {value}"))
+    })?;
+    let code = param(h, "format_currency", 1)?
+        .as_str()
+        .ok_or_else(|| invalid("format_currency", "currency code expected"))?
+        .to_uppercase();
+    let default_decimals = match code.as_str() {
+        "JPY" | "KRW" | "CLP" => 0,
+        _ => 2,
+    };
+    let decimals =
+        hash_u64(h, "decimals").map_or(default_decimals, |d| d as usize);
+    let locale = hash_str(h, "locale").unwrap_or(DEFAULT_LOCALE);
+
+    let sign = if number < 0.0 { "-" } else { "" };
+    let amount = group(number, decimals, locale);
+    let symbol = currency_symbol(&code);
+    let formatted = match locale.split(['-', '_']).next().unwrap_or_default() {
+        "en" | "ja" | "zh" | "ko" => format!("{sign}{symbol}{amount}"),
+        "pt" => format!("{sign}{symbol}\u{a0}{amount}"),
+        _ => format!("{sign}{amount}\u{a0}{symbol}"),
+    };
+
+    Ok(Value::String(formatted))
+}
+
+fn pluralize(h: &Helper) -> Result<Value, RenderError> {
+    let count = as_number(param(h, "pluralize", 0)?)
+        .ok_or_else(|| invalid("pluralize", "count must be a number"))?;
+    let singular = as_text(param(h, "pluralize", 1)?);
+    let word = if count == 1.0 {
+        singular
+    } else {
+        h.param(2)
+            .map(|plural| as_text(plural.value()))
+            .unwrap_or_else(|| format!("{singular}s"))
+    };
+
+    Ok(Value::String(word))
+}
+
+fn default(h: &Helper) -> Result<Value, RenderError> {
+    let value = param(h, "default", 0)?;
+    let fallback = param(h, "default", 1)?;
+    let missing = match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        _ => false,
+    };
+
+    Ok(if missing { fallback } else { value }.clone())
+}
+
+fn truncate(h: &Helper) -> Result<Value, RenderError> {
+    let text = as_text(param(h, "truncate", 0)?);
+    let length = param(h, "truncate", 1)?
+        .as_u64()
+        .ok_or_else(|| invalid("truncate", "length must be a number"))?
+        as usize;
+    if text.chars().count() <= length {
+        return Ok(Value::String(text));
+    }
+
+    let suffix = hash_str(h, "suffix").unwrap_or(DEFAULT_TRUNCATE_SUFFIX);
+    let kept = length.saturating_sub(suffix.chars().count());
+    let mut truncated: String = text.chars().take(kept).collect();
+    truncated.push_str(suffix);
+    Ok(Value::String(truncated))
+}
+
+fn upper(h: &Helper) -> Result<Value, RenderError> {
+    Ok(Value::String(as_text(param(h, "upper", 0)?).to_uppercase()))
+}
+
+fn lower(h: &Helper) -> Result<Value, RenderError> {
+    Ok(Value::String(as_text(param(h, "lower", 0)?).to_lowercase()))
+}
+
+fn url_encode(h: &Helper) -> Result<Value, RenderError> {
+    let text = as_text(param(h, "url_encode", 0)?);
+    Ok(Value::String(urlencoding::encode(&text).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, data: Value) -> String {
+        let mut handlebars = Handlebars::new();
+        register(&mut handlebars);
+        handlebars.render_template(template, &data).unwrap()
+    }
+
+    #[test]
+    fn formats_dates_in_a_timezone() {
+        let data = json!({"at": "2025-03-01T12:30:00Z"});
+        assert_eq!(
+            render(
+                r#"{{format_date at "%d/%m/%Y %H:%M" tz="America/Sao_Paulo"}}"#,
+                data.clone()
+            ),
+            "01/03/2025 09:30"
+        );
+        assert_eq!(render("{{format_date at}}", data), "2025-03-01 12:30:00");
+    }
+
+    #[test]
+    fn formats_numbers_and_currencies_by_locale() {
+        let data = json!({"amount": 1234567.891});
+        assert_eq!(
+            render("{{format_number amount decimals=2}}", data.clone()),
+            "1,234,567.89"
+        );
+        assert_eq!(
+            render(r#"{{format_currency amount "usd"}}"#, data.clone()),
+            "$1,234,567.89"
+        );
+        assert_eq!(
+            render(
+                r#"{{format_currency amount "BRL" locale="pt-BR"}}"#,
+                data.clone()
+            ),
+            "R$\u{a0}1.234.567,89"
+        );
+        assert_eq!(
+            render(r#"{{format_currency amount "EUR" locale="de-DE"}}"#, data),
+            "1.234.567,89\u{a0}€"
+        );
+    }
+
+    #[test]
+    fn text_helpers() {
+        let data = json!({"count": 2, "name": "", "text": "Hello world"});
+        assert_eq!(
+            render(r#"{{count}} {{pluralize count "item"}}"#, data.clone()),
+            "2 items"
+        );
+        assert_eq!(
+            render(r#"{{upper (default name "there")}}"#, data.clone()),
+            "THERE"
+        );
+        assert_eq!(render("{{truncate text 8}}", data.clone()), "Hello w…");
+        assert_eq!(render(r#"{{url_encode "a b&c"}}"#, data), "a%20b%26c");
+    }
+}
@@ -0,0 +1,199 @@
+use super::{
+    Escape, RenderError, Renderer, TemplateRenderContext, TemplateRenderPlugin,
+};
+use minijinja::{AutoEscape, Environment};
+use std::{cell::OnceCell, sync::Arc};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    RenderError(#[from] minijinja::Error),
+}
+
+impl Error {
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            Error::RenderError(e) => {
+                let column = e.range().zip(e.template_source()).map(
+                    |(range, source)| {
+                        let before = &source[..range.start];
+                        before.len() - before.rfind('\n').map_or(0, |i| i + 1)
+                            + 1
+                    },
+                );
+                e.line().zip(column)
+            }
+        }
+    }
+}
+
+/// Renders Jinja-style templates with MiniJinja and its built-in filters.
+/// Partials are available to `{% include %}` and `{% extends %}` by name.
+#[derive(Clone)]
+pub struct MiniJinjaPlugin {
+    /// Environment for HTML parts, escaping the values written.
+    html: Arc<Environment<'static>>,
+    /// Environment writing values as they are, for plain-text parts.
+    plain: Arc<Environment<'static>>,
+}
+
+impl Default for MiniJinjaPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MiniJinjaPlugin {
+    pub fn new() -> Self {
+        Self::default_environment(cfg!(debug_assertions))
+    }
+
+    /// Keeps the template source and context in errors raised while
+    /// rendering, at the cost of slower rendering. Syntax errors are
+    /// positioned either way. On by default in debug builds only.
+    pub fn with_debug(self, debug: bool) -> Self {
+        Self::default_environment(debug)
+    }
+
+    fn default_environment(debug: bool) -> Self {
+        let mut plain = Environment::new();
+        plain.set_debug(debug);
+        plain.set_auto_escape_callback(|_| AutoEscape::None);
+        let mut html = plain.clone();
+        html.set_auto_escape_callback(|_| AutoEscape::Html);
+        MiniJinjaPlugin {
+            html: Arc::new(html),
+            plain: Arc::new(plain),
+        }
+    }
+}
+
+impl TemplateRenderPlugin for MiniJinjaPlugin {
+    fn renderer<'c>(
+        &'c self,
+        context: TemplateRenderContext<'c>,
+    ) -> Box<dyn Renderer + 'c> {
+        Box::new(MiniJinjaRenderer {
+            plugin: self,
+            ctx: context,
+            with_partials: OnceCell::new(),
+        })
+    }
+}
+
+/// The plugin environments with the partials of a render context added.
+struct PartialEnvironments {
+    html: Environment<'static>,
+    plain: Environment<'static>,
+}
+
+struct MiniJinjaRenderer<'c> {
+    plugin: &'c MiniJinjaPlugin,
+    ctx: TemplateRenderContext<'c>,
+    /// Built on the first render, so the parts of a template share them.
+    with_partials: OnceCell<PartialEnvironments>,
+}
+
+impl MiniJinjaRenderer<'_> {
+    /// The environments to render with, with the context partials added
+    /// when it has any.
+    fn environments(&self) -> Result<Option<&PartialEnvironments>, Error> {
+        let Some(partials) = self.ctx.partials().filter(|p| !p.is_empty())
+        else {
+            return Ok(None);
+        };
+        if let Some(environments) = self.with_partials.get() {
+            return Ok(Some(environments));
+        }
+
+        let mut plain = self.plugin.plain.as_ref().clone();
+        for (name, source) in partials.iter() {
+            plain.add_template_owned(name.to_string(), source.to_string())?;
+        }
+        let mut html = plain.clone();
+        html.set_auto_escape_callback(|_| AutoEscape::Html);
+
+        Ok(Some(
+            self.with_partials
+                .get_or_init(|| PartialEnvironments { html, plain }),
+        ))
+    }
+}
+
+impl Renderer for MiniJinjaRenderer<'_> {
+    fn render(
+        &self,
+        template: &str,
+        escape: Escape,
+    ) -> Result<String, RenderError> {
+        let env = match (self.environments()?, escape) {
+            (Some(environments), Escape::Html) => &environments.html,
+            (Some(environments), Escape::Plain) => &environments.plain,
+            (None, Escape::Html) => self.plugin.html.as_ref(),
+            (None, Escape::Plain) => self.plugin.plain.as_ref(),
+        };
+
+        Ok(env.render_str(template, &self.ctx).map_err(Error::from)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partial::Partials;
+    use perroute_commons::types::{vars::Vars, Payload};
+    use serde_json::json;
+
+    #[test]
+    fn renders_jinja_templates_with_partials() {
+        let plugin = MiniJinjaPlugin::new();
+        let payload = Payload::new(json!({"name": "John", "items": [1, 2]}));
+        let vars = Vars::default();
+        let partials = Partials::from_iter([
+            ("footer", "Bye {{ payload.name }}"),
+            ("layout", "<main>{% block content %}{% endblock %}</main>"),
+        ]);
+        let renderer = plugin.renderer(
+            TemplateRenderContext::new(&payload, &vars)
+                .with_partials(&partials),
+        );
+
+        assert_eq!(
+            renderer
+                .render(
+                    "{{ payload.name | upper }} has {{ payload.items | length }}. {% include 'footer' %}",
+                    Escape::Plain
+                )
+                .unwrap(),
+            "JOHN has 2. Bye John"
+        );
+        assert_eq!(
+            renderer
+                .render(
+                    "{% extends 'layout' %}{% block content %}Hi {{ payload.name }}{% endblock %}",
+                    Escape::Html
+                )
+                .unwrap(),
+            "<main>Hi John</main>"
+        );
+
+        let error = renderer
+            .render("Hi\n{% if payload.name %}", Escape::Plain)
+            .unwrap_err();
+        assert_eq!(error.position().map(|(line, _)| line), Some(2));
+    }
+
+    #[test]
+    fn positions_syntax_errors_without_debug_info() {
+        let plugin = MiniJinjaPlugin::new().with_debug(false);
+        let payload = Payload::new(json!({"name": "John"}));
+        let vars = Vars::default();
+        let renderer =
+            plugin.renderer(TemplateRenderContext::new(&payload, &vars));
+
+        let error = renderer
+            .render("Hi\n  {% if payload.name %}", Escape::Plain)
+            .unwrap_err();
+        assert_eq!(error.position().map(|(line, _)| line), Some(2));
+    }
+}
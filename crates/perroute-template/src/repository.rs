@@ -1,16 +1,107 @@
 #[cfg(feature = "repo_aws_s3")]
 pub mod aws_s3;
+#[cfg(any(feature = "repo_aws_s3", feature = "repo_postgres"))]
+mod cache;
+#[cfg(feature = "repo_fs")]
+pub mod fs;
+#[cfg(feature = "repo_postgres")]
+pub mod postgres;
 
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
-use perroute_commons::types::{dispatch_type::DispatchType, id::Id};
-use crate::template::{NotRenderedTemplateState, Template};
+use perroute_commons::types::{actor::Actor, dispatch_type::DispatchType, id::Id};
+use crate::{
+    partial::Partials,
+    template::{NotRenderedTemplateState, Template},
+    version::TemplateVersion,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum TemplateRepositoryError {
     #[cfg(feature = "repo_aws_s3")]
-    #[error("")]
-    AwsS3TemplateRepositoryError(#[from] crate::repository::aws_s3::Error),
+    #[error("{0}")]
+    AwsS3TemplateRepositoryError(#[from] Box<crate::repository::aws_s3::Error>),
+
+    #[cfg(feature = "repo_fs")]
+    #[error("{0}")]
+    FsTemplateRepositoryError(#[from] crate::repository::fs::Error),
+
+    #[cfg(feature = "repo_postgres")]
+    #[error("{0}")]
+    PgTemplateRepositoryError(
+        #[from] perroute_storage::active_record::ActiveRecordError,
+    ),
+
+    #[error("Partial {0} not found")]
+    PartialNotFound(String),
+
+    #[error("Invalid partial name: {0}")]
+    InvalidPartialName(String),
+
+    #[error("Invalid template content: {0}")]
+    InvalidContent(#[from] serde_json::Error),
+}
+
+#[cfg(feature = "repo_aws_s3")]
+impl From<crate::repository::aws_s3::Error> for TemplateRepositoryError {
+    fn from(value: crate::repository::aws_s3::Error) -> Self {
+        Box::new(value).into()
+    }
+}
+
+/// The store templates are delivered from, as configured. A template
+/// directory takes over the template storage.
+#[cfg(all(
+    feature = "repo_aws_s3",
+    feature = "repo_fs",
+    feature = "repo_postgres"
+))]
+pub async fn configured(
+    settings: &perroute_commons::configuration::settings::Settings,
+    datasource: &perroute_storage::active_record::datasource::DataSource<
+        perroute_storage::active_record::datasource::NonTransactionalDataSource,
+    >,
+) -> Result<Arc<dyn TemplateLookup + Send + Sync>, &'static str> {
+    use perroute_commons::configuration::settings::TemplateStorageBackend;
+
+    let storage = match (
+        settings.template_directory.as_ref(),
+        settings.template_storage.as_ref(),
+    ) {
+        (Some(directory), _) => {
+            return Ok(Arc::new(fs::FsTemplateRepository::new(&directory.path)))
+        }
+        (None, Some(storage)) => storage,
+        (None, None) => return Err("Missing template storage settings"),
+    };
+
+    let ttl = storage
+        .cache_ttl_seconds
+        .map(std::time::Duration::from_secs);
+    match storage.backend {
+        TemplateStorageBackend::S3 => {
+            let bucket_name = storage
+                .bucket_name
+                .as_ref()
+                .ok_or("Missing template storage bucket name")?;
+            let repository = aws_s3::AwsS3TemplateRepository::new(
+                &aws_config::load_from_env().await,
+                bucket_name,
+            );
+            Ok(Arc::new(match ttl {
+                Some(ttl) => repository.with_ttl(ttl),
+                None => repository,
+            }))
+        }
+        TemplateStorageBackend::Postgres => {
+            let repository =
+                postgres::PgTemplateRepository::new(datasource.clone());
+            Ok(Arc::new(match ttl {
+                Some(ttl) => repository.with_ttl(ttl),
+                None => repository,
+            }))
+        }
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -46,12 +137,86 @@ impl<'i> TemplateId<'i> {
     }
 }
 
+/// A template found by a [`TemplateLookup`], along with the version it was
+/// read from when the store keeps versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundTemplate {
+    template: Template<NotRenderedTemplateState>,
+    version: Option<i32>,
+}
+
+impl FoundTemplate {
+    pub fn new(template: Template<NotRenderedTemplateState>) -> Self {
+        Self {
+            template,
+            version: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: i32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn template(&self) -> &Template<NotRenderedTemplateState> {
+        &self.template
+    }
+
+    pub fn version(&self) -> Option<i32> {
+        self.version
+    }
+}
+
 #[cfg(feature = "test-mocks")]
 use mockall::automock;
 
+/// Read side of the versions of a template, and the partials of a business
+/// unit. Versions are immutable: changing a template creates a new draft,
+/// which goes live once published.
 #[cfg_attr(feature = "test-mocks", automock)]
 #[async_trait::async_trait]
-pub trait TemplateRepository {}
+pub trait TemplateRepository {
+    /// All versions of the template, latest first.
+    async fn versions(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> Result<Vec<TemplateVersion>, TemplateRepositoryError>;
+
+    async fn version(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+        version: i32,
+    ) -> Result<Option<TemplateVersion>, TemplateRepositoryError>;
+
+    async fn published(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> Result<Option<TemplateVersion>, TemplateRepositoryError>;
+
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError>;
+
+    /// Creates or replaces a partial. Templates pick up the change on their
+    /// next render.
+    async fn save_partial(
+        &self,
+        business_unit_id: &Id,
+        name: &str,
+        content: &str,
+        actor: &Actor,
+    ) -> Result<(), TemplateRepositoryError>;
+
+    async fn delete_partial(
+        &self,
+        business_unit_id: &Id,
+        name: &str,
+    ) -> Result<(), TemplateRepositoryError>;
+}
 
 #[cfg_attr(feature = "test-mocks", automock)]
 #[async_trait::async_trait]
@@ -59,8 +224,29 @@ pub trait TemplateLookup {
     async fn get<'a>(
         &self,
         id: &'a TemplateId<'a>,
-    ) -> Result<
-        Option<Template<NotRenderedTemplateState>>,
-        TemplateRepositoryError,
-    >;
+    ) -> Result<Option<FoundTemplate>, TemplateRepositoryError>;
+
+    /// Partials and layouts available to the templates of the business
+    /// unit.
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError>;
+}
+
+#[async_trait::async_trait]
+impl<T: TemplateLookup + Send + Sync + ?Sized> TemplateLookup for Arc<T> {
+    async fn get<'a>(
+        &self,
+        id: &'a TemplateId<'a>,
+    ) -> Result<Option<FoundTemplate>, TemplateRepositoryError> {
+        self.as_ref().get(id).await
+    }
+
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError> {
+        self.as_ref().partials(business_unit_id).await
+    }
 }
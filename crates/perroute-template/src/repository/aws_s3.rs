@@ -1,28 +1,67 @@
 use aws_config::SdkConfig;
 use aws_sdk_s3::{
-    config::http::HttpResponse, error::SdkError,
-    operation::get_object::GetObjectError,
+    config::http::HttpResponse,
+    error::SdkError,
+    operation::{
+        get_object::GetObjectError, list_objects_v2::ListObjectsV2Error,
+    },
+    primitives::ByteStreamError,
+};
+use perroute_commons::types::{dispatch_type::DispatchType, id::Id};
+use crate::{
+    partial::Partials,
+    template::{NotRenderedTemplateState, Template},
+};
+use super::{
+    cache::{cached, store, Cached},
+    FoundTemplate, TemplateId, TemplateLookup, TemplateRepositoryError,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
 };
-use perroute_commons::types::dispatch_type::DispatchType;
-use crate::template::{NotRenderedTemplateState, Template};
-use super::{TemplateId, TemplateLookup, TemplateRepositoryError};
+
+/// How long fetched templates and partials are used before being read again
+/// from the bucket.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("")]
+    #[error("Failed to get template: {0}")]
     SdkError(#[from] SdkError<GetObjectError, HttpResponse>),
 
-    #[error("")]
+    #[error("Invalid template content: {0}")]
     Serde(#[from] serde_json::Error),
 
-    #[error("")]
+    #[error("The template is not of the requested dispatch type")]
     InvalidTemplate,
+
+    #[error("Failed to read template {0}: {1}")]
+    TemplateBody(String, ByteStreamError),
+
+    #[error("Failed to list partials: {0}")]
+    ListError(#[from] SdkError<ListObjectsV2Error, HttpResponse>),
+
+    #[error("Failed to read partial {0}: {1}")]
+    PartialBody(String, ByteStreamError),
+
+    #[error("Partial {0} is not valid UTF-8")]
+    InvalidPartial(String),
 }
 
+/// Reads templates and partials from a bucket. Both are cached for a TTL,
+/// so changes in the bucket are picked up once it expires rather than
+/// fetched on every message.
 #[derive(Clone)]
 pub struct AwsS3TemplateRepository {
     client: aws_sdk_s3::Client,
     bucket: String,
+    ttl: Duration,
+    templates: Arc<
+        RwLock<HashMap<String, Cached<Template<NotRenderedTemplateState>>>>,
+    >,
+    partials: Arc<RwLock<HashMap<Id, Cached<Partials>>>>,
 }
 
 impl AwsS3TemplateRepository {
@@ -30,8 +69,18 @@ impl AwsS3TemplateRepository {
         Self {
             client: aws_sdk_s3::Client::new(cfg),
             bucket: bucket.to_string(),
+            ttl: DEFAULT_TTL,
+            templates: Default::default(),
+            partials: Default::default(),
         }
     }
+
+    /// Caches fetched templates and partials for `ttl` instead of
+    /// [`DEFAULT_TTL`]. A zero TTL disables caching.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -39,22 +88,29 @@ impl TemplateLookup for AwsS3TemplateRepository {
     async fn get<'a>(
         &self,
         id: &'a TemplateId<'a>,
-    ) -> Result<
-        Option<Template<NotRenderedTemplateState>>,
-        TemplateRepositoryError,
-    > {
+    ) -> Result<Option<FoundTemplate>, TemplateRepositoryError> {
+        let key = to_key(id);
+        if let Some(template) = cached(&self.templates, &key, self.ttl) {
+            return Ok(Some(FoundTemplate::new(template)));
+        }
+
         let response = self
             .client
             .get_object()
             .bucket(&self.bucket)
-            .key(to_key(&id))
+            .key(&key)
             .send()
             .await
             .map_err(Error::from)?;
 
-        let bytes = response.body().bytes().unwrap();
+        let bytes = response
+            .body
+            .collect()
+            .await
+            .map_err(|e| Error::TemplateBody(key.clone(), e))?
+            .into_bytes();
         let template: Template<NotRenderedTemplateState> =
-            serde_json::from_reader(bytes).map_err(Error::from)?;
+            serde_json::from_slice(&bytes).map_err(Error::from)?;
 
         let expected = match (id.dispatch_type(), &template) {
             (DispatchType::Email, Template::Email(_)) => true,
@@ -63,10 +119,70 @@ impl TemplateLookup for AwsS3TemplateRepository {
             _ => false,
         };
 
-        match expected {
-            true => Ok(Some(template)),
-            false => Err(Error::InvalidTemplate.into()),
+        if !expected {
+            return Err(Error::InvalidTemplate.into());
+        }
+
+        store(&self.templates, key, &template);
+        Ok(Some(FoundTemplate::new(template)))
+    }
+
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError> {
+        if let Some(partials) =
+            cached(&self.partials, business_unit_id, self.ttl)
+        {
+            return Ok(partials);
         }
+
+        let prefix = format!("partials/{business_unit_id}/");
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(&prefix)
+            .into_paginator()
+            .send();
+
+        let mut partials = Partials::default();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(Error::from)?;
+            for key in page.contents().iter().filter_map(|o| o.key()) {
+                let Some(name) = key
+                    .strip_prefix(&prefix)
+                    .and_then(|name| name.strip_suffix(".hbs"))
+                else {
+                    continue;
+                };
+                partials.insert(name, self.read_partial(key).await?);
+            }
+        }
+
+        store(&self.partials, business_unit_id.clone(), &partials);
+        Ok(partials)
+    }
+}
+
+impl AwsS3TemplateRepository {
+    async fn read_partial(&self, key: &str) -> Result<String, Error> {
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await?;
+        let bytes = response
+            .body
+            .collect()
+            .await
+            .map_err(|e| Error::PartialBody(key.to_string(), e))?
+            .into_bytes();
+
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| Error::InvalidPartial(key.to_string()))
     }
 }
 
@@ -0,0 +1,49 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone)]
+pub(crate) struct Cached<T> {
+    fetched_at: Instant,
+    value: T,
+}
+
+/// The value cached for the key, unless it was fetched `ttl` ago or more.
+pub(crate) fn cached<K, T>(
+    cache: &RwLock<HashMap<K, Cached<T>>>,
+    key: &K,
+    ttl: Duration,
+) -> Option<T>
+where
+    K: Eq + Hash,
+    T: Clone,
+{
+    cache
+        .read()
+        .ok()?
+        .get(key)
+        .filter(|cached| cached.fetched_at.elapsed() < ttl)
+        .map(|cached| cached.value.clone())
+}
+
+pub(crate) fn store<K, T>(
+    cache: &RwLock<HashMap<K, Cached<T>>>,
+    key: K,
+    value: &T,
+) where
+    K: Eq + Hash,
+    T: Clone,
+{
+    if let Ok(mut cache) = cache.write() {
+        cache.insert(
+            key,
+            Cached {
+                fetched_at: Instant::now(),
+                value: value.clone(),
+            },
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use super::{FoundTemplate, TemplateId, TemplateLookup, TemplateRepositoryError};
+use crate::{
+    partial::Partials,
+    template::{
+        EmailTemplate, NotRenderedTemplateState, PushTemplate, SmsTemplate,
+        Template,
+    },
+};
+use config::{Config, FileFormat};
+use perroute_commons::types::{dispatch_type::DispatchType, id::Id};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read template file {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+
+    #[error("Failed to parse template file {0}: {1}")]
+    Parse(PathBuf, String),
+}
+
+/// How a template is stored on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Yaml,
+    /// A directory holding one plain file per template part.
+    Parts,
+}
+
+#[derive(Clone)]
+struct CachedTemplate {
+    modified: Vec<SystemTime>,
+    template: Template<NotRenderedTemplateState>,
+}
+
+/// Reads templates from a directory laid out as
+/// `<template assignment id>/<dispatch type>/<template id>`, where the
+/// template is either a `.json`, `.yaml` or `.yml` file, or a directory with
+/// the parts as plain files:
+///
+/// - email: `subject.txt`, `body.html` and `body.txt`
+/// - sms: `body.txt`
+/// - push: `title.txt` and `body.txt`
+///
+/// Parsed templates are cached and reloaded whenever one of their files
+/// changes on disk.
+///
+/// Partials live in `partials/<business unit id>/<name>.hbs` and are read on
+/// every lookup.
+#[derive(Clone)]
+pub struct FsTemplateRepository {
+    root: PathBuf,
+    cache: Arc<RwLock<HashMap<PathBuf, CachedTemplate>>>,
+}
+
+impl FsTemplateRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            cache: Default::default(),
+        }
+    }
+
+    fn base_path(&self, id: &TemplateId) -> PathBuf {
+        self.root
+            .join(id.template_assignment_id().to_string())
+            .join(id.dispatch_type().to_string().to_lowercase())
+    }
+
+    /// First existing template for the id along with the files it is read
+    /// from.
+    async fn locate(
+        &self,
+        id: &TemplateId<'_>,
+    ) -> Result<Option<(Format, Vec<PathBuf>)>, Error> {
+        let base = self.base_path(id);
+        let template_id = id.template_id().to_string();
+
+        for (extension, format) in [
+            ("json", Format::Json),
+            ("yaml", Format::Yaml),
+            ("yml", Format::Yaml),
+        ] {
+            let path = base.join(format!("{template_id}.{extension}"));
+            if modified(&path).await?.is_some() {
+                return Ok(Some((format, vec![path])));
+            }
+        }
+
+        let dir = base.join(&template_id);
+        if modified(&dir).await?.is_none() {
+            return Ok(None);
+        }
+        let parts = match id.dispatch_type() {
+            DispatchType::Email => vec!["subject.txt", "body.html", "body.txt"],
+            DispatchType::Sms => vec!["body.txt"],
+            DispatchType::Push => vec!["title.txt", "body.txt"],
+        };
+        Ok(Some((
+            Format::Parts,
+            parts.into_iter().map(|part| dir.join(part)).collect(),
+        )))
+    }
+
+    fn cached(
+        &self,
+        key: &Path,
+        modified: &[SystemTime],
+    ) -> Option<Template<NotRenderedTemplateState>> {
+        self.cache
+            .read()
+            .ok()?
+            .get(key)
+            .filter(|cached| cached.modified == modified)
+            .map(|cached| cached.template.clone())
+    }
+
+    fn store(
+        &self,
+        key: PathBuf,
+        modified: Vec<SystemTime>,
+        template: &Template<NotRenderedTemplateState>,
+    ) {
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert(
+                key,
+                CachedTemplate {
+                    modified,
+                    template: template.clone(),
+                },
+            );
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TemplateLookup for FsTemplateRepository {
+    async fn get<'a>(
+        &self,
+        id: &'a TemplateId<'a>,
+    ) -> Result<Option<FoundTemplate>, TemplateRepositoryError> {
+        let Some((format, files)) = self.locate(id).await? else {
+            return Ok(None);
+        };
+
+        let mut stamps = Vec::with_capacity(files.len());
+        for file in &files {
+            match modified(file).await? {
+                Some(stamp) => stamps.push(stamp),
+                None => return Ok(None),
+            }
+        }
+
+        let key = files[0].clone();
+        if let Some(template) = self.cached(&key, &stamps) {
+            return Ok(Some(FoundTemplate::new(template)));
+        }
+
+        let template = load(id.dispatch_type(), format, &files).await?;
+        self.store(key, stamps, &template);
+
+        Ok(Some(FoundTemplate::new(template)))
+    }
+
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError> {
+        let dir = self
+            .root
+            .join("partials")
+            .join(business_unit_id.to_string());
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Ok(Partials::default())
+            }
+            Err(e) => return Err(Error::Io(dir, e).into()),
+        };
+
+        let mut partials = Partials::default();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| Error::Io(dir.clone(), e))?
+        {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "hbs") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                partials.insert(name, read(&path).await?);
+            }
+        }
+
+        Ok(partials)
+    }
+}
+
+async fn modified(path: &Path) -> Result<Option<SystemTime>, Error> {
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata
+            .modified()
+            .map(Some)
+            .map_err(|e| Error::Io(path.to_path_buf(), e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::Io(path.to_path_buf(), e)),
+    }
+}
+
+async fn read(path: &Path) -> Result<String, Error> {
+    tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| Error::Io(path.to_path_buf(), e))
+}
+
+async fn load(
+    dispatch_type: &DispatchType,
+    format: Format,
+    files: &[PathBuf],
+) -> Result<Template<NotRenderedTemplateState>, Error> {
+    if format == Format::Parts {
+        let mut parts = Vec::with_capacity(files.len());
+        for file in files {
+            parts.push(read(file).await?);
+        }
+        return Ok(match (dispatch_type, parts.as_slice()) {
+            (DispatchType::Email, [subject, html, text]) => {
+                Template::email(subject.trim_end(), html, text)
+            }
+            (DispatchType::Push, [title, body]) => {
+                Template::push(title.trim_end(), body)
+            }
+            _ => Template::sms(&parts[0]),
+        });
+    }
+
+    let path = &files[0];
+    let content = read(path).await?;
+    Ok(match dispatch_type {
+        DispatchType::Email => {
+            Template::Email(parse::<EmailTemplate<_>>(path, format, &content)?)
+        }
+        DispatchType::Sms => {
+            Template::Sms(parse::<SmsTemplate<_>>(path, format, &content)?)
+        }
+        DispatchType::Push => {
+            Template::Push(parse::<PushTemplate<_>>(path, format, &content)?)
+        }
+    })
+}
+
+fn parse<T: DeserializeOwned>(
+    path: &Path,
+    format: Format,
+    content: &str,
+) -> Result<T, Error> {
+    let error = |e: &dyn std::fmt::Display| {
+        Error::Parse(path.to_path_buf(), e.to_string())
+    };
+
+    match format {
+        Format::Yaml => Config::builder()
+            .add_source(config::File::from_str(content, FileFormat::Yaml))
+            .build()
+            .and_then(Config::try_deserialize)
+            .map_err(|e| error(&e)),
+        _ => serde_json::from_str(content).map_err(|e| error(&e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perroute_commons::types::id::Id;
+    use std::fs;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_json_yaml_and_part_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = FsTemplateRepository::new(dir.path());
+        let assignment = Id::from("assignment");
+        let template_id = Id::from("welcome");
+
+        write(
+            dir.path(),
+            "assignment/email/welcome.json",
+            r#"{"subject": "Hi", "html": "<p>Hi</p>", "text": "Hi"}"#,
+        );
+        write(
+            dir.path(),
+            "assignment/push/welcome.yaml",
+            "title: Hi\nbody: Hello\n",
+        );
+        write(
+            dir.path(),
+            "assignment/sms/welcome/body.txt",
+            "Hello {{name}}",
+        );
+
+        let email =
+            TemplateId::new(&assignment, &DispatchType::Email, &template_id);
+        assert_eq!(
+            repository.get(&email).await.unwrap(),
+            Some(FoundTemplate::new(Template::email("Hi", "<p>Hi</p>", "Hi")))
+        );
+
+        let push =
+            TemplateId::new(&assignment, &DispatchType::Push, &template_id);
+        assert_eq!(
+            repository.get(&push).await.unwrap(),
+            Some(FoundTemplate::new(Template::push("Hi", "Hello")))
+        );
+
+        let sms =
+            TemplateId::new(&assignment, &DispatchType::Sms, &template_id);
+        assert_eq!(
+            repository.get(&sms).await.unwrap(),
+            Some(FoundTemplate::new(Template::sms("Hello {{name}}")))
+        );
+
+        let missing = Id::from("missing");
+        let missing =
+            TemplateId::new(&assignment, &DispatchType::Sms, &missing);
+        assert_eq!(repository.get(&missing).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reads_business_unit_partials() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = FsTemplateRepository::new(dir.path());
+        let business_unit = Id::from("bu");
+
+        assert!(repository
+            .partials(&business_unit)
+            .await
+            .unwrap()
+            .is_empty());
+
+        write(dir.path(), "partials/bu/footer.hbs", "Bye");
+        write(dir.path(), "partials/bu/notes.txt", "ignored");
+
+        assert_eq!(
+            repository.partials(&business_unit).await.unwrap(),
+            Partials::from_iter([("footer", "Bye")])
+        );
+    }
+
+    #[tokio::test]
+    async fn reloads_changed_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = FsTemplateRepository::new(dir.path());
+        let assignment = Id::from("assignment");
+        let template_id = Id::from("welcome");
+        let id = TemplateId::new(&assignment, &DispatchType::Sms, &template_id);
+        let path = "assignment/sms/welcome.json";
+
+        write(dir.path(), path, r#"{"body": "first"}"#);
+        assert_eq!(
+            repository.get(&id).await.unwrap(),
+            Some(FoundTemplate::new(Template::sms("first")))
+        );
+
+        write(dir.path(), path, r#"{"body": "second"}"#);
+        let file = fs::File::options()
+            .write(true)
+            .open(dir.path().join(path))
+            .unwrap();
+        file.set_modified(
+            SystemTime::now() + std::time::Duration::from_secs(1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            repository.get(&id).await.unwrap(),
+            Some(FoundTemplate::new(Template::sms("second")))
+        );
+    }
+}
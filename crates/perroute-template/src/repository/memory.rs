@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use crate::template::{NotRenderedTemplateState, Template};
-use super::{TemplateId, TemplateLookup, TemplateRepositoryError};
+use super::{FoundTemplate, TemplateId, TemplateLookup, TemplateRepositoryError};
 
 pub struct InMemoryTemplateRepository<'a> {
     map: HashMap<TemplateId<'a>, Template<NotRenderedTemplateState>>,
@@ -11,10 +11,7 @@ impl<'a> TemplateLookup for InMemoryTemplateRepository<'a> {
     async fn get<'b>(
         &self,
         id: &'b TemplateId<'b>,
-    ) -> Result<
-        Option<Template<NotRenderedTemplateState>>,
-        TemplateRepositoryError,
-    > {
+    ) -> Result<Option<FoundTemplate>, TemplateRepositoryError> {
         todo!()
     }
 }
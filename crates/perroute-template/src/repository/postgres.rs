@@ -0,0 +1,217 @@
+use super::{
+    cache::{cached, store, Cached},
+    FoundTemplate, TemplateId, TemplateLookup, TemplateRepository,
+    TemplateRepositoryError,
+};
+use crate::{partial::Partials, version::TemplateVersion};
+use perroute_commons::types::{
+    actor::Actor, dispatch_type::DispatchType, id::Id, Timestamp,
+};
+use perroute_storage::{
+    active_record::{
+        datasource::{DataSource, NonTransactionalDataSource},
+        template_partial::{SaveTemplatePartial, TemplatePartialQuery},
+        template_version::TemplateVersionQuery,
+        ActiveRecord,
+    },
+    models::{
+        template_partial::TemplatePartial,
+        template_version::TemplateVersion as DbTemplateVersion,
+    },
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tap::TapFallible;
+
+/// Template store keeping every version of a template in Postgres. Lookups
+/// return the published version. Versions are written through the command
+/// bus so the changes are recorded as events.
+///
+/// Partials looked up for delivery can be cached for a TTL, see
+/// [`PgTemplateRepository::with_ttl`].
+#[derive(Clone)]
+pub struct PgTemplateRepository {
+    datasource: DataSource<NonTransactionalDataSource>,
+    ttl: Duration,
+    partials: Arc<RwLock<HashMap<Id, Cached<Partials>>>>,
+}
+
+impl PgTemplateRepository {
+    pub fn new(datasource: DataSource<NonTransactionalDataSource>) -> Self {
+        Self {
+            datasource,
+            ttl: Duration::ZERO,
+            partials: Default::default(),
+        }
+    }
+
+    /// Caches the partials of a business unit looked up for delivery for
+    /// `ttl`, instead of reading them for every message. Partials read
+    /// through [`TemplateRepository`] are never cached.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl TryFrom<DbTemplateVersion> for TemplateVersion {
+    type Error = TemplateRepositoryError;
+
+    fn try_from(value: DbTemplateVersion) -> Result<Self, Self::Error> {
+        Ok(TemplateVersion::builder()
+            .template_id(value.template_id())
+            .version(*value.version())
+            .status(*value.status())
+            .template(serde_json::from_value(value.content().0.clone())?)
+            .created_by(value.created_by().0.clone())
+            .created_at(value.created_at())
+            .maybe_published_by(value.published_by().clone().map(|a| a.0))
+            .maybe_published_at(value.published_at().clone())
+            .build())
+    }
+}
+
+#[async_trait::async_trait]
+impl TemplateLookup for PgTemplateRepository {
+    async fn get<'a>(
+        &self,
+        id: &'a TemplateId<'a>,
+    ) -> Result<Option<FoundTemplate>, TemplateRepositoryError> {
+        Ok(self
+            .published(id.template_id(), id.dispatch_type())
+            .await?
+            .map(|version| {
+                FoundTemplate::new(version.template().clone())
+                    .with_version(*version.version())
+            }))
+    }
+
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError> {
+        if let Some(partials) =
+            cached(&self.partials, business_unit_id, self.ttl)
+        {
+            return Ok(partials);
+        }
+
+        let partials =
+            TemplateRepository::partials(self, business_unit_id).await?;
+        store(&self.partials, business_unit_id.clone(), &partials);
+        Ok(partials)
+    }
+}
+
+#[async_trait::async_trait]
+impl TemplateRepository for PgTemplateRepository {
+    async fn versions(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> Result<Vec<TemplateVersion>, TemplateRepositoryError> {
+        DbTemplateVersion::query(
+            &self.datasource,
+            TemplateVersionQuery::ByTemplate(template_id, dispatch_type),
+        )
+        .await?
+        .into_iter()
+        .map(TemplateVersion::try_from)
+        .collect()
+    }
+
+    async fn version(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+        version: i32,
+    ) -> Result<Option<TemplateVersion>, TemplateRepositoryError> {
+        DbTemplateVersion::fetch_optional(
+            &self.datasource,
+            TemplateVersionQuery::ByVersion(
+                template_id,
+                dispatch_type,
+                version,
+            ),
+        )
+        .await?
+        .map(TemplateVersion::try_from)
+        .transpose()
+    }
+
+    async fn published(
+        &self,
+        template_id: &Id,
+        dispatch_type: &DispatchType,
+    ) -> Result<Option<TemplateVersion>, TemplateRepositoryError> {
+        DbTemplateVersion::fetch_optional(
+            &self.datasource,
+            TemplateVersionQuery::Published(template_id, dispatch_type),
+        )
+        .await?
+        .map(TemplateVersion::try_from)
+        .transpose()
+    }
+
+    async fn partials(
+        &self,
+        business_unit_id: &Id,
+    ) -> Result<Partials, TemplateRepositoryError> {
+        Ok(TemplatePartial::query(
+            &self.datasource,
+            TemplatePartialQuery::ByBusinessUnit(business_unit_id),
+        )
+        .await?
+        .into_iter()
+        .map(|partial| (partial.name().clone(), partial.content().clone()))
+        .collect())
+    }
+
+    async fn save_partial(
+        &self,
+        business_unit_id: &Id,
+        name: &str,
+        content: &str,
+        actor: &Actor,
+    ) -> Result<(), TemplateRepositoryError> {
+        if !Partials::is_valid_name(name) {
+            return Err(TemplateRepositoryError::InvalidPartialName(
+                name.to_string(),
+            ));
+        }
+
+        let save = SaveTemplatePartial::builder()
+            .business_unit_id(business_unit_id.clone())
+            .name(name)
+            .content(content)
+            .updated_by(actor.clone())
+            .timestamp(Timestamp::now())
+            .build();
+
+        TemplatePartial::create(&self.datasource, save)
+            .await
+            .tap_err(|e| log::error!("Failed to save partial {name}: {e}"))?;
+        Ok(())
+    }
+
+    async fn delete_partial(
+        &self,
+        business_unit_id: &Id,
+        name: &str,
+    ) -> Result<(), TemplateRepositoryError> {
+        TemplatePartial::fetch_optional(
+            &self.datasource,
+            TemplatePartialQuery::ByName(business_unit_id, name),
+        )
+        .await?
+        .ok_or_else(|| {
+            TemplateRepositoryError::PartialNotFound(name.to_string())
+        })?
+        .destroy(&self.datasource)
+        .await?;
+        Ok(())
+    }
+}
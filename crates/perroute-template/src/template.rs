@@ -1,6 +1,11 @@
 use std::marker::PhantomData;
+use perroute_commons::types::dispatch_type::DispatchType;
 use serde::{Deserialize, Serialize};
-use crate::render::{RenderError, Renderer};
+use serde_json::Value;
+use crate::{
+    email::EmailProcessing,
+    render::{Escape, RenderError, Renderer, TemplateEngine},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Template<S> {
@@ -9,7 +14,66 @@ pub enum Template<S> {
     Push(PushTemplate<S>),
 }
 
+impl<S> Template<S> {
+    pub fn dispatch_type(&self) -> DispatchType {
+        match self {
+            Template::Sms(_) => DispatchType::Sms,
+            Template::Email(_) => DispatchType::Email,
+            Template::Push(_) => DispatchType::Push,
+        }
+    }
+
+    pub fn engine(&self) -> TemplateEngine {
+        match self {
+            Template::Sms(t) => t.engine,
+            Template::Email(t) => t.engine,
+            Template::Push(t) => t.engine,
+        }
+    }
+
+    /// The source of each part of the template by part name, e.g. `subject`.
+    pub fn parts(&self) -> Vec<(&'static str, &str)> {
+        match self {
+            Template::Sms(t) => vec![("body", &t.body)],
+            Template::Email(t) => vec![
+                ("subject", &t.subject),
+                ("html", &t.html),
+                ("text", &t.text),
+            ],
+            Template::Push(t) => vec![("title", &t.title), ("body", &t.body)],
+        }
+    }
+
+    /// The parts of the template without the dispatch type tag, as read by
+    /// [`Template::from_value`].
+    pub fn to_value(&self) -> Value {
+        match self {
+            Template::Email(t) => serde_json::to_value(t),
+            Template::Sms(t) => serde_json::to_value(t),
+            Template::Push(t) => serde_json::to_value(t),
+        }
+        .unwrap_or_default()
+    }
+}
+
 impl Template<NotRenderedTemplateState> {
+    /// Reads the parts of a template of the given dispatch type, e.g.
+    /// `{"title": "..", "body": ".."}` for push.
+    pub fn from_value(
+        dispatch_type: &DispatchType,
+        value: Value,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(match dispatch_type {
+            DispatchType::Email => {
+                Template::Email(serde_json::from_value(value)?)
+            }
+            DispatchType::Sms => Template::Sms(serde_json::from_value(value)?),
+            DispatchType::Push => {
+                Template::Push(serde_json::from_value(value)?)
+            }
+        })
+    }
+
     pub fn email(
         subject: &str,
         html: &str,
@@ -19,6 +83,8 @@ impl Template<NotRenderedTemplateState> {
             subject: subject.to_string(),
             html: html.to_string(),
             text: text.to_string(),
+            processing: EmailProcessing::default(),
+            engine: TemplateEngine::default(),
             state: PhantomData::<NotRenderedTemplateState>,
         })
     }
@@ -27,6 +93,7 @@ impl Template<NotRenderedTemplateState> {
         Template::Push(PushTemplate {
             title: title.to_string(),
             body: body.to_string(),
+            engine: TemplateEngine::default(),
             state: PhantomData::<NotRenderedTemplateState>,
         })
     }
@@ -34,10 +101,22 @@ impl Template<NotRenderedTemplateState> {
     pub fn sms(body: &str) -> Template<NotRenderedTemplateState> {
         Template::Sms(SmsTemplate {
             body: body.to_string(),
+            engine: TemplateEngine::default(),
             state: PhantomData::<NotRenderedTemplateState>,
         })
     }
 
+    pub fn with_engine(mut self, engine: TemplateEngine) -> Self {
+        match &mut self {
+            Template::Sms(t) => t.engine = engine,
+            Template::Email(t) => t.engine = engine,
+            Template::Push(t) => t.engine = engine,
+        }
+        self
+    }
+
+    /// Renders the parts of the template. The renderer is expected to be
+    /// built for the template [engine](Template::engine).
     pub fn render(
         &self,
         renderer: &dyn Renderer,
@@ -66,7 +145,13 @@ pub struct RenderedTemplateState;
 pub struct EmailTemplate<S> {
     subject: String,
     html: String,
+    /// Derived from `html` when left empty, see [`EmailProcessing`].
+    #[serde(default)]
     text: String,
+    #[serde(default)]
+    processing: EmailProcessing,
+    #[serde(default)]
+    engine: TemplateEngine,
     #[serde(skip)]
     state: PhantomData<S>,
 }
@@ -77,15 +162,50 @@ impl<S> From<EmailTemplate<S>> for Template<S> {
     }
 }
 
+impl<S> EmailTemplate<S> {
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn processing(&self) -> &EmailProcessing {
+        &self.processing
+    }
+}
+
 impl EmailTemplate<NotRenderedTemplateState> {
     fn render(
         &self,
         renderer: &dyn Renderer,
     ) -> Result<EmailTemplate<RenderedTemplateState>, RenderError> {
+        let html = renderer.render_part("html", &self.html, Escape::Html)?;
+        let html = self
+            .processing
+            .html(html)
+            .map_err(|e| RenderError::from(e).in_part("html"))?;
+        let text = renderer.render_part("text", &self.text, Escape::Plain)?;
+        let text = self
+            .processing
+            .text(&html, text)
+            .map_err(|e| RenderError::from(e).in_part("text"))?;
+
         Ok(EmailTemplate {
-            subject: renderer.render(&self.subject)?,
-            html: renderer.render(&self.html)?,
-            text: renderer.render(&self.text)?,
+            subject: renderer.render_part(
+                "subject",
+                &self.subject,
+                Escape::Plain,
+            )?,
+            html,
+            text,
+            processing: self.processing,
+            engine: self.engine,
             state: PhantomData::<RenderedTemplateState>,
         })
     }
@@ -94,6 +214,8 @@ impl EmailTemplate<NotRenderedTemplateState> {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SmsTemplate<S> {
     body: String,
+    #[serde(default)]
+    engine: TemplateEngine,
     #[serde(skip)]
     state: PhantomData<S>,
 }
@@ -104,13 +226,29 @@ impl<S> From<SmsTemplate<S>> for Template<S> {
     }
 }
 
+impl<S> SmsTemplate<S> {
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+}
+
+impl SmsTemplate<RenderedTemplateState> {
+    /// Replaces the rendered body, e.g. after adapting it to the SMS
+    /// alphabet.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
 impl SmsTemplate<NotRenderedTemplateState> {
     fn render(
         &self,
         renderer: &dyn Renderer,
     ) -> Result<SmsTemplate<RenderedTemplateState>, RenderError> {
         Ok(SmsTemplate {
-            body: renderer.render(&self.body)?,
+            body: renderer.render_part("body", &self.body, Escape::Plain)?,
+            engine: self.engine,
             state: PhantomData::<RenderedTemplateState>,
         })
     }
@@ -120,6 +258,8 @@ impl SmsTemplate<NotRenderedTemplateState> {
 pub struct PushTemplate<S> {
     title: String,
     body: String,
+    #[serde(default)]
+    engine: TemplateEngine,
     #[serde(skip)]
     state: PhantomData<S>,
 }
@@ -130,14 +270,25 @@ impl<S> From<PushTemplate<S>> for Template<S> {
     }
 }
 
+impl<S> PushTemplate<S> {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+}
+
 impl PushTemplate<NotRenderedTemplateState> {
     fn render(
         &self,
         renderer: &dyn Renderer,
     ) -> Result<PushTemplate<RenderedTemplateState>, RenderError> {
         Ok(PushTemplate {
-            title: renderer.render(&self.title)?,
-            body: renderer.render(&self.body)?,
+            title: renderer.render_part("title", &self.title, Escape::Plain)?,
+            body: renderer.render_part("body", &self.body, Escape::Plain)?,
+            engine: self.engine,
             state: PhantomData::<RenderedTemplateState>,
         })
     }
@@ -158,7 +309,11 @@ mod tests {
     }
 
     impl<'ctx> Renderer for FakeRenderer<'ctx> {
-        fn render(&self, template: &str) -> Result<String, RenderError> {
+        fn render(
+            &self,
+            template: &str,
+            _escape: Escape,
+        ) -> Result<String, RenderError> {
             Ok(template.replace("a", "b").to_owned())
         }
     }
@@ -175,6 +330,31 @@ mod tests {
     #[test]
     fn serialize() {}
 
+    #[test]
+    fn from_value_reads_the_parts_of_the_dispatch_type() {
+        let template = Template::from_value(
+            &DispatchType::Push,
+            json!({"title": "Hi", "body": "Hello"}),
+        )
+        .unwrap();
+        assert_eq!(template, Template::push("Hi", "Hello"));
+        assert_eq!(template.dispatch_type(), DispatchType::Push);
+
+        let template = Template::from_value(
+            &DispatchType::Sms,
+            json!({"body": "{{ name }}", "engine": "minijinja"}),
+        )
+        .unwrap();
+        assert_eq!(template.engine(), TemplateEngine::MiniJinja);
+        assert_eq!(template.to_value()["engine"], json!("minijinja"));
+
+        assert!(Template::from_value(
+            &DispatchType::Email,
+            json!({"title": "Hi", "body": "Hello"}),
+        )
+        .is_err());
+    }
+
     #[test]
     fn teste() {
         // let ctx = TemplateRenderContext::new(json!({}), json!({}));
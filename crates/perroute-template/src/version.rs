@@ -0,0 +1,22 @@
+use crate::template::{NotRenderedTemplateState, Template};
+use bon::Builder;
+use derive_getters::Getters;
+use perroute_commons::types::{
+    actor::Actor, id::Id, TemplateVersionStatus, Timestamp,
+};
+
+/// A numbered, immutable revision of a template along with who created and
+/// who published it.
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Builder)]
+pub struct TemplateVersion {
+    #[builder(into)]
+    template_id: Id,
+    version: i32,
+    status: TemplateVersionStatus,
+    template: Template<NotRenderedTemplateState>,
+    created_by: Actor,
+    #[builder(into)]
+    created_at: Timestamp,
+    published_by: Option<Actor>,
+    published_at: Option<Timestamp>,
+}